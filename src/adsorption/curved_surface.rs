@@ -0,0 +1,257 @@
+use super::pore::{PoreProfile, PoreProfile1D, PoreSpecification};
+use super::FluidParameters;
+use crate::convolver::{Convolver, ConvolverFFT};
+use crate::functional::{HelmholtzEnergyFunctional, DFT};
+use crate::geometry::{Axis, AxisGeometry, Grid};
+use crate::profile::{DFTProfile, MAX_POTENTIAL};
+use feos_core::{Contributions, EosError, EosResult, EosUnit, State};
+use log::info;
+use ndarray::{Array1, Array2, Ix1};
+use ndarray_stats::QuantileExt;
+use quantity::{QuantityArray1, QuantityScalar};
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+const GRID_POINTS_PER_SIGMA: f64 = 20.0;
+const N_PHI_CYLINDER: usize = 256;
+const N_RADIAL_CYLINDER: usize = 64;
+
+/// Parameters required to specify a fluid around a convex solid.
+///
+/// The solid is a sphere (spherical geometry) or an infinitely long cylinder
+/// (polar geometry) with the radius `solid_radius`, which consists of
+/// Lennard-Jones 12-6 sites with the homogeneous density `rho_s`. The fluid
+/// occupies the space between the solid and the end of the domain at
+/// `domain_size`, which has to be large enough for the fluid to reach the
+/// bulk state. In contrast to a [Pore1D](super::Pore1D), the curvature of
+/// the surface is convex from the perspective of the fluid.
+pub struct CurvedSurface1D<U, F> {
+    functional: Rc<DFT<F>>,
+    geometry: AxisGeometry,
+    solid_radius: QuantityScalar<U>,
+    domain_size: QuantityScalar<U>,
+    sigma_ss: f64,
+    epsilon_k_ss: f64,
+    rho_s: f64,
+    n_grid: Option<usize>,
+    potential_cutoff: Option<f64>,
+}
+
+impl<U: EosUnit, F: HelmholtzEnergyFunctional> CurvedSurface1D<U, F> {
+    pub fn new(
+        functional: &Rc<DFT<F>>,
+        geometry: AxisGeometry,
+        solid_radius: QuantityScalar<U>,
+        domain_size: QuantityScalar<U>,
+        sigma_ss: f64,
+        epsilon_k_ss: f64,
+        rho_s: f64,
+        n_grid: Option<usize>,
+        potential_cutoff: Option<f64>,
+    ) -> Self {
+        Self {
+            functional: functional.clone(),
+            geometry,
+            solid_radius,
+            domain_size,
+            sigma_ss,
+            epsilon_k_ss,
+            rho_s,
+            n_grid,
+            potential_cutoff,
+        }
+    }
+
+    /// Return the surface area of the solid (per unit length for cylinders).
+    pub fn surface_area(&self) -> QuantityScalar<U> {
+        match self.geometry {
+            AxisGeometry::Spherical => 4.0 * PI * self.solid_radius * self.solid_radius,
+            _ => 2.0 * PI * self.solid_radius,
+        }
+    }
+
+    /// Return the volume of the solid (per unit length for cylinders).
+    pub fn solid_volume(&self) -> QuantityScalar<U> {
+        match self.geometry {
+            AxisGeometry::Spherical => 4.0 / 3.0 * PI * self.solid_radius.powi(3),
+            _ => PI * self.solid_radius * self.solid_radius,
+        }
+    }
+
+    /// Return the interfacial tension
+    /// $\gamma=\frac{\Omega+p\left(V-V^\mathrm{s}\right)}{A}$ of the fluid at
+    /// the surface of the solid, with the dividing surface at the radius of
+    /// the solid.
+    pub fn interfacial_tension(
+        &self,
+        profile: &PoreProfile1D<U, F>,
+    ) -> EosResult<QuantityScalar<U>> {
+        let omega = profile
+            .grand_potential
+            .ok_or_else(|| EosError::Error("The profile has to be solved first.".into()))?;
+        let p = profile.profile.bulk.pressure(Contributions::Total);
+        Ok(
+            (omega + p * (profile.profile.grid_volume() - self.solid_volume()))
+                / self.surface_area(),
+        )
+    }
+
+    /// Return the adsorption (surface excess)
+    /// $\Gamma_i=\frac{N_i-\rho_i^\mathrm{b}\left(V-V^\mathrm{s}\right)}{A}$
+    /// of every component, with the dividing surface at the radius of the
+    /// solid.
+    pub fn adsorption(&self, profile: &PoreProfile1D<U, F>) -> QuantityArray1<U> {
+        let volume = profile.profile.grid_volume() - self.solid_volume();
+        (profile.profile.moles() - &profile.profile.bulk.partial_density * volume)
+            / self.surface_area()
+    }
+}
+
+impl<U: EosUnit, F: HelmholtzEnergyFunctional + FluidParameters> CurvedSurface1D<U, F> {
+    fn axis(&self) -> EosResult<Axis> {
+        let solid_radius = self.solid_radius.to_reduced(U::reference_length())?;
+        let domain_size = self.domain_size.to_reduced(U::reference_length())?;
+        if domain_size <= solid_radius {
+            return Err(EosError::Error(format!(
+                "The domain size {} has to exceed the radius of the solid {}.",
+                self.domain_size, self.solid_radius
+            )));
+        }
+
+        // choose the number of grid points from the smallest segment diameter
+        let n_grid = match self.n_grid {
+            Some(n_grid) => n_grid,
+            None => {
+                let spacing =
+                    self.functional.functional.sigma_ff().min().unwrap() / GRID_POINTS_PER_SIGMA;
+                let n_grid = Grid::next_fast_len((domain_size / spacing).ceil() as usize);
+                info!("CurvedSurface1D: using {} grid points.", n_grid);
+                n_grid
+            }
+        };
+
+        match self.geometry {
+            AxisGeometry::Spherical => Axis::new_spherical(n_grid, self.domain_size),
+            AxisGeometry::Polar => Axis::new_polar(n_grid, self.domain_size),
+            AxisGeometry::Cartesian => Err(EosError::Error(
+                "Convex surfaces require a spherical or polar geometry.".into(),
+            )),
+        }
+    }
+
+    /// Calculate the (reduced) external potential of the solid on the grid.
+    fn external_potential(&self, axis: &Axis, temperature: f64) -> EosResult<Array2<f64>> {
+        let solid_radius = self.solid_radius.to_reduced(U::reference_length())?;
+        let fluid = &self.functional.functional;
+        let m = fluid.m();
+        let sigma_sf = (fluid.sigma_ff() + self.sigma_ss) * 0.5;
+        let epsilon_k_sf = (fluid.epsilon_k_ff() * self.epsilon_k_ss).mapv(f64::sqrt);
+        let potential_cutoff = self.potential_cutoff.unwrap_or(MAX_POTENTIAL);
+
+        Ok(Array2::from_shape_fn(
+            (m.len(), axis.grid.len()),
+            |(i, k)| {
+                let r = axis.grid[k];
+                let v = if r <= solid_radius {
+                    potential_cutoff
+                } else {
+                    let v = match self.geometry {
+                        AxisGeometry::Spherical => {
+                            solid_sphere(r, solid_radius, sigma_sf[i], epsilon_k_sf[i], self.rho_s)
+                        }
+                        _ => solid_cylinder(
+                            r,
+                            solid_radius,
+                            sigma_sf[i],
+                            epsilon_k_sf[i],
+                            self.rho_s,
+                        ),
+                    };
+                    m[i] * v / temperature
+                };
+                if v.is_nan() || v > potential_cutoff {
+                    potential_cutoff
+                } else {
+                    v
+                }
+            },
+        ))
+    }
+}
+
+impl<U: EosUnit, F: HelmholtzEnergyFunctional + FluidParameters> PoreSpecification<U, Ix1, F>
+    for CurvedSurface1D<U, F>
+{
+    fn initialize_with_convolver(
+        &self,
+        bulk: &State<U, DFT<F>>,
+        external_potential: Option<&Array2<f64>>,
+        convolver: Option<&Rc<dyn Convolver<f64, Ix1>>>,
+    ) -> EosResult<PoreProfile1D<U, F>> {
+        let dft = &bulk.eos;
+        let axis = self.axis()?;
+        let t = bulk.temperature.to_reduced(U::reference_temperature())?;
+
+        // calculate external potential
+        let external_potential = match external_potential {
+            Some(e) => e.clone(),
+            None => self.external_potential(&axis, t)?,
+        };
+
+        // initialize convolver
+        let grid = Grid::new_1d(axis);
+        let convolver = convolver.cloned().unwrap_or_else(|| {
+            let weight_functions = dft.functional.weight_functions(t);
+            ConvolverFFT::plan(&grid, &weight_functions, Some(1))
+        });
+
+        Ok(PoreProfile {
+            profile: DFTProfile::new(grid, convolver, bulk, Some(external_potential))?,
+            grand_potential: None,
+            interfacial_tension: None,
+        })
+    }
+}
+
+/// 12-6 Lennard-Jones potential of a homogeneous solid sphere with radius
+/// `radius` at the distance `r > radius` from its center.
+fn solid_sphere(r: f64, radius: f64, sigma: f64, epsilon: f64, rho_s: f64) -> f64 {
+    let sigma6 = sigma.powi(6);
+    let sigma12 = sigma6 * sigma6;
+
+    // antiderivatives of F(t) and t*F(t), with F the antiderivative of t*u(t)/(4 epsilon)
+    let g0 = |t: f64| sigma12 / (90.0 * t.powi(9)) - sigma6 / (12.0 * t.powi(3));
+    let g1 = |t: f64| sigma12 / (80.0 * t.powi(8)) - sigma6 / (8.0 * t.powi(2));
+    let h = |t: f64| g1(t) - r * g0(t);
+    8.0 * PI * epsilon * rho_s / r * (h(r + radius) - h(r - radius))
+}
+
+/// 12-6 Lennard-Jones potential of a homogeneous, infinitely long solid
+/// cylinder with radius `radius` at the distance `r > radius` from its axis.
+///
+/// The potential of the sites along lines parallel to the axis is integrated
+/// analytically and the cross section of the cylinder numerically.
+fn solid_cylinder(r: f64, radius: f64, sigma: f64, epsilon: f64, rho_s: f64) -> f64 {
+    let sigma6 = sigma.powi(6);
+    let sigma12 = sigma6 * sigma6;
+    let line = |d2: f64| {
+        4.0 * epsilon
+            * (sigma12 * 63.0 * PI / 256.0 / d2.powf(5.5) - sigma6 * 3.0 * PI / 8.0 / d2.powf(2.5))
+    };
+
+    // midpoint rule in the radial and trapezoidal (periodic) rule in the
+    // angular direction
+    let ds = radius / N_RADIAL_CYLINDER as f64;
+    let dphi = 2.0 * PI / N_PHI_CYLINDER as f64;
+    let cos_phi = Array1::from_shape_fn(N_PHI_CYLINDER, |j| (j as f64 * dphi).cos());
+    let mut potential = 0.0;
+    for k in 0..N_RADIAL_CYLINDER {
+        let s = (k as f64 + 0.5) * ds;
+        let ring: f64 = cos_phi
+            .iter()
+            .map(|c| line(r * r + s * s - 2.0 * r * s * c))
+            .sum();
+        potential += ring * dphi * s * ds;
+    }
+    rho_s * potential
+}
@@ -1,12 +1,14 @@
 use crate::adsorption::fea_potential::calculate_fea_potential;
 use crate::geometry::AxisGeometry;
-use feos_core::EosUnit;
+use feos_core::{EosError, EosResult, EosUnit};
 use libc::c_double;
 use ndarray::{Array1, Array2, Axis as Axis_nd};
 use quantity::{QuantityArray2, QuantityScalar};
 use std::f64::consts::PI;
 
 const DELTA_STEELE: f64 = 3.35;
+/// $\frac{e}{k_\mathrm{B}}$ in units of K/V.
+const ELEMENTARY_CHARGE_PER_BOLTZMANN: f64 = 11_604.518;
 
 /// A collection of external potentials.
 #[derive(Clone)]
@@ -40,6 +42,13 @@ pub enum ExternalPotential<U> {
         epsilon2_k_ss: f64,
         rho_s: f64,
     },
+    /// 12-6 Lennard-Jones potential of a spherical shell with inner radius $R$ (the pore size) and thickness $\Delta$, integrated analytically over the shell: $V_i^\mathrm{ext}(r)=\frac{2\pi m_i\rho_s}{r}\int_R^{R+\Delta}a\int_{a-r}^{a+r}s\,u_{si}(s)\mathrm{d}s\,\mathrm{d}a,~~~~u_{si}(s)=4\varepsilon_{si}\left(\left(\frac{\sigma_{si}}{s}\right)^{12}-\left(\frac{\sigma_{si}}{s}\right)^6\right),~~~~\varepsilon_{si}=\sqrt{\varepsilon_{ss}\varepsilon_{ii}},~~~~\sigma_{si}=\frac{1}{2}\left(\sigma_{ss}+\sigma_{ii}\right)$
+    SphericalShell {
+        sigma_ss: f64,
+        epsilon_k_ss: f64,
+        rho_s: f64,
+        thickness: f64,
+    },
     /// Free-energy averaged potential:
     FreeEnergyAveraged {
         coordinates: QuantityArray2<U>,
@@ -50,6 +59,27 @@ pub enum ExternalPotential<U> {
         n_grid: [usize; 2],
     },
 
+    /// Individual potentials for every segment, e.g., to use different solid parameters for
+    /// different components. A single potential repeated for every segment is equivalent to
+    /// using that potential directly.
+    PerComponent(Vec<ExternalPotential<U>>),
+
+    /// Sum of several external potentials: $V_i^\mathrm{ext}(z)=\sum_kV_{i,k}^\mathrm{ext}(z)$
+    ///
+    /// The potential cutoff of the pore is applied to the total potential.
+    Sum(Vec<ExternalPotential<U>>),
+
+    /// Linear electrostatic potential of a charged surface: $V_i^\mathrm{ext}(z)=z_ie\left(\psi_0-Ez\right)$
+    ///
+    /// The surface potential $\psi_0$ is given in V, the field strength $E$ in V/Å,
+    /// and the valences $z_i$ for every segment. The field is fixed and not solved
+    /// self-consistently with the charge distribution of the fluid.
+    Electrostatic {
+        surface_potential: f64,
+        field: f64,
+        charges: Array1<f64>,
+    },
+
     /// Custom potential
     Custom(Array2<f64>),
 }
@@ -59,6 +89,45 @@ pub trait FluidParameters {
     fn epsilon_k_ff(&self) -> Array1<f64>;
     fn sigma_ff(&self) -> &Array1<f64>;
     fn m(&self) -> Array1<f64>;
+
+    /// Return the (temperature dependent) hard-sphere diameter of the fluid segments.
+    ///
+    /// Overwrite this for functionals with a temperature dependent diameter, e.g.
+    /// the Barker-Henderson diameter of SAFT-type functionals. It is used for the
+    /// geometric correction of the pore size in curved geometries, while the
+    /// dispersive part of the potential still uses `sigma_ff`.
+    fn hs_diameter(&self, _temperature: f64) -> Array1<f64> {
+        self.sigma_ff().clone()
+    }
+}
+
+impl<U> ExternalPotential<U> {
+    /// Check that the potential is available in the given geometry and that
+    /// [ExternalPotential::PerComponent] provides a potential for each of the
+    /// `n_segments` segments.
+    pub(crate) fn check_geometry(
+        &self,
+        geometry: AxisGeometry,
+        n_segments: usize,
+    ) -> EosResult<()> {
+        match (self, geometry) {
+            (Self::PerComponent(potentials), _) if potentials.len() != n_segments => {
+                Err(EosError::Error(format!(
+                    "The PerComponent potential contains {} potentials, but the fluid has {} segments.",
+                    potentials.len(),
+                    n_segments
+                )))
+            }
+            (Self::PerComponent(potentials), _) | (Self::Sum(potentials), _) => potentials
+                .iter()
+                .try_for_each(|potential| potential.check_geometry(geometry, n_segments)),
+            (Self::SphericalShell { .. }, AxisGeometry::Cartesian)
+            | (Self::SphericalShell { .. }, AxisGeometry::Polar) => Err(EosError::Error(
+                "The SphericalShell potential is only available for spherical pores.".into(),
+            )),
+            _ => Ok(()),
+        }
+    }
 }
 
 impl<U: EosUnit> ExternalPotential<U> {
@@ -77,6 +146,25 @@ impl<U: EosUnit> ExternalPotential<U> {
         let m = fluid_parameters.m();
         let mut ext_pot = Array2::zeros((m.len(), z_grid.len()));
 
+        if let ExternalPotential::PerComponent(potentials) = self {
+            for (i, potential) in potentials.iter().enumerate() {
+                ext_pot.index_axis_mut(Axis_nd(0), i).assign(
+                    &potential
+                        .calculate_cartesian_potential(z_grid, fluid_parameters, temperature)
+                        .index_axis(Axis_nd(0), i),
+                );
+            }
+            return ext_pot;
+        }
+
+        if let ExternalPotential::Sum(potentials) = self {
+            for potential in potentials {
+                ext_pot +=
+                    &potential.calculate_cartesian_potential(z_grid, fluid_parameters, temperature);
+            }
+            return ext_pot;
+        }
+
         for (i, &mi) in m.iter().enumerate() {
             ext_pot.index_axis_mut(Axis_nd(0), i).assign(&match self {
                 Self::HardWall { sigma_ss } => {
@@ -161,6 +249,14 @@ impl<U: EosUnit> ExternalPotential<U> {
                             * (2.0 * (sigma_sf[i] / z_grid).mapv(|x| x.powi(9))
                                 - 15.0 * (sigma_sf[i] / z_grid).mapv(|x| x.powi(3))))
                 }
+                Self::SphericalShell {
+                    sigma_ss: _,
+                    epsilon_k_ss: _,
+                    rho_s: _,
+                    thickness: _,
+                } => {
+                    unimplemented!()
+                }
                 Self::FreeEnergyAveraged {
                     coordinates,
                     sigma_ss,
@@ -187,7 +283,14 @@ impl<U: EosUnit> ExternalPotential<U> {
                         AxisGeometry::Cartesian,
                     )
                 }
-                Self::Custom(_) => unreachable!(),
+                Self::Electrostatic {
+                    surface_potential,
+                    field,
+                    charges,
+                } => z_grid.mapv(|z| {
+                    charges[i] * ELEMENTARY_CHARGE_PER_BOLTZMANN * (surface_potential - field * z)
+                }),
+                Self::PerComponent(_) | Self::Sum(_) | Self::Custom(_) => unreachable!(),
             });
         }
         ext_pot
@@ -209,10 +312,38 @@ impl<U: EosUnit> ExternalPotential<U> {
         let m = fluid_parameters.m();
         let mut ext_pot = Array2::zeros((m.len(), r_grid.len()));
 
+        if let ExternalPotential::PerComponent(potentials) = self {
+            for (i, potential) in potentials.iter().enumerate() {
+                ext_pot.index_axis_mut(Axis_nd(0), i).assign(
+                    &potential
+                        .calculate_cylindrical_potential(
+                            r_grid,
+                            pore_size,
+                            fluid_parameters,
+                            temperature,
+                        )
+                        .index_axis(Axis_nd(0), i),
+                );
+            }
+            return ext_pot;
+        }
+
+        if let ExternalPotential::Sum(potentials) = self {
+            for potential in potentials {
+                ext_pot += &potential.calculate_cylindrical_potential(
+                    r_grid,
+                    pore_size,
+                    fluid_parameters,
+                    temperature,
+                );
+            }
+            return ext_pot;
+        }
+
         for (i, &mi) in m.iter().enumerate() {
             ext_pot.index_axis_mut(Axis_nd(0), i).assign(&match self {
                 Self::HardWall { sigma_ss } => {
-                    let sigma_sf = (fluid_parameters.sigma_ff()[i] + *sigma_ss) * 0.5;
+                    let sigma_sf = (fluid_parameters.hs_diameter(temperature)[i] + *sigma_ss) * 0.5;
                     r_grid.mapv(|r| {
                         if r > pore_size - sigma_sf {
                             f64::INFINITY
@@ -307,6 +438,14 @@ impl<U: EosUnit> ExternalPotential<U> {
                             * sigma_sf[i].powi(3)
                             * *rho_s)
                 }
+                Self::SphericalShell {
+                    sigma_ss: _,
+                    epsilon_k_ss: _,
+                    rho_s: _,
+                    thickness: _,
+                } => {
+                    unimplemented!()
+                }
                 Self::FreeEnergyAveraged {
                     coordinates,
                     sigma_ss,
@@ -333,7 +472,14 @@ impl<U: EosUnit> ExternalPotential<U> {
                         AxisGeometry::Polar,
                     )
                 }
-                Self::Custom(_) => unreachable!(),
+                Self::Electrostatic {
+                    surface_potential,
+                    field,
+                    charges,
+                } => (pore_size - r_grid).mapv(|z| {
+                    charges[i] * ELEMENTARY_CHARGE_PER_BOLTZMANN * (surface_potential - field * z)
+                }),
+                Self::PerComponent(_) | Self::Sum(_) | Self::Custom(_) => unreachable!(),
             });
         }
         ext_pot
@@ -355,10 +501,38 @@ impl<U: EosUnit> ExternalPotential<U> {
         let m = fluid_parameters.m();
         let mut ext_pot = Array2::zeros((m.len(), r_grid.len()));
 
+        if let ExternalPotential::PerComponent(potentials) = self {
+            for (i, potential) in potentials.iter().enumerate() {
+                ext_pot.index_axis_mut(Axis_nd(0), i).assign(
+                    &potential
+                        .calculate_spherical_potential(
+                            r_grid,
+                            pore_size,
+                            fluid_parameters,
+                            temperature,
+                        )
+                        .index_axis(Axis_nd(0), i),
+                );
+            }
+            return ext_pot;
+        }
+
+        if let ExternalPotential::Sum(potentials) = self {
+            for potential in potentials {
+                ext_pot += &potential.calculate_spherical_potential(
+                    r_grid,
+                    pore_size,
+                    fluid_parameters,
+                    temperature,
+                );
+            }
+            return ext_pot;
+        }
+
         for (i, &mi) in m.iter().enumerate() {
             ext_pot.index_axis_mut(Axis_nd(0), i).assign(&match self {
                 Self::HardWall { sigma_ss } => {
-                    let sigma_sf = (fluid_parameters.sigma_ff()[i] + *sigma_ss) * 0.5;
+                    let sigma_sf = (fluid_parameters.hs_diameter(temperature)[i] + *sigma_ss) * 0.5;
                     r_grid.mapv(|r| {
                         if r > pore_size - sigma_sf {
                             f64::INFINITY
@@ -468,6 +642,28 @@ impl<U: EosUnit> ExternalPotential<U> {
                             * (2.0 / 5.0 * sum_n(10, r_grid, sigma_sf[i], pore_size)
                                 - sum_n(4, r_grid, sigma_sf[i], pore_size)))
                 }
+                Self::SphericalShell {
+                    sigma_ss,
+                    epsilon_k_ss,
+                    rho_s,
+                    thickness,
+                } => {
+                    // combining rules
+                    let epsilon_k_sf =
+                        (fluid_parameters.epsilon_k_ff() * *epsilon_k_ss).mapv(|e| e.sqrt());
+                    let sigma_sf = (fluid_parameters.sigma_ff() + *sigma_ss) * 0.5;
+
+                    r_grid.mapv(|r| {
+                        mi * spherical_shell(
+                            r,
+                            pore_size,
+                            *thickness,
+                            sigma_sf[i],
+                            epsilon_k_sf[i],
+                            *rho_s,
+                        )
+                    })
+                }
                 Self::FreeEnergyAveraged {
                     coordinates,
                     sigma_ss,
@@ -494,13 +690,51 @@ impl<U: EosUnit> ExternalPotential<U> {
                         AxisGeometry::Spherical,
                     )
                 }
-                Self::Custom(_) => unreachable!(),
+                Self::Electrostatic {
+                    surface_potential,
+                    field,
+                    charges,
+                } => (pore_size - r_grid).mapv(|z| {
+                    charges[i] * ELEMENTARY_CHARGE_PER_BOLTZMANN * (surface_potential - field * z)
+                }),
+                Self::PerComponent(_) | Self::Sum(_) | Self::Custom(_) => unreachable!(),
             });
         }
         ext_pot
     }
 }
 
+/// 12-6 Lennard-Jones potential of a homogeneous spherical shell with inner radius `radius`
+/// and thickness `thickness` at the distance `r` from its center.
+fn spherical_shell(
+    r: f64,
+    radius: f64,
+    thickness: f64,
+    sigma: f64,
+    epsilon: f64,
+    rho_s: f64,
+) -> f64 {
+    if r >= radius {
+        return f64::INFINITY;
+    }
+    let sigma6 = sigma.powi(6);
+    let sigma12 = sigma6 * sigma6;
+    let outer = radius + thickness;
+
+    // limit r -> 0 (avoids the cancellation of the general expression at the center)
+    if r < 1e-5 * radius {
+        let h = |a: f64| -sigma12 / (9.0 * a.powi(9)) + sigma6 / (3.0 * a.powi(3));
+        return 16.0 * PI * epsilon * rho_s * (h(outer) - h(radius));
+    }
+
+    // antiderivatives of F(t) and t*F(t), with F the antiderivative of s*u(s)/(4 epsilon)
+    let g0 = |t: f64| sigma12 / (90.0 * t.powi(9)) - sigma6 / (12.0 * t.powi(3));
+    let g1 = |t: f64| sigma12 / (80.0 * t.powi(8)) - sigma6 / (8.0 * t.powi(2));
+    let plus = g1(outer + r) - r * g0(outer + r) - g1(radius + r) + r * g0(radius + r);
+    let minus = g1(outer - r) + r * g0(outer - r) - g1(radius - r) - r * g0(radius - r);
+    8.0 * PI * epsilon * rho_s / r * (plus - minus)
+}
+
 fn phi(n: i32, r_r: &Array1<f64>, sigma_r: f64) -> Array1<f64> {
     let m3n2 = 3.0 - 2.0 * n as f64;
     let n2m3 = 2.0 * n as f64 - 3.0;
@@ -1,21 +1,88 @@
 //! Adsorption profiles and isotherms.
+use super::convolver::Convolver;
 use super::functional::{HelmholtzEnergyFunctional, DFT};
+use super::geometry::{AxisGeometry, Grid};
+use super::profile::DFTSpecifications;
 use super::solver::DFTSolver;
 use feos_core::{
-    Contributions, EosError, EosResult, EosUnit, EquationOfState, StateBuilder, VLEOptions,
+    Contributions, EosError, EosResult, EosUnit, EquationOfState, PhaseEquilibrium, State,
+    StateBuilder, VLEOptions,
 };
-use ndarray::{arr1, Array1, Dimension, Ix1, Ix3};
-use quantity::{QuantityArray1, QuantityArray2, QuantityScalar};
+use log::warn;
+use ndarray::{arr1, Array, Array1, Array2, Dimension, Ix1, Ix2, Ix3};
+use quantity::{QuantityArray, QuantityArray1, QuantityArray2, QuantityScalar};
+use std::f64::consts::PI;
 use std::rc::Rc;
 
+mod curved_surface;
+mod elements;
 mod external_potential;
 mod fea_potential;
 mod pore;
+pub use curved_surface::CurvedSurface1D;
 pub use external_potential::{ExternalPotential, FluidParameters};
-pub use pore::{Pore1D, Pore3D, PoreProfile, PoreProfile1D, PoreProfile3D, PoreSpecification};
+pub use pore::{
+    kelvin_pressure, optimize_pore_size, ContactSummary, CutoffRadius,
+    InterfacialTensionConvention, Pore1D, Pore1DBuilder, Pore2D, Pore3D, PoreProfile,
+    PoreProfile1D, PoreProfile2D, PoreProfile3D, PoreSpecification, PotentialForm, SumRuleReport,
+};
 
 const MAX_ITER_ADSORPTION_EQUILIBRIUM: usize = 50;
 const TOL_ADSORPTION_EQUILIBRIUM: f64 = 1e-8;
+const MAX_POINTS_ADAPTIVE_ISOTHERM: usize = 1000;
+const MIN_RELATIVE_PRESSURE_STEP: f64 = 1e-6;
+const MAX_ITER_PRESSURE_FOR_LOADING: usize = 100;
+const TOL_PRESSURE_FOR_LOADING: f64 = 1e-8;
+const MAX_ITER_PORE_SIZE_DISTRIBUTION: usize = 100_000;
+const TOL_PORE_SIZE_DISTRIBUTION: f64 = 1e-10;
+const DELTA_PORE_SIZE_STRESS: f64 = 1e-3;
+const NEAR_CRITICAL_TEMPERATURE: f64 = 0.9;
+const STEPS_NEAR_CRITICAL_VLE: usize = 20;
+const MAX_ITER_ARC_LENGTH: usize = 20;
+const TOL_ARC_LENGTH: f64 = 1e-6;
+const MIN_ARC_LENGTH_STEP: f64 = 1e-3;
+const DELTA_ARC_LENGTH_SECANT: f64 = 1e-4;
+const MAX_RELATIVE_PRESSURE_FOR_LOADING: f64 = 0.9999;
+
+/// Calculate the saturation pressure of a pure bulk fluid.
+///
+/// Returns `None` at and above the critical temperature. Close to the critical
+/// point, where the direct VLE calculation can fail, the phase equilibrium is
+/// traced from a lower temperature.
+pub fn bulk_saturation_pressure<U: EosUnit, F: HelmholtzEnergyFunctional>(
+    functional: &Rc<DFT<F>>,
+    temperature: QuantityScalar<U>,
+) -> EosResult<Option<QuantityScalar<U>>> {
+    if functional.components() > 1 {
+        return Err(EosError::Error(
+            "Saturation pressures are only available for pure components.".into(),
+        ));
+    }
+    let critical_temperature =
+        State::critical_point(functional, None, None, VLEOptions::default())?.temperature;
+    if temperature >= critical_temperature {
+        return Ok(None);
+    }
+    let vle = PhaseEquilibrium::pure_t(functional, temperature, None, VLEOptions::default())
+        .or_else(|e| {
+            let t_start = NEAR_CRITICAL_TEMPERATURE * critical_temperature;
+            if temperature <= t_start {
+                return Err(e);
+            }
+            let mut vle =
+                PhaseEquilibrium::pure_t(functional, t_start, None, VLEOptions::default())?;
+            for i in 1..=STEPS_NEAR_CRITICAL_VLE {
+                let t =
+                    t_start + (temperature - t_start) * (i as f64 / STEPS_NEAR_CRITICAL_VLE as f64);
+                vle = PhaseEquilibrium::pure_t(functional, t, Some(&vle), VLEOptions::default())?;
+            }
+            Ok(vle)
+        })?;
+    if PhaseEquilibrium::is_trivial_solution(vle.vapor(), vle.liquid()) {
+        return Err(EosError::TrivialSolution);
+    }
+    Ok(Some(vle.vapor().pressure(Contributions::Total)))
+}
 
 /// Possible inputs for the pressure grid of adsorption isotherms.
 pub enum PressureSpecification<U> {
@@ -104,6 +171,8 @@ pub struct Adsorption<U, D: Dimension, F>(pub Vec<EosResult<PoreProfile<U, D, F>
 
 /// Container structure for adsorption isotherms in 1D pores.
 pub type Adsorption1D<U, F> = Adsorption<U, Ix1, F>;
+/// Container structure for adsorption isotherms in 2D pores.
+pub type Adsorption2D<U, F> = Adsorption<U, Ix2, F>;
 /// Container structure for adsorption isotherms in 3D pores.
 pub type Adsorption3D<U, F> = Adsorption<U, Ix3, F>;
 
@@ -125,6 +194,64 @@ where
         Self::isotherm(functional, temperature, &pressure, pore, molefracs, solver)
     }
 
+    /// Lazily calculate an adsorption isotherm (starting at low pressure).
+    ///
+    /// Every call to `next` solves a single pressure point and yields the
+    /// pressure and the total adsorption. Only the density of the last
+    /// converged profile is kept for the continuation, so the memory
+    /// requirement is independent of the number of points.
+    pub fn iter<'a, S: PoreSpecification<U, D, F>>(
+        functional: &Rc<DFT<F>>,
+        temperature: QuantityScalar<U>,
+        pressure: &PressureSpecification<U>,
+        pore: &'a S,
+        molefracs: Option<&Array1<f64>>,
+        solver: Option<&'a DFTSolver>,
+    ) -> EosResult<AdsorptionIter<'a, U, D, F, S>> {
+        let moles =
+            functional.validate_moles(molefracs.map(|x| x * U::reference_moles()).as_ref())?;
+        Ok(AdsorptionIter {
+            functional: functional.clone(),
+            temperature,
+            pressure: pressure.to_vec()?,
+            pore,
+            moles,
+            solver,
+            index: 0,
+            density: None,
+            external_potential: None,
+            convolver: None,
+        })
+    }
+
+    /// Calculate an adsorption isotherm (starting at low pressure) for
+    /// pressures given relative to the saturation pressure of the bulk fluid.
+    ///
+    /// Only available for pure components. Fails if the temperature is above
+    /// the critical temperature of the bulk fluid.
+    pub fn isotherm_relative<S: PoreSpecification<U, D, F>>(
+        functional: &Rc<DFT<F>>,
+        temperature: QuantityScalar<U>,
+        relative_pressure: &Array1<f64>,
+        pore: &S,
+        solver: Option<&DFTSolver>,
+    ) -> EosResult<RelativeAdsorption<U, D, F>> {
+        let saturation_pressure = bulk_saturation_pressure(functional, temperature)?
+            .ok_or_else(|| {
+                EosError::Error(format!(
+                    "The temperature {} is above the critical temperature. No saturation pressure exists.",
+                    temperature
+                ))
+            })?;
+        let pressure = relative_pressure * saturation_pressure;
+        let adsorption = Self::isotherm(functional, temperature, &pressure, pore, None, solver)?;
+        Ok(RelativeAdsorption {
+            adsorption,
+            saturation_pressure,
+            relative_pressure: relative_pressure.clone(),
+        })
+    }
+
     /// Calculate an desorption isotherm (starting at high pressure)
     pub fn desorption_isotherm<S: PoreSpecification<U, D, F>>(
         functional: &Rc<DFT<F>>,
@@ -144,6 +271,47 @@ where
         ))
     }
 
+    /// Calculate both branches of a hysteresis loop in a single continuation.
+    ///
+    /// The adsorption branch is calculated starting from the lowest pressure.
+    /// The desorption branch is then calculated starting from the converged
+    /// profile at the highest pressure of the adsorption branch, so that both
+    /// branches are connected even if the pore does not fill from the bulk
+    /// density at high pressures.
+    pub fn hysteresis<S: PoreSpecification<U, D, F>>(
+        functional: &Rc<DFT<F>>,
+        temperature: QuantityScalar<U>,
+        pressure: &PressureSpecification<U>,
+        pore: &S,
+        molefracs: Option<&Array1<f64>>,
+        solver: Option<&DFTSolver>,
+    ) -> EosResult<Hysteresis<U, D, F>> {
+        let pressure = pressure.to_vec()?;
+        let adsorption =
+            Self::isotherm(functional, temperature, &pressure, pore, molefracs, solver)?;
+        let density = adsorption
+            .0
+            .last()
+            .and_then(|p| p.as_ref().ok())
+            .map(|p| p.profile.density.clone());
+        let pressure =
+            QuantityArray1::from_shape_fn(pressure.len(), |i| pressure.get(pressure.len() - i - 1));
+        let desorption = Self::continuation(
+            functional,
+            temperature,
+            &pressure,
+            pore,
+            molefracs,
+            solver,
+            density,
+        )?;
+        let desorption = Adsorption(
+            desorption.0.into_iter().rev().collect(),
+            functional.components(),
+        );
+        Hysteresis::new(adsorption, desorption)
+    }
+
     /// Calculate an equilibrium isotherm
     pub fn equilibrium_isotherm<S: PoreSpecification<U, D, F>>(
         functional: &Rc<DFT<F>>,
@@ -208,21 +376,92 @@ where
                 molefracs,
                 solver,
             )?;
-            let omega_a = adsorption.grand_potential();
-            let omega_d = desorption.grand_potential();
-            let is_ads = Array1::from_shape_fn(adsorption.0.len(), |i| {
-                omega_d.get(i).is_nan() || omega_a.get(i) < omega_d.get(i)
-            });
-            let profiles = is_ads
-                .into_iter()
-                .zip(adsorption.0.into_iter())
-                .zip(desorption.0.into_iter())
-                .map(|((is_ads, a), d)| if is_ads { a } else { d })
-                .collect();
-            Ok(Adsorption(profiles, functional.components()))
+            Ok(Self::stable_branch(adsorption, desorption))
         }
     }
 
+    /// Combine an adsorption and a desorption isotherm evaluated at the same
+    /// pressures into the thermodynamically stable isotherm, i.e., select the
+    /// profile with the lower grand potential at every pressure.
+    pub fn stable_branch(adsorption: Self, desorption: Self) -> Self {
+        let omega_a = adsorption.grand_potential();
+        let omega_d = desorption.grand_potential();
+        let is_ads = Array1::from_shape_fn(adsorption.0.len(), |i| {
+            omega_d.get(i).is_nan() || omega_a.get(i) < omega_d.get(i)
+        });
+        let profiles = is_ads
+            .into_iter()
+            .zip(adsorption.0.into_iter())
+            .zip(desorption.0.into_iter())
+            .map(|((is_ads, a), d)| if is_ads { a } else { d })
+            .collect();
+        Adsorption(profiles, adsorption.1)
+    }
+
+    /// Calculate the equilibrium pressure of a first order transition in the
+    /// pore from the crossing of the grand potentials of the adsorption and the
+    /// desorption branch.
+    ///
+    /// The grand potential of the desorption branch is interpolated linearly in
+    /// ln(p) at the pressures of the adsorption branch. Returns `None` if the
+    /// grand potentials do not cross, e.g., for reversible pore filling.
+    pub fn equilibrium_transition(
+        adsorption: &Self,
+        desorption: &Self,
+    ) -> EosResult<Option<QuantityScalar<U>>> {
+        let omega_scale = match adsorption
+            .0
+            .iter()
+            .chain(desorption.0.iter())
+            .find_map(|p| p.as_ref().ok().and_then(|p| p.grand_potential))
+        {
+            Some(omega) => omega,
+            None => return Ok(None),
+        };
+        let branch = |isotherm: &Self| -> EosResult<Vec<(f64, f64)>> {
+            let p = isotherm.pressure().to_reduced(U::reference_pressure())?;
+            let omega = isotherm.grand_potential().to_reduced(omega_scale)?;
+            let mut points: Vec<_> = p
+                .iter()
+                .zip(omega.iter())
+                .filter(|(p, o)| p.is_finite() && o.is_finite())
+                .map(|(p, &o)| (p.ln(), o))
+                .collect();
+            points.sort_by(|x, y| x.0.partial_cmp(&y.0).unwrap());
+            Ok(points)
+        };
+        let ads = branch(adsorption)?;
+        let des = branch(desorption)?;
+
+        // difference of the grand potentials at the pressures of the adsorption branch
+        let delta: Vec<(f64, f64)> = ads
+            .iter()
+            .filter_map(|&(ln_p, omega_a)| {
+                des.windows(2)
+                    .find(|w| w[0].0 <= ln_p && ln_p <= w[1].0)
+                    .map(|w| {
+                        let (x1, y1) = w[0];
+                        let (x2, y2) = w[1];
+                        let omega_d = if x2 > x1 {
+                            y1 + (y2 - y1) * (ln_p - x1) / (x2 - x1)
+                        } else {
+                            y1
+                        };
+                        (ln_p, omega_a - omega_d)
+                    })
+            })
+            .collect();
+
+        Ok(delta
+            .windows(2)
+            .find(|w| w[0].1 * w[1].1 <= 0.0 && w[0].1 != w[1].1)
+            .map(|w| {
+                let (x1, y1) = w[0];
+                let (x2, y2) = w[1];
+                (x1 - y1 * (x2 - x1) / (y2 - y1)).exp() * U::reference_pressure()
+            }))
+    }
+
     fn isotherm<S: PoreSpecification<U, D, F>>(
         functional: &Rc<DFT<F>>,
         temperature: QuantityScalar<U>,
@@ -230,48 +469,499 @@ where
         pore: &S,
         molefracs: Option<&Array1<f64>>,
         solver: Option<&DFTSolver>,
+    ) -> EosResult<Adsorption<U, D, F>> {
+        Self::continuation(
+            functional,
+            temperature,
+            pressure,
+            pore,
+            molefracs,
+            solver,
+            None,
+        )
+    }
+
+    /// Solve the profiles for the given pressures in order, each initialized
+    /// with the converged density of the previous point. The first point is
+    /// initialized with `density`, if provided.
+    fn continuation<S: PoreSpecification<U, D, F>>(
+        functional: &Rc<DFT<F>>,
+        temperature: QuantityScalar<U>,
+        pressure: &QuantityArray1<U>,
+        pore: &S,
+        molefracs: Option<&Array1<f64>>,
+        solver: Option<&DFTSolver>,
+        mut density: Option<QuantityArray<U, D::Larger>>,
     ) -> EosResult<Adsorption<U, D, F>> {
         let moles =
             functional.validate_moles(molefracs.map(|x| x * U::reference_moles()).as_ref())?;
         let mut profiles: Vec<EosResult<PoreProfile<U, D, F>>> = Vec::with_capacity(pressure.len());
 
-        // Calculate the external potential once
-        let mut bulk = StateBuilder::new(functional)
+        // Calculate the external potential and the convolver once
+        let bulk = Self::bulk_state(functional, temperature, pressure.get(0), &moles)?;
+        let initial_profile = pore.initialize(&bulk, None)?.profile;
+        let external_potential = initial_profile.external_potential;
+
+        // The convolver only depends on the temperature and is reused for all pressures
+        let convolver = initial_profile.convolver;
+
+        for i in 0..pressure.len() {
+            let bulk = Self::bulk_state(functional, temperature, pressure.get(i), &moles)?;
+            let mut p =
+                pore.initialize_with_convolver(&bulk, Some(&external_potential), Some(&convolver))?;
+            let p2 = p.clone();
+            if let Some(Ok(l)) = profiles.last() {
+                p.profile.density = l.profile.density.clone();
+            } else if let Some(density) = density.take() {
+                p.profile.density = density;
+            }
+            profiles.push(p.solve(solver).or_else(|_| p2.solve(solver)));
+        }
+
+        Ok(Adsorption(profiles, functional.components()))
+    }
+
+    /// Bulk state at the given pressure. For unstable mixtures, the vapor
+    /// phase of a tp-flash is used instead.
+    fn bulk_state(
+        functional: &Rc<DFT<F>>,
+        temperature: QuantityScalar<U>,
+        pressure: QuantityScalar<U>,
+        moles: &QuantityArray1<U>,
+    ) -> EosResult<State<U, DFT<F>>> {
+        let bulk = StateBuilder::new(functional)
             .temperature(temperature)
-            .pressure(pressure.get(0))
-            .moles(&moles)
+            .pressure(pressure)
+            .moles(moles)
             .build()?;
         if functional.components() > 1 && !bulk.is_stable(VLEOptions::default())? {
-            bulk = bulk
+            Ok(bulk
                 .tp_flash(None, VLEOptions::default(), None)?
                 .vapor()
-                .clone();
+                .clone())
+        } else {
+            Ok(bulk)
         }
-        let external_potential = pore.initialize(&bulk, None)?.profile.external_potential;
+    }
 
-        for i in 0..pressure.len() {
-            let mut bulk = StateBuilder::new(functional)
-                .temperature(temperature)
-                .pressure(pressure.get(i))
-                .moles(&moles)
-                .build()?;
-            if functional.components() > 1 && !bulk.is_stable(VLEOptions::default())? {
-                bulk = bulk
-                    .tp_flash(None, VLEOptions::default(), None)?
-                    .vapor()
-                    .clone();
+    /// Calculate an adsorption isotherm with adaptive pressure steps.
+    ///
+    /// Starting from the given pressures, additional points are inserted
+    /// between consecutive points whose total adsorption differs by more
+    /// than `max_adsorption_step` (relative to the maximum total adsorption
+    /// of the isotherm). Intervals are refined recursively until the step
+    /// criterion is met or the pressure step falls below a relative
+    /// tolerance, e.g., at a capillary condensation. Every new point is
+    /// initialized with the converged profile at the next lower pressure.
+    /// The resulting pressure grid is available via [Adsorption::pressure].
+    pub fn adaptive_adsorption_isotherm<S: PoreSpecification<U, D, F>>(
+        functional: &Rc<DFT<F>>,
+        temperature: QuantityScalar<U>,
+        pressure: &PressureSpecification<U>,
+        pore: &S,
+        max_adsorption_step: f64,
+        molefracs: Option<&Array1<f64>>,
+        solver: Option<&DFTSolver>,
+    ) -> EosResult<Adsorption<U, D, F>> {
+        let pressure = pressure.to_vec()?;
+        let moles =
+            functional.validate_moles(molefracs.map(|x| x * U::reference_moles()).as_ref())?;
+        let mut profiles =
+            Self::isotherm(functional, temperature, &pressure, pore, molefracs, solver)?.0;
+        let mut pressure: Vec<_> = (0..pressure.len()).map(|i| pressure.get(i)).collect();
+
+        // The external potential and the convolver are the same for all points
+        let (external_potential, convolver) = match profiles.iter().find_map(|p| p.as_ref().ok()) {
+            Some(p) => (
+                p.profile.external_potential.clone(),
+                p.profile.convolver.clone(),
+            ),
+            None => return Ok(Adsorption(profiles, functional.components())),
+        };
+
+        let total_moles =
+            |p: &EosResult<PoreProfile<U, D, F>>| p.as_ref().ok().map(|p| p.profile.total_moles());
+        let mut n_max = profiles
+            .iter()
+            .filter_map(total_moles)
+            .fold(None, |acc: Option<QuantityScalar<U>>, n| match acc {
+                Some(acc) if acc >= n => Some(acc),
+                _ => Some(n),
+            })
+            .unwrap();
+
+        let mut i = 0;
+        while i + 1 < profiles.len() && profiles.len() < MAX_POINTS_ADAPTIVE_ISOTHERM {
+            let refine = match (total_moles(&profiles[i]), total_moles(&profiles[i + 1])) {
+                (Some(n1), Some(n2)) => {
+                    (n2 - n1).to_reduced(n_max)?.abs() > max_adsorption_step
+                        && (pressure[i + 1] - pressure[i])
+                            .to_reduced(pressure[i + 1])?
+                            .abs()
+                            > MIN_RELATIVE_PRESSURE_STEP
+                }
+                _ => false,
+            };
+            if !refine {
+                i += 1;
+                continue;
             }
-            let mut p = pore.initialize(&bulk, Some(&external_potential))?;
+
+            let p_new = 0.5 * (pressure[i] + pressure[i + 1]);
+            let bulk = Self::bulk_state(functional, temperature, p_new, &moles)?;
+            let mut p =
+                pore.initialize_with_convolver(&bulk, Some(&external_potential), Some(&convolver))?;
             let p2 = p.clone();
-            if let Some(Ok(l)) = profiles.last() {
+            if let Ok(l) = &profiles[i] {
                 p.profile.density = l.profile.density.clone();
             }
-            profiles.push(p.solve(solver).or_else(|_| p2.solve(solver)));
+            let profile = p.solve(solver).or_else(|_| p2.solve(solver));
+            if let Some(n) = total_moles(&profile) {
+                if n > n_max {
+                    n_max = n;
+                }
+            }
+            profiles.insert(i + 1, profile);
+            pressure.insert(i + 1, p_new);
         }
 
         Ok(Adsorption(profiles, functional.components()))
     }
 
+    /// Solve a single point of an isotherm, optionally starting from the
+    /// density of a previously converged profile.
+    fn solve_point<S: PoreSpecification<U, D, F>>(
+        functional: &Rc<DFT<F>>,
+        temperature: QuantityScalar<U>,
+        pressure: QuantityScalar<U>,
+        moles: &QuantityArray1<U>,
+        pore: &S,
+        initial: Option<&PoreProfile<U, D, F>>,
+        solver: Option<&DFTSolver>,
+    ) -> EosResult<PoreProfile<U, D, F>> {
+        let bulk = Self::bulk_state(functional, temperature, pressure, moles)?;
+        let mut p = pore.initialize_with_convolver(
+            &bulk,
+            initial.map(|i| &i.profile.external_potential),
+            initial.map(|i| &i.profile.convolver),
+        )?;
+        let p2 = p.clone();
+        if let Some(initial) = initial {
+            p.profile.density = initial.profile.density.clone();
+        }
+        p.solve(solver).or_else(|_| p2.solve(solver))
+    }
+
+    /// Calculate the bulk pressure at which the total adsorption in the pore
+    /// reaches the given value.
+    ///
+    /// The pressure is bracketed by `p_min` and `p_max` and determined by a
+    /// bisection in ln(p). Every profile is initialized with the solution at
+    /// the lower bound of the bracket, so that the adsorption branch of the
+    /// isotherm is followed. If the adsorption is not reachable within the
+    /// bracket, the error message contains the closest achievable value.
+    ///
+    /// For pure components, `p_max` is limited to just below the saturation
+    /// pressure of the bulk fluid, so that loadings that exceed the capacity
+    /// of the pore are reported together with the maximum attainable loading.
+    pub fn pressure_for_loading<S: PoreSpecification<U, D, F>>(
+        functional: &Rc<DFT<F>>,
+        temperature: QuantityScalar<U>,
+        loading: QuantityScalar<U>,
+        p_min: QuantityScalar<U>,
+        p_max: QuantityScalar<U>,
+        pore: &S,
+        molefracs: Option<&Array1<f64>>,
+        solver: Option<&DFTSolver>,
+    ) -> EosResult<QuantityScalar<U>> {
+        let moles =
+            functional.validate_moles(molefracs.map(|x| x * U::reference_moles()).as_ref())?;
+        let solve = |pressure: QuantityScalar<U>, initial: Option<&PoreProfile<U, D, F>>| {
+            Self::solve_point(
+                functional,
+                temperature,
+                pressure,
+                &moles,
+                pore,
+                initial,
+                solver,
+            )
+        };
+
+        // limit the bracket to pressures below the saturation pressure
+        let p_sat = if functional.components() == 1 {
+            bulk_saturation_pressure(functional, temperature)?
+                .map(|p_sat| p_sat * MAX_RELATIVE_PRESSURE_FOR_LOADING)
+                .filter(|&p_sat| p_sat < p_max && p_sat > p_min)
+        } else {
+            None
+        };
+        let p_max = p_sat.unwrap_or(p_max);
+
+        // check that the adsorption is reachable within the bracket
+        let mut lower = solve(p_min, None)?;
+        let upper = solve(p_max, Some(&lower))?;
+        let n_min = lower.profile.total_moles();
+        let n_max = upper.profile.total_moles();
+        if let (Some(p_sat), true) = (p_sat, loading > n_max) {
+            return Err(EosError::Error(format!(
+                "The adsorption {} exceeds the maximum adsorption {} that is attainable below the saturation pressure ({}).",
+                loading, n_max, p_sat
+            )));
+        }
+        if loading < n_min || loading > n_max {
+            let (n, p) = if loading < n_min {
+                (n_min, p_min)
+            } else {
+                (n_max, p_max)
+            };
+            return Err(EosError::Error(format!(
+                "The adsorption {} is not reachable between {} and {}. The closest achievable adsorption is {} at {}.",
+                loading, p_min, p_max, n, p
+            )));
+        }
+
+        // bisection in ln(p)
+        let (mut p_lower, mut p_upper) = (p_min, p_max);
+        for _ in 0..MAX_ITER_PRESSURE_FOR_LOADING {
+            let p = p_lower.to_reduced(p_upper)?.sqrt() * p_upper;
+            let profile = solve(p, Some(&lower))?;
+            let n = profile.profile.total_moles();
+            if (n - loading).to_reduced(loading)?.abs() < TOL_PRESSURE_FOR_LOADING
+                || (p_upper - p_lower).to_reduced(p_upper)? < TOL_PRESSURE_FOR_LOADING
+            {
+                return Ok(p);
+            }
+            if n < loading {
+                lower = profile;
+                p_lower = p;
+            } else {
+                p_upper = p;
+            }
+        }
+        Err(EosError::NotConverged(
+            "Adsorption::pressure_for_loading".into(),
+        ))
+    }
+
+    /// Trace the complete, S-shaped isotherm of a first-order transition in
+    /// the pore by pseudo-arclength continuation.
+    ///
+    /// The path is parameterized by its arc length in the plane of the scaled
+    /// pressure $\ln(p/p_\mathrm{min})/\ln(p_\mathrm{max}/p_\mathrm{min})$ and
+    /// the loading relative to the loading at `p_max`. Every step consists of
+    /// a predictor along the tangent of the path and a corrector, in which the
+    /// loading is fixed in a canonical calculation and adjusted by a secant
+    /// method until the arc-length condition is fulfilled. The step size
+    /// `arc_length_step` is reduced if the corrector fails. Because the
+    /// loading increases monotonically along the path, the continuation turns
+    /// at both spinodals and follows the unstable branch in between.
+    pub fn arc_length_continuation<S: PoreSpecification<U, D, F>>(
+        functional: &Rc<DFT<F>>,
+        temperature: QuantityScalar<U>,
+        p_min: QuantityScalar<U>,
+        p_max: QuantityScalar<U>,
+        pore: &S,
+        arc_length_step: f64,
+        molefracs: Option<&Array1<f64>>,
+        solver: Option<&DFTSolver>,
+    ) -> EosResult<VanDerWaalsLoop<U, D, F>> {
+        let moles =
+            functional.validate_moles(molefracs.map(|x| x * U::reference_moles()).as_ref())?;
+        let ln_p_range = p_max.to_reduced(p_min)?.ln();
+
+        // total number of segments and segment chemical potentials of the
+        // canonical specification
+        let specification = |p: &PoreProfile<U, D, F>| -> EosResult<(f64, Array1<f64>)> {
+            match &*DFTSpecifications::total_moles_from_profile(&p.profile)? {
+                DFTSpecifications::TotalMoles {
+                    total_moles,
+                    chemical_potential,
+                } => Ok((*total_moles, chemical_potential.clone())),
+                _ => unreachable!(),
+            }
+        };
+
+        // end points of the adsorption branch determine the scale of the loading
+        let first = Self::solve_point(functional, temperature, p_min, &moles, pore, None, solver)?;
+        let last = Self::solve_point(
+            functional,
+            temperature,
+            p_max,
+            &moles,
+            pore,
+            Some(&first),
+            solver,
+        )?;
+        let (n_min, chemical_potential) = specification(&first)?;
+        let n_scale = specification(&last)?.0;
+        if n_scale <= n_min {
+            return Err(EosError::Error(
+                "The loading does not increase between the minimum and the maximum pressure."
+                    .into(),
+            ));
+        }
+
+        let coordinates = |p: &PoreProfile<U, D, F>| -> EosResult<[f64; 2]> {
+            let pressure = p.profile.bulk.pressure(Contributions::Total);
+            Ok([
+                pressure.to_reduced(p_min)?.ln() / ln_p_range,
+                specification(p)?.0 / n_scale,
+            ])
+        };
+        let solve_canonical = |initial: &PoreProfile<U, D, F>, loading: f64| {
+            let mut p = initial.clone();
+            p.profile.specification = Rc::new(DFTSpecifications::TotalMoles {
+                total_moles: loading * n_scale,
+                chemical_potential: chemical_potential.clone(),
+            });
+            p.solve(solver)
+        };
+
+        let mut path = vec![(coordinates(&first)?, first)];
+        let mut tangent = [1.0, 0.0];
+        let mut step = arc_length_step;
+        while path.len() < MAX_POINTS_ADAPTIVE_ISOTHERM {
+            let x0 = path.last().unwrap().0;
+            let p0 = &path.last().unwrap().1;
+            if path.len() > 1 && !(0.0..=1.0).contains(&x0[0]) {
+                break;
+            }
+
+            // predictor and secant corrector for the arc-length condition
+            let condition = |p: &PoreProfile<U, D, F>| -> EosResult<(f64, [f64; 2])> {
+                let x = coordinates(p)?;
+                let g = (x[0] - x0[0]) * tangent[0] + (x[1] - x0[1]) * tangent[1] - step;
+                Ok((g, x))
+            };
+            let corrector = || -> EosResult<([f64; 2], PoreProfile<U, D, F>)> {
+                let mut v_a = x0[1] + step * tangent[1];
+                let mut p_a = solve_canonical(p0, v_a)?;
+                let (mut g_a, mut x_a) = condition(&p_a)?;
+                let mut v_b = v_a + DELTA_ARC_LENGTH_SECANT;
+                let mut g_b = condition(&solve_canonical(&p_a, v_b)?)?.0;
+                for _ in 0..MAX_ITER_ARC_LENGTH {
+                    if g_a.abs() < TOL_ARC_LENGTH * step {
+                        return Ok((x_a, p_a));
+                    }
+                    let v = v_a - g_a * (v_a - v_b) / (g_a - g_b);
+                    let p = solve_canonical(&p_a, v)?;
+                    let (g, x) = condition(&p)?;
+                    v_b = v_a;
+                    g_b = g_a;
+                    v_a = v;
+                    g_a = g;
+                    x_a = x;
+                    p_a = p;
+                }
+                Err(EosError::NotConverged(
+                    "Adsorption::arc_length_continuation".into(),
+                ))
+            };
+            match corrector() {
+                Ok((x, p)) => {
+                    let dx = [x[0] - x0[0], x[1] - x0[1]];
+                    let norm = dx[0].hypot(dx[1]);
+                    tangent = [dx[0] / norm, dx[1] / norm];
+                    path.push((x, p));
+                    step = (1.5 * step).min(arc_length_step);
+                }
+                Err(e) => {
+                    step *= 0.5;
+                    if step < MIN_ARC_LENGTH_STEP * arc_length_step {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+
+        // split the path at the turning points of the pressure
+        let mut branches = [Vec::new(), Vec::new(), Vec::new()];
+        let mut branch = 0;
+        let mut u_last = f64::NEG_INFINITY;
+        for ([u, _], p) in path {
+            if (branch == 0 && u < u_last) || (branch == 1 && u > u_last) {
+                branch += 1;
+            }
+            u_last = u;
+            branches[branch].push(Ok(p));
+        }
+        let [adsorption, unstable, desorption] =
+            branches.map(|profiles| Adsorption(profiles, functional.components()));
+
+        // the last point of a branch is a turning point if the path continues
+        let turning_point = |branch: &Self, next: &Self| {
+            branch
+                .0
+                .last()
+                .filter(|_| !next.0.is_empty())
+                .and_then(|p| p.as_ref().ok())
+                .map(|p| p.profile.bulk.pressure(Contributions::Total))
+        };
+        let condensation_spinodal = turning_point(&adsorption, &unstable);
+        let evaporation_spinodal = turning_point(&unstable, &desorption);
+        let equilibrium_pressure = if adsorption.0.len() > 1 && desorption.0.len() > 1 {
+            Self::equilibrium_transition(&adsorption, &desorption)?
+        } else {
+            None
+        };
+        Ok(VanDerWaalsLoop {
+            adsorption,
+            unstable,
+            desorption,
+            condensation_spinodal,
+            evaporation_spinodal,
+            equilibrium_pressure,
+        })
+    }
+
+    /// Simulate a temperature-programmed desorption from a closed pore.
+    ///
+    /// The pore is equilibrated with the bulk fluid at the first temperature
+    /// and the given pressure. Afterwards, the number of moles in the pore is
+    /// fixed and the profile is solved for every temperature, starting from
+    /// the previous solution. The result is the pressure of the bulk fluid
+    /// that is in equilibrium with the pore at every temperature.
+    pub fn temperature_programmed_desorption<S: PoreSpecification<U, D, F>>(
+        functional: &Rc<DFT<F>>,
+        temperatures: &QuantityArray1<U>,
+        pressure: QuantityScalar<U>,
+        pore: &S,
+        molefracs: Option<&Array1<f64>>,
+        solver: Option<&DFTSolver>,
+    ) -> EosResult<QuantityArray1<U>> {
+        let moles =
+            functional.validate_moles(molefracs.map(|x| x * U::reference_moles()).as_ref())?;
+        let mut profile = Self::solve_point(
+            functional,
+            temperatures.get(0),
+            pressure,
+            &moles,
+            pore,
+            None,
+            solver,
+        )?;
+        let specification = DFTSpecifications::moles_from_profile(&profile.profile)?;
+
+        let mut pressures = vec![profile.profile.bulk.pressure(Contributions::Total)];
+        for i in 1..temperatures.len() {
+            let bulk = &profile.profile.bulk;
+            let bulk = StateBuilder::new(functional)
+                .temperature(temperatures.get(i))
+                .pressure(bulk.pressure(Contributions::Total))
+                .moles(&(&bulk.molefracs * U::reference_moles()))
+                .build()?;
+            let mut p = pore.initialize(&bulk, None)?;
+            p.profile.density = profile.profile.density.clone();
+            p.profile.specification = specification.clone();
+            profile = p.solve(solver)?;
+            pressures.push(profile.profile.bulk.pressure(Contributions::Total));
+        }
+        Ok(QuantityArray1::from_shape_fn(pressures.len(), |i| {
+            pressures[i]
+        }))
+    }
+
     /// Calculate the phase transition from an empty to a filled pore.
     pub fn phase_equilibrium<S: PoreSpecification<U, D, F>>(
         functional: &Rc<DFT<F>>,
@@ -372,6 +1062,178 @@ where
         ))
     }
 
+    /// Calculate the isosteric heat of adsorption at the given total adsorption
+    /// from isotherms at different temperatures.
+    ///
+    /// The pressure at which every isotherm reaches the given adsorption is
+    /// interpolated linearly in ln(p). The isosteric heat follows from the
+    /// Clausius-Clapeyron equation as q_st = -R d ln(p)/d(1/T), with the slope
+    /// obtained from a linear least squares fit. Isotherms that do not contain
+    /// the given adsorption are skipped with a warning.
+    pub fn isosteric_heat_clausius(
+        isotherms: &[Adsorption<U, D, F>],
+        loading: QuantityScalar<U>,
+    ) -> EosResult<QuantityScalar<U>> {
+        let mut x = Vec::with_capacity(isotherms.len());
+        let mut y = Vec::with_capacity(isotherms.len());
+        for isotherm in isotherms {
+            let temperature = match isotherm.0.iter().find_map(|p| p.as_ref().ok()) {
+                Some(p) => p.profile.temperature,
+                None => continue,
+            };
+            let adsorption = isotherm.total_adsorption().to_reduced(loading)?;
+            let pressure = isotherm.pressure().to_reduced(U::reference_pressure())?;
+            let ln_p = (0..adsorption.len().saturating_sub(1)).find_map(|i| {
+                let (n1, n2) = (adsorption[i], adsorption[i + 1]);
+                if (n1 - 1.0) * (n2 - 1.0) <= 0.0 && n1 != n2 {
+                    let (ln_p1, ln_p2) = (pressure[i].ln(), pressure[i + 1].ln());
+                    Some(ln_p1 + (ln_p2 - ln_p1) * (1.0 - n1) / (n2 - n1))
+                } else {
+                    None
+                }
+            });
+            match ln_p {
+                Some(ln_p) => {
+                    x.push(temperature.to_reduced(U::reference_temperature())?.recip());
+                    y.push(ln_p);
+                }
+                None => warn!(
+                    "The adsorption {} is outside the range of the isotherm at {}.",
+                    loading, temperature
+                ),
+            }
+        }
+        if x.len() < 2 {
+            return Err(EosError::Error(
+                "At least two isotherms containing the given adsorption are required.".into(),
+            ));
+        }
+
+        // linear least squares fit of ln(p) over 1/T
+        let (x, y) = (Array1::from_vec(x), Array1::from_vec(y));
+        let dx = &x - x.mean().unwrap();
+        let dy = &y - y.mean().unwrap();
+        let slope = (&dx * &dy).sum() / (&dx * &dx).sum();
+        Ok(-slope * U::gas_constant() * U::reference_temperature())
+    }
+
+    /// Calculate the pore size distribution from an experimental isotherm and
+    /// a kernel of isotherms of single pores.
+    ///
+    /// The kernel isotherms are interpolated linearly in ln(p) at the given
+    /// pressures. The weights $w_k$ of the kernel isotherms follow from the
+    /// non-negative least squares problem
+    /// $$\min_{w\geq0}\left\lVert Aw-n^\mathrm{exp}\right\rVert^2+\lambda\left\lVert Lw\right\rVert^2$$
+    /// in which $L$ is the second order difference operator that smoothes
+    /// the distribution over neighboring kernel isotherms. The kernel has to be
+    /// sorted by pore size. The returned distribution is normalized to unity.
+    pub fn pore_size_distribution(
+        kernel: &[Adsorption<U, D, F>],
+        experimental: &QuantityArray1<U>,
+        pressure: &QuantityArray1<U>,
+        regularization: f64,
+    ) -> EosResult<Array1<f64>> {
+        let n_p = pressure.len();
+        let n_k = kernel.len();
+        if experimental.len() != n_p {
+            return Err(EosError::Error(format!(
+                "Experimental isotherm has {} points but {} pressures were given.",
+                experimental.len(),
+                n_p
+            )));
+        }
+        if n_k == 0 || n_p == 0 {
+            return Err(EosError::Error(
+                "The kernel and the experimental isotherm must not be empty.".into(),
+            ));
+        }
+
+        // the overall scaling of kernel and experimental isotherm drops out
+        // after the normalization of the distribution
+        let kernel_scale = kernel
+            .iter()
+            .flat_map(|k| k.0.iter().rev())
+            .find_map(|p| p.as_ref().ok())
+            .map(|p| p.profile.total_moles())
+            .ok_or_else(|| EosError::Error("The kernel contains no converged profiles.".into()))?;
+        let b = experimental.to_reduced(experimental.get(n_p - 1))?;
+        let ln_p = pressure.to_reduced(U::reference_pressure())?.mapv(f64::ln);
+
+        // interpolate the kernel isotherms at the experimental pressures
+        let mut a = Array2::<f64>::zeros((n_p, n_k));
+        for (k, isotherm) in kernel.iter().enumerate() {
+            let n = isotherm.total_adsorption().to_reduced(kernel_scale)?;
+            let p = isotherm.pressure().to_reduced(U::reference_pressure())?;
+            let mut points: Vec<_> = p
+                .iter()
+                .zip(n.iter())
+                .filter(|(p, n)| p.is_finite() && n.is_finite())
+                .map(|(p, &n)| (p.ln(), n))
+                .collect();
+            points.sort_by(|x, y| x.0.partial_cmp(&y.0).unwrap());
+            for (i, &ln_p) in ln_p.iter().enumerate() {
+                a[(i, k)] = points
+                    .windows(2)
+                    .find(|w| w[0].0 <= ln_p && ln_p <= w[1].0)
+                    .map(|w| {
+                        let (x1, y1) = w[0];
+                        let (x2, y2) = w[1];
+                        if x2 > x1 {
+                            y1 + (y2 - y1) * (ln_p - x1) / (x2 - x1)
+                        } else {
+                            y1
+                        }
+                    })
+                    .ok_or_else(|| {
+                        EosError::Error(format!(
+                            "The pressure {} is outside the range of kernel isotherm {}.",
+                            pressure.get(i),
+                            k
+                        ))
+                    })?;
+            }
+        }
+
+        // normal equations including the smoothing term
+        let mut hessian = a.t().dot(&a);
+        let gradient = a.t().dot(&b);
+        for k in 1..n_k.saturating_sub(1) {
+            let l = [(k - 1, 1.0), (k, -2.0), (k + 1, 1.0)];
+            for &(i, li) in l.iter() {
+                for &(j, lj) in l.iter() {
+                    hessian[(i, j)] += regularization * li * lj;
+                }
+            }
+        }
+
+        // projected coordinate descent for the non-negative least squares problem
+        let mut w = Array1::<f64>::zeros(n_k);
+        for _ in 0..MAX_ITER_PORE_SIZE_DISTRIBUTION {
+            let mut max_step: f64 = 0.0;
+            for k in 0..n_k {
+                if hessian[(k, k)] <= 0.0 {
+                    continue;
+                }
+                let residual = hessian.row(k).dot(&w) - gradient[k];
+                let w_new = (w[k] - residual / hessian[(k, k)]).max(0.0);
+                max_step = max_step.max((w_new - w[k]).abs());
+                w[k] = w_new;
+            }
+            if max_step <= TOL_PORE_SIZE_DISTRIBUTION * w.iter().cloned().fold(1.0, f64::max) {
+                let sum = w.sum();
+                if sum <= 0.0 {
+                    return Err(EosError::Error(
+                        "The experimental isotherm cannot be represented by the kernel.".into(),
+                    ));
+                }
+                return Ok(w / sum);
+            }
+        }
+        Err(EosError::NotConverged(
+            "Adsorption::pore_size_distribution".into(),
+        ))
+    }
+
     pub fn pressure(&self) -> QuantityArray1<U> {
         QuantityArray1::from_shape_fn(self.0.len(), |i| match &self.0[i] {
             Ok(p) => {
@@ -392,6 +1254,15 @@ where
         })
     }
 
+    /// Return the fugacity of every component in the bulk fluid at every point
+    /// of the isotherm (see [PoreProfile::bulk_fugacity]).
+    pub fn fugacity(&self) -> QuantityArray2<U> {
+        QuantityArray2::from_shape_fn((self.1, self.0.len()), |(j, i)| match &self.0[i] {
+            Ok(p) => p.bulk_fugacity().get(j),
+            Err(_) => f64::NAN * U::reference_pressure(),
+        })
+    }
+
     pub fn molar_gibbs_energy(&self) -> QuantityArray1<U> {
         QuantityArray1::from_shape_fn(self.0.len(), |i| match &self.0[i] {
             Ok(p) => {
@@ -419,6 +1290,27 @@ where
         })
     }
 
+    /// Return the adsorption of the given component at every pressure.
+    pub fn loading(&self, component: usize) -> QuantityArray1<U> {
+        QuantityArray1::from_shape_fn(self.0.len(), |i| match &self.0[i] {
+            Ok(p) => p.profile.moles().get(component),
+            Err(_) => f64::NAN * U::reference_moles() / U::reference_length().powi(2),
+        })
+    }
+
+    /// Return the adsorption of every component per mass of the adsorbent.
+    ///
+    /// The mass of the solid has to correspond to the domain of the profiles,
+    /// e.g., the mass of the solid in the unit cell of a 3D pore.
+    pub fn loading_per_mass(&self, solid_mass: QuantityScalar<U>) -> QuantityArray2<U> {
+        self.adsorption() / solid_mass
+    }
+
+    /// Return all converged profiles of the isotherm.
+    pub fn profiles(&self) -> Vec<&PoreProfile<U, D, F>> {
+        self.0.iter().filter_map(|p| p.as_ref().ok()).collect()
+    }
+
     pub fn total_adsorption(&self) -> QuantityArray1<U> {
         QuantityArray1::from_shape_fn(self.0.len(), |i| match &self.0[i] {
             Ok(p) => p.profile.total_moles(),
@@ -432,4 +1324,367 @@ where
             Err(_) => f64::NAN * U::reference_surface_tension(),
         })
     }
+
+    /// Return the pressures and the total adsorption in the given units,
+    /// e.g., for plotting.
+    pub fn pressure_loading(
+        &self,
+        pressure_unit: QuantityScalar<U>,
+        loading_unit: QuantityScalar<U>,
+    ) -> EosResult<(Array1<f64>, Array1<f64>)> {
+        Ok((
+            self.pressure().to_reduced(pressure_unit)?,
+            self.total_adsorption().to_reduced(loading_unit)?,
+        ))
+    }
+
+    /// Return the pressures and the grand potentials in the given units,
+    /// e.g., for plotting.
+    pub fn pressure_grand_potential(
+        &self,
+        pressure_unit: QuantityScalar<U>,
+        grand_potential_unit: QuantityScalar<U>,
+    ) -> EosResult<(Array1<f64>, Array1<f64>)> {
+        Ok((
+            self.pressure().to_reduced(pressure_unit)?,
+            self.grand_potential().to_reduced(grand_potential_unit)?,
+        ))
+    }
+}
+
+impl<U: EosUnit, F: HelmholtzEnergyFunctional + FluidParameters> Adsorption<U, Ix1, F> {
+    /// Calculate the adsorption stress $-\frac{1}{A}\frac{\partial\Omega}{\partial h}$
+    /// exerted by the confined fluid on the walls of a 1D pore.
+    ///
+    /// The pore is created by `pore` for pore sizes $h\pm\delta h$ and the
+    /// derivative of the grand potential is evaluated by central differences.
+    /// The result is normalized by the wall area of the pore, so it is a pressure
+    /// for all geometries. The number of grid points should be fixed in `pore`
+    /// to avoid discretization noise in the finite differences.
+    pub fn adsorption_stress<P: Fn(QuantityScalar<U>) -> Pore1D<U, F>>(
+        pore: P,
+        pore_size: QuantityScalar<U>,
+        bulk: &State<U, DFT<F>>,
+        solver: Option<&DFTSolver>,
+    ) -> EosResult<QuantityScalar<U>> {
+        let delta = DELTA_PORE_SIZE_STRESS * pore_size;
+        let grand_potential = |h: QuantityScalar<U>| -> EosResult<_> {
+            let profile = pore(h).initialize(bulk, None)?.solve(solver)?;
+            // grand potential of the whole pore and wall area at the given pore size
+            let omega = profile.grand_potential.unwrap();
+            Ok(match profile.profile.grid {
+                Grid::Polar(_) => (omega, 2.0 * PI * pore_size),
+                Grid::Spherical(_) => (omega, 4.0 * PI * pore_size * pore_size),
+                _ => (2.0 * omega, pore_size.powi(0)),
+            })
+        };
+        let (omega_plus, area) = grand_potential(pore_size + delta)?;
+        let (omega_minus, _) = grand_potential(pore_size - delta)?;
+        Ok(-(omega_plus - omega_minus) / (2.0 * delta * area))
+    }
+
+    /// Calculate the solvation force per wall area
+    /// $f=-\frac{1}{A}\frac{\partial\Omega}{\partial H}-p$ between the walls of
+    /// a slit pore for the given wall separations $H$.
+    ///
+    /// At every separation, the grand potential of the whole pore is evaluated at
+    /// $H\pm\delta H$ with the same relative step and differentiated by central
+    /// differences. The number of grid points is fixed for all separations, so
+    /// that every profile can be initialized with the converged density at the
+    /// previous separation. The temperature is taken from the bulk state.
+    pub fn solvation_force(
+        functional: &Rc<DFT<F>>,
+        potential: &ExternalPotential<U>,
+        bulk: &State<U, DFT<F>>,
+        separations: &QuantityArray1<U>,
+        n_grid: usize,
+        solver: Option<&DFTSolver>,
+    ) -> EosResult<QuantityArray1<U>> {
+        let pressure = bulk.pressure(Contributions::Total);
+        let mut density: Option<QuantityArray2<U>> = None;
+        let mut grand_potential = |h: QuantityScalar<U>| -> EosResult<_> {
+            let pore = Pore1D::new(
+                functional,
+                AxisGeometry::Cartesian,
+                h,
+                potential.clone(),
+                Some(n_grid),
+                None,
+            );
+            let mut profile = pore.initialize(bulk, None)?;
+            let initial = profile.clone();
+            if let Some(density) = density.take() {
+                profile.profile.density = density;
+            }
+            let profile = profile.solve(solver).or_else(|_| initial.solve(solver))?;
+            density = Some(profile.profile.density.clone());
+            // grand potential of the whole pore per wall area
+            Ok(2.0 * profile.grand_potential.unwrap())
+        };
+        let mut force = Vec::with_capacity(separations.len());
+        for i in 0..separations.len() {
+            let h = separations.get(i);
+            let delta = DELTA_PORE_SIZE_STRESS * h;
+            let omega_minus = grand_potential(h - delta)?;
+            let omega_plus = grand_potential(h + delta)?;
+            force.push(-(omega_plus - omega_minus) / (2.0 * delta) - pressure);
+        }
+        Ok(QuantityArray1::from_shape_fn(force.len(), |i| force[i]))
+    }
+}
+
+/// Adsorption isotherm in terms of pressures relative to the saturation
+/// pressure of the bulk fluid.
+///
+/// Created by [Adsorption::isotherm_relative].
+pub struct RelativeAdsorption<U, D: Dimension, F> {
+    pub adsorption: Adsorption<U, D, F>,
+    pub saturation_pressure: QuantityScalar<U>,
+    pub relative_pressure: Array1<f64>,
+}
+
+impl<U: EosUnit, D: Dimension, F: HelmholtzEnergyFunctional> RelativeAdsorption<U, D, F>
+where
+    QuantityScalar<U>: std::fmt::Display,
+    D::Larger: Dimension<Smaller = D>,
+{
+    /// The absolute pressures of the isotherm.
+    pub fn pressure(&self) -> QuantityArray1<U> {
+        &self.relative_pressure * self.saturation_pressure
+    }
+
+    /// The pressures of the isotherm relative to the saturation pressure.
+    pub fn relative_pressure(&self) -> &Array1<f64> {
+        &self.relative_pressure
+    }
+}
+
+/// Hysteresis loop formed by an adsorption and a desorption isotherm.
+pub struct Hysteresis<U, D: Dimension, F> {
+    pub adsorption: Adsorption<U, D, F>,
+    pub desorption: Adsorption<U, D, F>,
+    /// Pressure of the largest loading jump on the adsorption branch.
+    pub condensation_pressure: QuantityScalar<U>,
+    /// Pressure of the largest loading jump on the desorption branch.
+    pub evaporation_pressure: QuantityScalar<U>,
+    /// Area enclosed by the two branches in the loading-pressure plane.
+    pub area: QuantityScalar<U>,
+    /// Whether the adsorption branch has the lower grand potential at every pressure.
+    pub adsorption_is_stable: Array1<bool>,
+}
+
+impl<U: EosUnit, D: Dimension, F: HelmholtzEnergyFunctional> Hysteresis<U, D, F>
+where
+    QuantityScalar<U>: std::fmt::Display,
+    D::Larger: Dimension<Smaller = D>,
+{
+    /// Analyze the hysteresis loop of an adsorption and a desorption isotherm
+    /// that are evaluated at the same pressures, e.g., the results of
+    /// [Adsorption::adsorption_isotherm] and [Adsorption::desorption_isotherm].
+    ///
+    /// The condensation and evaporation pressures are estimated as the
+    /// geometric mean of the two pressures enclosing the largest change in
+    /// the total loading of the respective branch.
+    pub fn new(
+        adsorption: Adsorption<U, D, F>,
+        desorption: Adsorption<U, D, F>,
+    ) -> EosResult<Self> {
+        let n = adsorption.0.len();
+        if desorption.0.len() != n {
+            return Err(EosError::Error(
+                "Both branches have to be evaluated at the same pressures.".into(),
+            ));
+        }
+        let loading_scale = adsorption
+            .0
+            .iter()
+            .chain(desorption.0.iter())
+            .rev()
+            .find_map(|p| p.as_ref().ok())
+            .map(|p| p.profile.total_moles())
+            .ok_or_else(|| {
+                EosError::Error("The isotherms contain no converged profiles.".into())
+            })?;
+        let pressure = adsorption.pressure().to_reduced(U::reference_pressure())?;
+        let n_ads = adsorption.total_adsorption().to_reduced(loading_scale)?;
+        let n_des = desorption.total_adsorption().to_reduced(loading_scale)?;
+
+        // pressure at the largest jump of the loading
+        let jump = |loading: &Array1<f64>| {
+            (0..n.saturating_sub(1))
+                .filter(|&i| loading[i].is_finite() && loading[i + 1].is_finite())
+                .max_by(|&i, &j| {
+                    let di = (loading[i + 1] - loading[i]).abs();
+                    let dj = (loading[j + 1] - loading[j]).abs();
+                    di.partial_cmp(&dj).unwrap()
+                })
+                .map(|i| (pressure[i] * pressure[i + 1]).sqrt() * U::reference_pressure())
+                .ok_or_else(|| EosError::Error("The isotherms contain too few points.".into()))
+        };
+        let condensation_pressure = jump(&n_ads)?;
+        let evaporation_pressure = jump(&n_des)?;
+
+        // trapezoidal rule for the difference between the branches
+        let delta = &n_des - &n_ads;
+        let area = (0..n.saturating_sub(1))
+            .filter(|&i| delta[i].is_finite() && delta[i + 1].is_finite())
+            .map(|i| 0.5 * (delta[i] + delta[i + 1]) * (pressure[i + 1] - pressure[i]))
+            .sum::<f64>()
+            .abs()
+            * loading_scale
+            * U::reference_pressure();
+
+        let omega_a = adsorption.grand_potential();
+        let omega_d = desorption.grand_potential();
+        let adsorption_is_stable = Array1::from_shape_fn(n, |i| {
+            omega_d.get(i).is_nan() || omega_a.get(i) < omega_d.get(i)
+        });
+
+        Ok(Self {
+            adsorption,
+            desorption,
+            condensation_pressure,
+            evaporation_pressure,
+            area,
+            adsorption_is_stable,
+        })
+    }
+}
+
+/// Complete isotherm of a first-order transition in a pore including the
+/// unstable states between the spinodals.
+///
+/// Created by [Adsorption::arc_length_continuation].
+pub struct VanDerWaalsLoop<U, D: Dimension, F> {
+    /// States up to the condensation spinodal (empty pore).
+    pub adsorption: Adsorption<U, D, F>,
+    /// Unstable states between the two spinodals.
+    pub unstable: Adsorption<U, D, F>,
+    /// States starting at the evaporation spinodal (filled pore).
+    pub desorption: Adsorption<U, D, F>,
+    /// Pressure at the upper turning point of the isotherm.
+    pub condensation_spinodal: Option<QuantityScalar<U>>,
+    /// Pressure at the lower turning point of the isotherm.
+    pub evaporation_spinodal: Option<QuantityScalar<U>>,
+    /// Pressure at which the filled and the empty pore coexist.
+    pub equilibrium_pressure: Option<QuantityScalar<U>>,
+}
+
+/// Lazy iterator over the points of an adsorption isotherm.
+///
+/// Created by [Adsorption::iter].
+pub struct AdsorptionIter<'a, U, D: Dimension, F, S> {
+    functional: Rc<DFT<F>>,
+    temperature: QuantityScalar<U>,
+    pressure: QuantityArray1<U>,
+    pore: &'a S,
+    moles: QuantityArray1<U>,
+    solver: Option<&'a DFTSolver>,
+    index: usize,
+    density: Option<QuantityArray<U, D::Larger>>,
+    external_potential: Option<Array<f64, D::Larger>>,
+    convolver: Option<Rc<dyn Convolver<f64, D>>>,
+}
+
+impl<'a, U: EosUnit, D: Dimension, F: HelmholtzEnergyFunctional, S: PoreSpecification<U, D, F>>
+    AdsorptionIter<'a, U, D, F, S>
+where
+    QuantityScalar<U>: std::fmt::Display,
+    D::Larger: Dimension<Smaller = D>,
+{
+    fn solve_point(&mut self, pressure: QuantityScalar<U>) -> EosResult<QuantityScalar<U>> {
+        let bulk = Adsorption::<U, D, F>::bulk_state(
+            &self.functional,
+            self.temperature,
+            pressure,
+            &self.moles,
+        )?;
+        let mut p = self.pore.initialize_with_convolver(
+            &bulk,
+            self.external_potential.as_ref(),
+            self.convolver.as_ref(),
+        )?;
+
+        // The external potential and the convolver are reused for all points
+        if self.convolver.is_none() {
+            self.external_potential = Some(p.profile.external_potential.clone());
+            self.convolver = Some(p.profile.convolver.clone());
+        }
+
+        let p2 = p.clone();
+        if let Some(density) = self.density.take() {
+            p.profile.density = density;
+        }
+        let profile = p.solve(self.solver).or_else(|_| p2.solve(self.solver))?;
+        let loading = profile.profile.total_moles();
+        self.density = Some(profile.profile.density);
+        Ok(loading)
+    }
+}
+
+impl<'a, U: EosUnit, D: Dimension, F: HelmholtzEnergyFunctional, S: PoreSpecification<U, D, F>>
+    Iterator for AdsorptionIter<'a, U, D, F, S>
+where
+    QuantityScalar<U>: std::fmt::Display,
+    D::Larger: Dimension<Smaller = D>,
+{
+    type Item = EosResult<(QuantityScalar<U>, QuantityScalar<U>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.pressure.len() {
+            return None;
+        }
+        let pressure = self.pressure.get(self.index);
+        self.index += 1;
+        Some(
+            self.solve_point(pressure)
+                .map(|loading| (pressure, loading)),
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.pressure.len() - self.index;
+        (n, Some(n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fundamental_measure_theory::{FMTFunctional, FMTVersion};
+    use quantity::si::{SIUnit, ANGSTROM, KELVIN, MOL, NAV};
+
+    #[test]
+    fn adsorption_stress_in_curved_pores() {
+        // without wall potential the grand potential of a dilute fluid is -pV,
+        // so the stress on the walls equals the bulk pressure
+        let func = Rc::new(FMTFunctional::new(&arr1(&[1.0]), FMTVersion::WhiteBear));
+        let bulk = State::new_nvt(
+            &func,
+            300.0 * KELVIN,
+            NAV * MOL * ANGSTROM.powi(3) / 1e-4,
+            &(arr1(&[1.0]) * MOL),
+        )
+        .unwrap();
+        let p = bulk.pressure(Contributions::Total);
+        for &geometry in &[AxisGeometry::Polar, AxisGeometry::Spherical] {
+            let pore = |h| {
+                Pore1D::new(
+                    &func,
+                    geometry,
+                    h,
+                    ExternalPotential::SimpleLJ93 {
+                        sigma_ss: 1.0,
+                        epsilon_k_ss: 0.0,
+                    },
+                    Some(256),
+                    None,
+                )
+            };
+            let stress =
+                Adsorption::<SIUnit, Ix1, _>::adsorption_stress(pore, 10.0 * ANGSTROM, &bulk, None)
+                    .unwrap();
+            assert!((stress.to_reduced(p).unwrap() - 1.0).abs() < 1e-2);
+        }
+    }
 }
@@ -1,21 +1,61 @@
+use crate::adsorption::elements::molar_mass;
 use crate::adsorption::{ExternalPotential, FluidParameters};
-use crate::convolver::ConvolverFFT;
+use crate::convolver::{Convolver, ConvolverFFT};
 use crate::functional::{HelmholtzEnergyFunctional, DFT};
 use crate::geometry::{Axis, AxisGeometry, Grid};
-use crate::profile::{DFTProfile, CUTOFF_RADIUS, MAX_POTENTIAL};
+use crate::interface::SurfaceTensionDiagram;
+use crate::profile::{is_accessible, DFTProfile, CUTOFF_RADIUS, MAX_PAIR_POTENTIAL, MAX_POTENTIAL};
 use crate::solver::DFTSolver;
-use feos_core::{Contributions, EosResult, EosUnit, State};
+use feos_core::{
+    Contributions, EosError, EosResult, EosUnit, EquationOfState, PhaseEquilibrium, State,
+    StateBuilder, VLEOptions,
+};
+use log::{info, warn};
 use ndarray::prelude::*;
 use ndarray::Axis as Axis_nd;
-use ndarray::Zip;
+use ndarray::{RemoveAxis, Zip};
+use ndarray_linalg::Inverse;
 use ndarray_stats::QuantileExt;
-use quantity::{QuantityArray2, QuantityScalar};
+use quantity::si::{SINumber, SIUnit, GRAM, MOL, NAV};
+use quantity::{QuantityArray, QuantityArray1, QuantityArray2, QuantityScalar};
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+use std::collections::{HashMap, VecDeque};
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
 use std::rc::Rc;
 
 const POTENTIAL_OFFSET: f64 = 2.0;
-const DEFAULT_GRID_POINTS: usize = 2048;
+const TOL_TEMPERATURE: f64 = 1e-10;
+const GRID_POINTS_PER_SIGMA: f64 = 20.0;
+const SUM_RULE_TOLERANCE: f64 = 1e-3;
+const MAX_GRID_ANISOTROPY: f64 = 1.5;
+const KCAL_PER_MOL_IN_K: f64 = 4184.0 / 8.314_462_618;
+const MIN_RELATIVE_DENSITY: f64 = 1e-10;
+const MAX_ITER_PORE_SIZE: usize = 100;
+const TOL_PORE_SIZE: f64 = 1e-4;
+const DELTA_MU_COMPRESSIBILITY: f64 = 1e-3;
+const DELTA_LAPLACIAN: f64 = 1e-3;
+const MAX_ITER_INNER_MAXIMUM: usize = 100;
+const N_PHI_SHELL: usize = 256;
+const TOL_DISTINCT_PROFILES: f64 = 1e-4;
+const MIN_FILM_EXCESS: f64 = 1e-12;
 
 /// Parameters required to specify a 1D pore.
+///
+/// Slit pores (cartesian geometry) are assumed to consist of two identical
+/// walls. Only one half of the pore, from the center to the wall, is resolved
+/// on the grid. The cosine transforms used in the convolutions correspond to a
+/// reflective boundary in the center of the pore, so the density profile is
+/// mirror symmetric by construction.
+///
+/// If no number of grid points is provided, the grid is chosen such that
+/// the smallest segment diameter is resolved by at least 20 grid points.
+///
+/// For slit pores, the domain extends beyond the wall by the potential
+/// offset (in units of the largest segment diameter, default: 2) so that
+/// particles can not interact through the wall.
 pub struct Pore1D<U, F> {
     functional: Rc<DFT<F>>,
     geometry: AxisGeometry,
@@ -23,6 +63,8 @@ pub struct Pore1D<U, F> {
     potential: ExternalPotential<U>,
     n_grid: Option<usize>,
     potential_cutoff: Option<f64>,
+    potential_offset: Option<f64>,
+    quantum_correction: Option<QuantityScalar<U>>,
 }
 
 impl<U: EosUnit, F: HelmholtzEnergyFunctional> Pore1D<U, F> {
@@ -41,11 +83,480 @@ impl<U: EosUnit, F: HelmholtzEnergyFunctional> Pore1D<U, F> {
             potential,
             n_grid,
             potential_cutoff,
+            potential_offset: None,
+            quantum_correction: None,
+        }
+    }
+
+    /// Create a new pore with the pore size given in reduced units
+    /// (multiples of the reference length of `U`).
+    pub fn new_reduced(
+        functional: &Rc<DFT<F>>,
+        geometry: AxisGeometry,
+        pore_size: f64,
+        potential: ExternalPotential<U>,
+        n_grid: Option<usize>,
+        potential_cutoff: Option<f64>,
+    ) -> Self {
+        Self::new(
+            functional,
+            geometry,
+            pore_size * U::reference_length(),
+            potential,
+            n_grid,
+            potential_cutoff,
+        )
+    }
+
+    /// Set the extent of the domain beyond the wall of slit pores in units
+    /// of the largest segment diameter.
+    pub fn potential_offset(mut self, potential_offset: f64) -> Self {
+        self.potential_offset = Some(potential_offset);
+        self
+    }
+
+    /// Use the Feynman-Hibbs effective potential
+    /// $V^\mathrm{FH}=V+\frac{\hbar^2}{24mk_\mathrm{B}T}\nabla^2V$ for light gases.
+    ///
+    /// The quantum correction is the parameter $\frac{\hbar^2}{24mk_\mathrm{B}}$
+    /// (in units of length² × temperature), which is about 1.0 Å² K for hydrogen.
+    /// The laplacian of the wall potential is evaluated numerically.
+    pub fn quantum_correction(mut self, quantum_correction: QuantityScalar<U>) -> Self {
+        self.quantum_correction = Some(quantum_correction);
+        self
+    }
+
+    /// Return the condensation pressure in the pore predicted by the Kelvin
+    /// equation. See [kelvin_pressure] for details.
+    pub fn kelvin_pressure(
+        &self,
+        temperature: QuantityScalar<U>,
+        contact_angle: f64,
+    ) -> EosResult<QuantityScalar<U>> {
+        kelvin_pressure(
+            &self.functional,
+            temperature,
+            self.pore_size,
+            self.geometry,
+            contact_angle,
+        )
+    }
+}
+
+/// Return the condensation pressure in a pore predicted by the Kelvin
+/// equation $\ln\frac{p}{p^0}=-\frac{2\gamma v^\mathrm{L}\cos\theta}{RTh}$.
+///
+/// The characteristic length $h$ is the pore width for slit pores and the
+/// pore radius for cylindrical and spherical pores, consistent with a
+/// cylindrical and hemispherical meniscus, respectively. The saturation
+/// pressure, the liquid molar volume, and the surface tension are
+/// calculated from the functional. The contact angle is given in radians.
+pub fn kelvin_pressure<U: EosUnit, F: HelmholtzEnergyFunctional>(
+    functional: &Rc<DFT<F>>,
+    temperature: QuantityScalar<U>,
+    pore_size: QuantityScalar<U>,
+    geometry: AxisGeometry,
+    contact_angle: f64,
+) -> EosResult<QuantityScalar<U>> {
+    if functional.components() > 1 {
+        return Err(EosError::Error(
+            "The Kelvin equation is only available for pure components.".into(),
+        ));
+    }
+    let vle = PhaseEquilibrium::pure_t(functional, temperature, None, VLEOptions::default())?;
+    let surface_tension =
+        SurfaceTensionDiagram::new(&vec![vle.clone()], None, None, None, None, None)
+            .profiles
+            .first()
+            .and_then(|p| p.surface_tension)
+            .ok_or_else(|| EosError::NotConverged("kelvin_pressure".into()))?;
+    let p0 = vle.vapor().pressure(Contributions::Total);
+    let molar_volume = vle.liquid().density.recip();
+
+    // curvature of the meniscus: cylindrical with the radius h/2 in slit
+    // pores and hemispherical with the radius h otherwise
+    let curvature = match geometry {
+        AxisGeometry::Cartesian => (0.5 * pore_size).recip(),
+        AxisGeometry::Polar | AxisGeometry::Spherical => 2.0 / pore_size,
+    };
+    let exponent = (-surface_tension * molar_volume * contact_angle.cos() * curvature)
+        .to_reduced(U::gas_constant() * temperature)?;
+    Ok(p0 * exponent.exp())
+}
+
+/// Builder for [Pore1D].
+///
+/// The geometry, the pore size and the external potential have to be
+/// specified. If the number of grid points is not specified, it is chosen
+/// from the pore size and the segment diameters of the fluid. The potential
+/// cutoff defaults to 50.
+pub struct Pore1DBuilder<U, F> {
+    functional: Rc<DFT<F>>,
+    geometry: Option<AxisGeometry>,
+    pore_size: Option<QuantityScalar<U>>,
+    potential: Option<ExternalPotential<U>>,
+    n_grid: Option<usize>,
+    potential_cutoff: Option<f64>,
+    potential_offset: Option<f64>,
+}
+
+impl<U: EosUnit, F: HelmholtzEnergyFunctional> Pore1DBuilder<U, F> {
+    /// Create a new builder for a pore filled with the given functional.
+    pub fn new(functional: &Rc<DFT<F>>) -> Self {
+        Self {
+            functional: functional.clone(),
+            geometry: None,
+            pore_size: None,
+            potential: None,
+            n_grid: None,
+            potential_cutoff: None,
+            potential_offset: None,
+        }
+    }
+
+    /// Set the geometry of the pore.
+    pub fn geometry(mut self, geometry: AxisGeometry) -> Self {
+        self.geometry = Some(geometry);
+        self
+    }
+
+    /// Set the pore size (width of a slit pore or radius of curved pores).
+    pub fn pore_size(mut self, pore_size: QuantityScalar<U>) -> Self {
+        self.pore_size = Some(pore_size);
+        self
+    }
+
+    /// Set the external potential of the pore walls.
+    pub fn potential(mut self, potential: ExternalPotential<U>) -> Self {
+        self.potential = Some(potential);
+        self
+    }
+
+    /// Set the number of grid points.
+    pub fn n_grid(mut self, n_grid: usize) -> Self {
+        self.n_grid = Some(n_grid);
+        self
+    }
+
+    /// Set the maximum value of the reduced external potential (default: 50).
+    ///
+    /// The value is used to clamp the final external potential. The solver
+    /// keeps the density fixed at all points where the potential reaches 50,
+    /// so larger values only change the potential inside the wall, where the
+    /// density is practically zero anyway. Values well below 50 lead to a
+    /// spurious density $\rho_\mathrm{b}e^{-V}$ inside the wall, which is
+    /// included in the adsorption.
+    pub fn potential_cutoff(mut self, potential_cutoff: f64) -> Self {
+        self.potential_cutoff = Some(potential_cutoff);
+        self
+    }
+
+    /// Set the extent of the domain beyond the wall of slit pores in units
+    /// of the largest segment diameter.
+    pub fn potential_offset(mut self, potential_offset: f64) -> Self {
+        self.potential_offset = Some(potential_offset);
+        self
+    }
+
+    /// Build the [Pore1D].
+    pub fn build(self) -> EosResult<Pore1D<U, F>> {
+        let missing = |field: &str| EosError::Error(format!("Pore1DBuilder: missing {}", field));
+        let geometry = self.geometry.ok_or_else(|| missing("geometry"))?;
+        let potential = self.potential.ok_or_else(|| missing("potential"))?;
+        potential.check_geometry(geometry, self.functional.component_index.len())?;
+        let mut pore = Pore1D::new(
+            &self.functional,
+            geometry,
+            self.pore_size.ok_or_else(|| missing("pore_size"))?,
+            potential,
+            self.n_grid,
+            Some(self.potential_cutoff.unwrap_or(MAX_POTENTIAL)),
+        );
+        pore.potential_offset = self.potential_offset;
+        Ok(pore)
+    }
+}
+
+impl<U: EosUnit, F: HelmholtzEnergyFunctional + FluidParameters> Pore1D<U, F> {
+    /// Create the axis for the given pore size.
+    fn axis(&self, pore_size: QuantityScalar<U>) -> EosResult<Axis> {
+        // check that the pore can hold at least one fluid segment
+        let sigma_ff = self.functional.functional.sigma_ff().max().unwrap();
+        let pore_width = match self.geometry {
+            AxisGeometry::Cartesian => pore_size,
+            AxisGeometry::Polar | AxisGeometry::Spherical => 2.0 * pore_size,
+        };
+        if pore_width.to_reduced(U::reference_length())? < *sigma_ff {
+            return Err(EosError::Error(format!(
+                "Invalid pore size: the pore width {} is smaller than the largest segment diameter {}.",
+                pore_width,
+                *sigma_ff * U::reference_length()
+            )));
+        }
+        let potential_offset = self.potential_offset.unwrap_or(POTENTIAL_OFFSET) * sigma_ff;
+
+        // choose the number of grid points from the smallest segment diameter
+        let n_grid = match self.n_grid {
+            Some(n_grid) => n_grid,
+            None => {
+                let length = match self.geometry {
+                    AxisGeometry::Cartesian => {
+                        0.5 * pore_size.to_reduced(U::reference_length())? + potential_offset
+                    }
+                    AxisGeometry::Polar | AxisGeometry::Spherical => {
+                        pore_size.to_reduced(U::reference_length())?
+                    }
+                };
+                let spacing =
+                    self.functional.functional.sigma_ff().min().unwrap() / GRID_POINTS_PER_SIGMA;
+                let n_grid = Grid::next_fast_len((length / spacing).ceil() as usize);
+                info!("Pore1D: using {} grid points.", n_grid);
+                n_grid
+            }
+        };
+
+        match self.geometry {
+            AxisGeometry::Cartesian => {
+                Axis::new_cartesian(n_grid, 0.5 * pore_size, Some(potential_offset))
+            }
+            AxisGeometry::Polar => Axis::new_polar(n_grid, pore_size),
+            AxisGeometry::Spherical => Axis::new_spherical(n_grid, pore_size),
+        }
+    }
+
+    /// Calculate the external potential for several pore sizes at once.
+    ///
+    /// The pore size of `self` is ignored. The results are identical to the
+    /// external potentials calculated in [PoreSpecification::initialize] and
+    /// can be passed to the initialization of the respective pores, e.g., to
+    /// construct kernels for pore size distributions.
+    pub fn external_potential_batch(
+        &self,
+        pore_sizes: &[QuantityScalar<U>],
+        temperature: QuantityScalar<U>,
+    ) -> EosResult<Vec<Array2<f64>>> {
+        let t = temperature.to_reduced(U::reference_temperature())?;
+        let lambda = self
+            .quantum_correction
+            .map(|q| q.to_reduced(U::reference_length().powi(2) * U::reference_temperature()))
+            .transpose()?
+            .map(|q| q / t);
+        pore_sizes
+            .iter()
+            .map(|&pore_size| {
+                let axis = self.axis(pore_size)?;
+                external_potential_1d_reduced(
+                    effective_pore_size(pore_size, &axis)?,
+                    t,
+                    &self.potential,
+                    &self.functional.functional,
+                    &axis,
+                    lambda,
+                    self.potential_cutoff,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Find the width of a slit pore that maximizes the selectivity of component
+/// `i` over component `j` for the given bulk state.
+///
+/// A golden section search is performed between the given bounds. All solved
+/// profiles are cached and the profile of the closest pore size, shifted so
+/// that the walls coincide and interpolated onto the new grid, is used as
+/// initial guess. Returns the optimal pore size and the corresponding
+/// selectivity.
+pub fn optimize_pore_size<U: EosUnit, F: HelmholtzEnergyFunctional + FluidParameters>(
+    functional: &Rc<DFT<F>>,
+    potential: &ExternalPotential<U>,
+    temperature: QuantityScalar<U>,
+    bulk: &State<U, DFT<F>>,
+    component_pair: (usize, usize),
+    bounds: (QuantityScalar<U>, QuantityScalar<U>),
+) -> EosResult<(QuantityScalar<U>, f64)> {
+    let t = temperature.to_reduced(U::reference_temperature())?;
+    let t_bulk = bulk.temperature.to_reduced(U::reference_temperature())?;
+    if (t - t_bulk).abs() > TOL_TEMPERATURE * t {
+        return Err(EosError::Error(format!(
+            "The temperature {} does not match the temperature {} of the bulk state.",
+            temperature, bulk.temperature
+        )));
+    }
+
+    let (i, j) = component_pair;
+    let mut cache: Vec<(f64, f64, PoreProfile1D<U, F>)> = Vec::new();
+    let mut selectivity = |pore_size: f64| -> EosResult<f64> {
+        if let Some((_, s, _)) = cache.iter().find(|(h, _, _)| *h == pore_size) {
+            return Ok(*s);
+        }
+        let pore = Pore1D::new(
+            functional,
+            AxisGeometry::Cartesian,
+            pore_size * U::reference_length(),
+            potential.clone(),
+            None,
+            None,
+        );
+        let mut profile = pore.initialize(bulk, None)?;
+        let fallback = profile.clone();
+        let nearest = cache.iter().min_by(|(h1, _, _), (h2, _, _)| {
+            (h1 - pore_size)
+                .abs()
+                .partial_cmp(&(h2 - pore_size).abs())
+                .unwrap()
+        });
+        if let Some((h, _, nearest)) = nearest {
+            // align the walls of both pores before interpolating
+            let mut guess = nearest.profile.clone();
+            guess.grid.axes_mut()[0].grid += 0.5 * (pore_size - h);
+            profile.profile.interpolate_density(&guess)?;
+        }
+        let profile = profile.solve(None).or_else(|_| fallback.solve(None))?;
+        let s = profile.selectivity(i, j)?;
+        cache.push((pore_size, s, profile));
+        Ok(s)
+    };
+
+    // golden section search for the maximum
+    let g = 0.5 * (5f64.sqrt() - 1.0);
+    let mut a = bounds.0.to_reduced(U::reference_length())?;
+    let mut b = bounds.1.to_reduced(U::reference_length())?;
+    let mut c = b - g * (b - a);
+    let mut d = a + g * (b - a);
+    let mut s_c = selectivity(c)?;
+    let mut s_d = selectivity(d)?;
+    for _ in 0..MAX_ITER_PORE_SIZE {
+        if (b - a).abs() < TOL_PORE_SIZE * b.abs() {
+            break;
+        }
+        if s_c > s_d {
+            b = d;
+            d = c;
+            s_d = s_c;
+            c = b - g * (b - a);
+            s_c = selectivity(c)?;
+        } else {
+            a = c;
+            c = d;
+            s_c = s_d;
+            d = a + g * (b - a);
+            s_d = selectivity(d)?;
+        }
+    }
+    let (pore_size, s) = if s_c > s_d { (c, s_c) } else { (d, s_d) };
+    Ok((pore_size * U::reference_length(), s))
+}
+
+/// Parameters required to specify a 2D cylindrical pore of finite length.
+///
+/// The pore is an open tube, e.g., a carbon nanotube, modeled as a homogeneous
+/// cylindrical shell with radius `pore_size`, length `length`, and the surface
+/// density `rho_s` of solid interaction sites. The solid-fluid interactions are
+/// given by the 12-6 Lennard-Jones potential integrated over the shell, which
+/// includes the end effects at the pore mouths.
+///
+/// The grid resolves the radial and the axial coordinate. Only one half of the
+/// tube, from its center to the reservoir, is resolved axially with a reflective
+/// boundary in the center. The domain extends radially beyond the shell and
+/// axially beyond the pore mouth by the reservoir length (default: twice the
+/// largest segment diameter), so that the fluid in the tube is in contact with
+/// the surrounding fluid.
+pub struct Pore2D<U, F> {
+    functional: Rc<DFT<F>>,
+    pore_size: QuantityScalar<U>,
+    length: QuantityScalar<U>,
+    sigma_ss: f64,
+    epsilon_k_ss: f64,
+    rho_s: f64,
+    n_grid: Option<[usize; 2]>,
+    potential_cutoff: Option<f64>,
+    reservoir_length: Option<QuantityScalar<U>>,
+}
+
+impl<U, F> Pore2D<U, F> {
+    pub fn new(
+        functional: &Rc<DFT<F>>,
+        pore_size: QuantityScalar<U>,
+        length: QuantityScalar<U>,
+        sigma_ss: f64,
+        epsilon_k_ss: f64,
+        rho_s: f64,
+        n_grid: Option<[usize; 2]>,
+        potential_cutoff: Option<f64>,
+    ) -> Self {
+        Self {
+            functional: functional.clone(),
+            pore_size,
+            length,
+            sigma_ss,
+            epsilon_k_ss,
+            rho_s,
+            n_grid,
+            potential_cutoff,
+            reservoir_length: None,
+        }
+    }
+
+    /// Set the extent of the domain beyond the shell and beyond the pore mouth.
+    pub fn reservoir_length(mut self, reservoir_length: QuantityScalar<U>) -> Self {
+        self.reservoir_length = Some(reservoir_length);
+        self
+    }
+}
+
+/// Cutoff radius for the solid-fluid interactions in 3D pores.
+#[derive(Clone, Copy)]
+pub enum CutoffRadius<U> {
+    /// The same cutoff radius for all components.
+    Fixed(QuantityScalar<U>),
+    /// A multiple of the largest solid-fluid diameter $\sigma_{si}$ of every component.
+    Multiple(f64),
+}
+
+/// Functional form of the solid-fluid interactions in 3D pores.
+#[derive(Clone, Copy)]
+pub enum PotentialForm {
+    /// Lennard-Jones 12-6 potential.
+    LJ,
+    /// Exponential-6 (Buckingham) potential
+    /// $u(r)=\frac{\varepsilon}{1-6/\alpha}\left(\frac{6}{\alpha}e^{\alpha\left(1-r/r_m\right)}-\left(\frac{r_m}{r}\right)^6\right)$
+    /// with $r_m=2^{1/6}\sigma$. Below its inner maximum, the potential is kept constant.
+    Exp6 { alpha: f64 },
+}
+
+impl PotentialForm {
+    /// Reduced distance $r/r_m$ of the inner maximum of the exp-6 potential.
+    fn inner_maximum(&self) -> f64 {
+        match self {
+            Self::LJ => 0.0,
+            Self::Exp6 { alpha } => {
+                // the maximum is the root of alpha*(1-x) + 7*ln(x) below 7/alpha
+                let f = |x: f64| alpha * (1.0 - x) + 7.0 * x.ln();
+                let (mut a, mut b) = (f64::EPSILON, 7.0 / alpha);
+                if f(b) <= 0.0 {
+                    return 0.0;
+                }
+                for _ in 0..MAX_ITER_INNER_MAXIMUM {
+                    let c = 0.5 * (a + b);
+                    if f(c) < 0.0 {
+                        a = c;
+                    } else {
+                        b = c;
+                    }
+                }
+                b
+            }
         }
     }
 }
 
 /// Parameters required to specify a 3D pore.
+///
+/// The weight functions of FMT assume a roughly isotropic resolution. The
+/// grid spacing should be similar in all directions and not larger than
+/// about 5% of the smallest segment diameter `sigma_ff`. The ratio of the
+/// largest to the smallest grid spacing is checked during initialization.
 pub struct Pore3D<U, F> {
     functional: Rc<DFT<F>>,
     system_size: [QuantityScalar<U>; 3],
@@ -54,7 +565,16 @@ pub struct Pore3D<U, F> {
     sigma_ss: Array1<f64>,
     epsilon_k_ss: Array1<f64>,
     potential_cutoff: Option<f64>,
-    cutoff_radius: Option<QuantityScalar<U>>,
+    cutoff_radius: Option<CutoffRadius<U>>,
+    strict_grid_spacing: bool,
+    periodic: [bool; 3],
+    cell_vectors: Option<QuantityArray2<U>>,
+    quantum_correction: Option<QuantityScalar<U>>,
+    potential_form: PotentialForm,
+    freeze_inaccessible: bool,
+    molar_masses: Option<Array1<f64>>,
+    vacuum_padding: Option<[QuantityScalar<U>; 3]>,
+    wrap_coordinates: bool,
 }
 
 impl<U, F> Pore3D<U, F> {
@@ -66,7 +586,7 @@ impl<U, F> Pore3D<U, F> {
         sigma_ss: Array1<f64>,
         epsilon_k_ss: Array1<f64>,
         potential_cutoff: Option<f64>,
-        cutoff_radius: Option<QuantityScalar<U>>,
+        cutoff_radius: Option<CutoffRadius<U>>,
     ) -> Self {
         Self {
             functional: functional.clone(),
@@ -77,8 +597,465 @@ impl<U, F> Pore3D<U, F> {
             epsilon_k_ss,
             potential_cutoff,
             cutoff_radius,
+            strict_grid_spacing: false,
+            periodic: [true; 3],
+            cell_vectors: None,
+            quantum_correction: None,
+            potential_form: PotentialForm::LJ,
+            freeze_inaccessible: false,
+            molar_masses: None,
+            vacuum_padding: None,
+            wrap_coordinates: false,
         }
     }
+
+    /// Return an error instead of a warning if the grid spacing is strongly
+    /// anisotropic.
+    pub fn strict_grid_spacing(mut self, strict: bool) -> Self {
+        self.strict_grid_spacing = strict;
+        self
+    }
+
+    /// Specify which directions of the solid are periodic (default: all).
+    ///
+    /// For non-periodic directions, the minimum image convention is not
+    /// applied in the calculation of the external potential, e.g., for
+    /// isolated solutes or nanoparticles. If any direction is non-periodic,
+    /// the density profile is calculated with reflective boundaries in all
+    /// directions instead of periodic boundaries.
+    pub fn periodic(mut self, periodic: [bool; 3]) -> Self {
+        self.periodic = periodic;
+        self
+    }
+
+    /// Extend the grid on both sides of every non-periodic direction by
+    /// the given vacuum buffer.
+    ///
+    /// Analogous to the potential offset of slit pores, the buffer allows
+    /// the density to relax towards the bulk density far away from the
+    /// solid. The grid spacing is kept fixed and the solid is shifted
+    /// by the width of the buffer.
+    pub fn vacuum_padding(mut self, vacuum_padding: [QuantityScalar<U>; 3]) -> Self {
+        self.vacuum_padding = Some(vacuum_padding);
+        self
+    }
+
+    /// Wrap all interaction sites into the unit cell in the periodic
+    /// directions, e.g., for coordinates taken from a trajectory frame.
+    pub fn wrap_coordinates(mut self, wrap: bool) -> Self {
+        self.wrap_coordinates = wrap;
+        self
+    }
+
+    /// Use a triclinic (non-orthogonal) unit cell, e.g., for zeolites or
+    /// MOFs with monoclinic or triclinic symmetry.
+    ///
+    /// The rows of `cell_vectors` are the three cell vectors in cartesian
+    /// coordinates. They replace the system size and the grid points are
+    /// distributed along the cell vectors. Triclinic cells are only
+    /// supported for systems that are periodic in all directions.
+    pub fn cell_vectors(mut self, cell_vectors: QuantityArray2<U>) -> Self {
+        self.cell_vectors = Some(cell_vectors);
+        self
+    }
+
+    /// Use the Feynman-Hibbs effective potential
+    /// $V^\mathrm{FH}=V+\frac{\hbar^2}{24mk_\mathrm{B}T}\nabla^2V$ for light gases.
+    ///
+    /// The quantum correction is the parameter $\frac{\hbar^2}{24mk_\mathrm{B}}$
+    /// (in units of length² × temperature), which is about 1.0 Å² K for hydrogen.
+    pub fn quantum_correction(mut self, quantum_correction: QuantityScalar<U>) -> Self {
+        self.quantum_correction = Some(quantum_correction);
+        self
+    }
+
+    /// Freeze all grid points at which the external potential reaches the
+    /// potential cutoff, e.g., inside the solid of dense frameworks.
+    ///
+    /// The potential at these points is raised to the maximum potential, so
+    /// that the solver keeps their (practically vanishing) density fixed.
+    pub fn freeze_inaccessible(mut self, freeze: bool) -> Self {
+        self.freeze_inaccessible = freeze;
+        self
+    }
+
+    /// Set the functional form of the solid-fluid interactions (default: LJ 12-6).
+    pub fn potential_form(mut self, potential_form: PotentialForm) -> Self {
+        self.potential_form = potential_form;
+        self
+    }
+
+    /// Specify the element of every interaction site of the solid, which
+    /// determines the mass of the solid.
+    pub fn elements(mut self, elements: &[&str]) -> EosResult<Self> {
+        if elements.len() != self.sigma_ss.len() {
+            return Err(EosError::Error(format!(
+                "{} elements were provided for {} interaction sites.",
+                elements.len(),
+                self.sigma_ss.len()
+            )));
+        }
+        self.molar_masses = Some(
+            elements
+                .iter()
+                .map(|&e| {
+                    molar_mass(e)
+                        .ok_or_else(|| EosError::Error(format!("Unknown element '{}'.", e)))
+                })
+                .collect::<EosResult<_>>()?,
+        );
+        Ok(self)
+    }
+
+    /// Increase the number of grid points in every direction to the next
+    /// number that factors into 2, 3, and 5, for which FFTs are most efficient.
+    ///
+    /// The system size is kept fixed, so only the grid spacing changes
+    /// slightly. For periodic systems the results are essentially unaffected.
+    pub fn round_grid_to_fast_fft(mut self, round: bool) -> Self {
+        if round {
+            for n in self.n_grid.iter_mut() {
+                *n = Grid::next_fast_len(*n);
+            }
+        }
+        self
+    }
+}
+
+impl<U: EosUnit, F> Pore3D<U, F> {
+    /// Create a new 3D pore with the system size and the coordinates of the
+    /// interaction sites given in reduced units (multiples of the reference
+    /// length of `U`).
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_reduced(
+        functional: &Rc<DFT<F>>,
+        system_size: [f64; 3],
+        n_grid: [usize; 3],
+        coordinates: Array2<f64>,
+        sigma_ss: Array1<f64>,
+        epsilon_k_ss: Array1<f64>,
+        potential_cutoff: Option<f64>,
+        cutoff_radius: Option<CutoffRadius<U>>,
+    ) -> Self {
+        Self::new(
+            functional,
+            system_size.map(|l| l * U::reference_length()),
+            n_grid,
+            coordinates * U::reference_length(),
+            sigma_ss,
+            epsilon_k_ss,
+            potential_cutoff,
+            cutoff_radius,
+        )
+    }
+
+    /// Read the solid structure from a LAMMPS data file.
+    ///
+    /// The data file has to be written in `real` units (Å and kcal/mol) with
+    /// LJ parameters for every atom type in the `Pair Coeffs` section. The
+    /// atom style (`atomic`, `charge`, `molecular` or `full`) is read from the
+    /// `Atoms # <style>` header. Without the hint, it is determined from the
+    /// number of columns, which is ambiguous for 6 and 9 columns. Tilt factors are converted into the
+    /// cell vectors of a triclinic unit cell. If the `Masses` section is
+    /// present, the masses are used to determine the mass of the solid.
+    pub fn from_lammps_data<P: AsRef<Path>>(
+        functional: &Rc<DFT<F>>,
+        path: P,
+        n_grid: [usize; 3],
+        potential_cutoff: Option<f64>,
+        cutoff_radius: Option<CutoffRadius<U>>,
+    ) -> EosResult<Self> {
+        let error = |e: String| EosError::Error(format!("Reading LAMMPS data file failed: {}", e));
+        let content = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+        let parse = |w: &str| {
+            w.parse::<f64>()
+                .map_err(|e| error(format!("{} ({})", e, w)))
+        };
+
+        let mut bounds = [[0.0; 2]; 3];
+        let mut tilt = [0.0; 3];
+        let mut pair_coeffs = HashMap::new();
+        let mut masses = HashMap::new();
+        let mut atoms = Vec::new();
+        let mut section = String::new();
+        let mut atom_style = None;
+        // the first line of a data file is a comment
+        for line in content.lines().skip(1) {
+            let (line, comment) = line.split_once('#').unwrap_or((line, ""));
+            let line = line.trim();
+            let words: Vec<_> = line.split_whitespace().collect();
+            match words[..] {
+                [] => (),
+                [lo, hi, "xlo", "xhi"] => bounds[0] = [parse(lo)?, parse(hi)?],
+                [lo, hi, "ylo", "yhi"] => bounds[1] = [parse(lo)?, parse(hi)?],
+                [lo, hi, "zlo", "zhi"] => bounds[2] = [parse(lo)?, parse(hi)?],
+                [xy, xz, yz, "xy", "xz", "yz"] => tilt = [parse(xy)?, parse(xz)?, parse(yz)?],
+                [w, ..] if w.parse::<f64>().is_err() => {
+                    section = line.to_string();
+                    if section == "Atoms" {
+                        atom_style = comment.split_whitespace().next().map(String::from);
+                    }
+                }
+                _ => match section.as_str() {
+                    "Pair Coeffs" => {
+                        if words.len() < 3 {
+                            return Err(error(format!("invalid pair coefficients '{}'", line)));
+                        }
+                        pair_coeffs
+                            .insert(words[0].to_string(), (parse(words[1])?, parse(words[2])?));
+                    }
+                    "Masses" => {
+                        if words.len() < 2 {
+                            return Err(error(format!("invalid mass '{}'", line)));
+                        }
+                        masses.insert(words[0].to_string(), parse(words[1])?);
+                    }
+                    "Atoms" => {
+                        // position of the atom type and the coordinates for
+                        // the atomic, charge, molecular, and full atom styles
+                        let (t, x) = match (atom_style.as_deref(), words.len()) {
+                            (Some("atomic"), _) => (1, 2),
+                            (Some("charge"), _) => (1, 3),
+                            (Some("molecular"), _) => (2, 3),
+                            (Some("full"), _) => (2, 4),
+                            (Some(style), _) => {
+                                return Err(error(format!("unsupported atom style '{}'", style)))
+                            }
+                            (None, 5 | 8) => (1, 2),
+                            (None, 7 | 10) => (2, 4),
+                            (None, n @ (6 | 9)) => {
+                                return Err(error(format!(
+                                    "ambiguous atom style with {} columns, add the style to the Atoms header",
+                                    n
+                                )))
+                            }
+                            (None, n) => {
+                                return Err(error(format!(
+                                    "unsupported atom style with {} columns",
+                                    n
+                                )))
+                            }
+                        };
+                        if words.len() < x + 3 {
+                            return Err(error(format!("invalid atom '{}'", line)));
+                        }
+                        atoms.push((
+                            words[t].to_string(),
+                            [parse(words[x])?, parse(words[x + 1])?, parse(words[x + 2])?],
+                        ));
+                    }
+                    _ => (),
+                },
+            }
+        }
+        if atoms.is_empty() {
+            return Err(error("no atoms found".into()));
+        }
+
+        // interaction sites relative to the lower bounds of the box
+        let n_sites = atoms.len();
+        let mut coordinates = Array2::zeros((3, n_sites));
+        let mut sigma_ss = Array1::zeros(n_sites);
+        let mut epsilon_k_ss = Array1::zeros(n_sites);
+        for (i, (atom_type, r)) in atoms.iter().enumerate() {
+            let (epsilon, sigma) = pair_coeffs.get(atom_type).ok_or_else(|| {
+                error(format!("no pair coefficients for atom type {}", atom_type))
+            })?;
+            for k in 0..3 {
+                coordinates[[k, i]] = r[k] - bounds[k][0];
+            }
+            sigma_ss[i] = *sigma;
+            epsilon_k_ss[i] = epsilon * KCAL_PER_MOL_IN_K;
+        }
+
+        let molar_masses = atoms
+            .iter()
+            .map(|(atom_type, _)| masses.get(atom_type).copied())
+            .collect::<Option<Array1<f64>>>();
+
+        let lengths = [0, 1, 2].map(|k| bounds[k][1] - bounds[k][0]);
+        let mut pore = Self::new(
+            functional,
+            lengths.map(|l| l * U::reference_length()),
+            n_grid,
+            coordinates * U::reference_length(),
+            sigma_ss,
+            epsilon_k_ss,
+            potential_cutoff,
+            cutoff_radius,
+        );
+        pore.molar_masses = molar_masses;
+        Ok(if tilt.iter().any(|&t| t != 0.0) {
+            let [xy, xz, yz] = tilt;
+            let cell = arr2(&[
+                [lengths[0], 0.0, 0.0],
+                [xy, lengths[1], 0.0],
+                [xz, yz, lengths[2]],
+            ]);
+            pore.cell_vectors(cell * U::reference_length())
+        } else {
+            pore
+        })
+    }
+}
+
+impl<U: EosUnit, F> Pore3D<U, F> {
+    /// Return the number of fluid molecules within `shell_cutoff` of any
+    /// interaction site of the solid, e.g., in the first solvation shell
+    /// around a solute.
+    pub fn coordination_number<G: HelmholtzEnergyFunctional>(
+        &self,
+        profile: &PoreProfile3D<U, G>,
+        shell_cutoff: QuantityScalar<U>,
+    ) -> EosResult<QuantityScalar<U>> {
+        let shell_cutoff = shell_cutoff.to_reduced(U::reference_length())?;
+        let axes = profile.profile.grid.axes();
+        let lengths = [0, 1, 2].map(|k| axes[k].length());
+        let (cell, cell_inv, coordinates) = self.reduced_solid(&profile.profile.grid)?;
+
+        // grid points within the cutoff of any interaction site
+        let cell_list = CellList::new(&coordinates, &cell_inv, shell_cutoff, self.periodic);
+        let shape = (axes[0].grid.len(), axes[1].grid.len(), axes[2].grid.len());
+        let mask = Array3::from_shape_fn(shape, |(ix, iy, iz)| {
+            let fractional = [
+                axes[0].grid[ix] / lengths[0],
+                axes[1].grid[iy] / lengths[1],
+                axes[2].grid[iz] / lengths[2],
+            ];
+            let point = [0, 1, 2].map(|c| (0..3).map(|k| fractional[k] * cell[[k, c]]).sum());
+            let sites = cell_list.neighbors(fractional);
+            calculate_distance2(point, &coordinates, &sites, &cell, &cell_inv, self.periodic)
+                .iter()
+                .any(|&d2| d2 <= shell_cutoff * shell_cutoff)
+        });
+        Ok(profile.profile.integrate_region(&mask))
+    }
+
+    /// Return the (reduced) cell vectors, their inverse and the coordinates of
+    /// the interaction sites as they are located on the grid of a profile.
+    fn reduced_solid(&self, grid: &Grid) -> EosResult<(Array2<f64>, Array2<f64>, Array2<f64>)> {
+        let axes = grid.axes();
+        let lengths = [0, 1, 2].map(|k| axes[k].length());
+        let cell = match grid {
+            Grid::Triclinic { cell, .. } => cell.clone(),
+            _ => Array2::from_diag(&arr1(&lengths)),
+        };
+        let cell_inv = cell
+            .inv()
+            .map_err(|_| EosError::Error("The cell vectors are linearly dependent.".into()))?;
+
+        // the solid is shifted by the vacuum padding of the profile
+        let mut shift = [0.0; 3];
+        if self.cell_vectors.is_none() {
+            for k in 0..3 {
+                shift[k] =
+                    0.5 * (lengths[k] - self.system_size[k].to_reduced(U::reference_length())?);
+            }
+        }
+        let mut coordinates = self.coordinates.to_reduced(U::reference_length())?;
+        for (mut r, &s) in coordinates.outer_iter_mut().zip(shift.iter()) {
+            r += s;
+        }
+        Ok((cell, cell_inv, coordinates))
+    }
+}
+
+impl<U: EosUnit, F: FluidParameters> Pore3D<U, F> {
+    /// Decompose the solid-fluid interaction energy of a converged profile
+    /// into the contributions $\sum_i\int\rho_i(\mathbf{r})V_{i\alpha}(\mathbf{r})\mathrm{d}\mathbf{r}$
+    /// of the individual interaction sites $\alpha$ of the solid.
+    ///
+    /// Where the external potential of the profile is clamped, the
+    /// contributions of all sites are scaled by the same factor, so that the
+    /// sum over all sites equals the total solid-fluid interaction energy
+    /// $\sum_i\int\rho_i(\mathbf{r})V_i^\mathrm{ext}(\mathbf{r})\mathrm{d}\mathbf{r}$.
+    /// Requires that the profile was initialized with the potential of this
+    /// pore and not with a user-supplied external potential.
+    pub fn energy_per_solid_atom<G: HelmholtzEnergyFunctional>(
+        &self,
+        profile: &PoreProfile3D<U, G>,
+    ) -> EosResult<QuantityArray1<U>> {
+        let profile = &profile.profile;
+        let t = profile.temperature.to_reduced(U::reference_temperature())?;
+        let density = profile.density.to_reduced(U::reference_density())?;
+        let external_potential = &profile.external_potential;
+        let axes = profile.grid.axes();
+        let weights = profile.grid.integration_weights();
+        let (cell, cell_inv, coordinates) = self.reduced_solid(&profile.grid)?;
+
+        let functional = &self.functional.functional;
+        let m = functional.m();
+        let sigma_ff = functional.sigma_ff();
+        let epsilon_k_ff = functional.epsilon_k_ff();
+        let (cutoff_radius2, lambda) = interaction_parameters(
+            functional,
+            &self.sigma_ss,
+            self.cutoff_radius,
+            self.quantum_correction,
+            t,
+        )?;
+        let inner_maximum = self.potential_form.inner_maximum();
+        let cell_list = CellList::new(
+            &coordinates,
+            &cell_inv,
+            cutoff_radius2.fold(0.0, |a: f64, &b| a.max(b)).sqrt(),
+            self.periodic,
+        );
+
+        let mut energy = Array1::zeros(coordinates.ncols());
+        for ((ix, iy, iz), _) in external_potential.index_axis(Axis_nd(0), 0).indexed_iter() {
+            let fractional = [
+                axes[0].grid[ix] / axes[0].length(),
+                axes[1].grid[iy] / axes[1].length(),
+                axes[2].grid[iz] / axes[2].length(),
+            ];
+            let point = [0, 1, 2].map(|c| (0..3).map(|k| fractional[k] * cell[[k, c]]).sum());
+            let sites = cell_list.neighbors(fractional);
+            let distance2 =
+                calculate_distance2(point, &coordinates, &sites, &cell, &cell_inv, self.periodic);
+            let w = weights[0][ix] * weights[1][iy] * weights[2][iz];
+            for i in 0..m.len() {
+                let rho_w = density[[i, ix, iy, iz]] * w;
+                if rho_w == 0.0 {
+                    continue;
+                }
+                let u = Array1::from_shape_fn(sites.len(), |j| {
+                    m[i] * evaluate(
+                        distance2[j],
+                        (self.sigma_ss[sites[j]] + sigma_ff[i]) / 2.0,
+                        (self.epsilon_k_ss[sites[j]] * epsilon_k_ff[i]).sqrt(),
+                        cutoff_radius2[i],
+                        lambda,
+                        self.potential_form,
+                        inner_maximum,
+                    ) / t
+                });
+                let v = external_potential[[i, ix, iy, iz]];
+                let u_sum = u.sum();
+                if u_sum == 0.0 {
+                    continue;
+                }
+                let u = u * (v / u_sum);
+                for (&alpha, &u) in sites.iter().zip(u.iter()) {
+                    energy[alpha] += rho_w * u;
+                }
+            }
+        }
+        Ok(energy * U::reference_moles() * U::gas_constant() * profile.temperature)
+    }
+}
+
+impl<F> Pore3D<SIUnit, F> {
+    /// Return the mass of the solid in the simulation box.
+    ///
+    /// Requires the elements of the interaction sites or the masses from
+    /// the LAMMPS data file.
+    pub fn solid_mass(&self) -> EosResult<SINumber> {
+        let molar_masses = self.molar_masses.as_ref().ok_or_else(|| {
+            EosError::Error("The masses of the interaction sites are not known.".into())
+        })?;
+        Ok(molar_masses.sum() * GRAM / MOL / NAV)
+    }
 }
 
 /// Trait for the generic implementation of adsorption applications.
@@ -88,6 +1065,42 @@ pub trait PoreSpecification<U, D: Dimension, F> {
         &self,
         bulk: &State<U, DFT<F>>,
         external_potential: Option<&Array<f64, D::Larger>>,
+    ) -> EosResult<PoreProfile<U, D, F>> {
+        self.initialize_with_convolver(bulk, external_potential, None)
+    }
+
+    /// Initialize a new single pore with the given initial guess for the
+    /// density profile, e.g., from a cheaper functional or a simulation.
+    fn initialize_with_density(
+        &self,
+        bulk: &State<U, DFT<F>>,
+        external_potential: Option<&Array<f64, D::Larger>>,
+        density: &QuantityArray<U, D::Larger>,
+    ) -> EosResult<PoreProfile<U, D, F>>
+    where
+        U: Clone,
+    {
+        let mut profile = self.initialize(bulk, external_potential)?;
+        if density.shape() != profile.profile.density.shape() {
+            return Err(EosError::Error(format!(
+                "The initial density has shape {:?}, but the grid requires {:?}.",
+                density.shape(),
+                profile.profile.density.shape()
+            )));
+        }
+        profile.profile.density = density.clone();
+        Ok(profile)
+    }
+
+    /// Initialize a new single pore using a precomputed convolver.
+    ///
+    /// The convolver only depends on the temperature and the grid and can
+    /// therefore be reused, e.g., for all points of an isotherm.
+    fn initialize_with_convolver(
+        &self,
+        bulk: &State<U, DFT<F>>,
+        external_potential: Option<&Array<f64, D::Larger>>,
+        convolver: Option<&Rc<dyn Convolver<f64, D>>>,
     ) -> EosResult<PoreProfile<U, D, F>>;
 }
 
@@ -95,11 +1108,32 @@ pub trait PoreSpecification<U, D: Dimension, F> {
 pub struct PoreProfile<U, D: Dimension, F> {
     pub profile: DFTProfile<U, D, F>,
     pub grand_potential: Option<QuantityScalar<U>>,
+    /// The excess grand potential $\Omega+pV$ of the resolved domain.
+    ///
+    /// The value is not normalized: for slit pores it refers to a single wall
+    /// (one half of the pore) per unit area, for cylindrical pores to the unit
+    /// length of the pore, and for spherical and 3D pores to the whole system.
+    /// It is negative if the fluid is attracted by the walls. Use
+    /// [PoreProfile::normalized_interfacial_tension] for other conventions.
     pub interfacial_tension: Option<QuantityScalar<U>>,
 }
 
+/// Normalization of the interfacial tension of a pore.
+#[derive(Clone, Copy)]
+pub enum InterfacialTensionConvention<U> {
+    /// The excess grand potential $\Omega+pV$ of the resolved domain.
+    Total,
+    /// The excess grand potential per unit wall area of a 1D pore.
+    WallArea,
+    /// The excess grand potential per given solid surface area, e.g., the
+    /// accessible surface area of a 3D solid.
+    SurfaceArea(QuantityScalar<U>),
+}
+
 /// Density profile and properties of a 1D confined system.
 pub type PoreProfile1D<U, F> = PoreProfile<U, Ix1, F>;
+/// Density profile and properties of a 2D confined system.
+pub type PoreProfile2D<U, F> = PoreProfile<U, Ix2, F>;
 /// Density profile and properties of a 3D confined system.
 pub type PoreProfile3D<U, F> = PoreProfile<U, Ix3, F>;
 
@@ -113,65 +1147,977 @@ impl<U: Copy, D: Dimension, F> Clone for PoreProfile<U, D, F> {
     }
 }
 
-impl<U: EosUnit, D: Dimension, F: HelmholtzEnergyFunctional> PoreProfile<U, D, F>
-where
-    D::Larger: Dimension<Smaller = D>,
-{
-    pub fn solve_inplace(&mut self, solver: Option<&DFTSolver>, debug: bool) -> EosResult<()> {
-        // Solve the profile
-        self.profile.solve(solver, debug)?;
+impl<U: EosUnit, D: Dimension, F: HelmholtzEnergyFunctional> PoreProfile<U, D, F>
+where
+    D::Larger: Dimension<Smaller = D>,
+{
+    pub fn solve_inplace(&mut self, solver: Option<&DFTSolver>, debug: bool) -> EosResult<()> {
+        // Solve the profile
+        self.profile.solve(solver, debug)?;
+
+        // calculate the excess grand potential relative to the bulk and
+        // correct it for the part of the grid that is not part of the pore
+        let omega_ex = self.profile.grid_excess_grand_potential()?;
+        let p = self.profile.bulk.pressure(Contributions::Total);
+        let grid_volume = self.profile.grid_volume();
+        self.grand_potential = Some(omega_ex - p * grid_volume);
+        self.interfacial_tension = Some(omega_ex - p * (grid_volume - self.profile.volume()));
+
+        Ok(())
+    }
+
+    pub fn solve(mut self, solver: Option<&DFTSolver>) -> EosResult<Self> {
+        self.solve_inplace(solver, false)?;
+        Ok(self)
+    }
+
+    /// Return the interfacial tension of the solved profile in the given convention.
+    pub fn normalized_interfacial_tension(
+        &self,
+        convention: InterfacialTensionConvention<U>,
+    ) -> EosResult<QuantityScalar<U>> {
+        let gamma = self
+            .interfacial_tension
+            .ok_or_else(|| EosError::Error("The profile has to be solved first.".into()))?;
+        match convention {
+            InterfacialTensionConvention::Total => Ok(gamma),
+            InterfacialTensionConvention::SurfaceArea(area) => Ok(gamma / area),
+            InterfacialTensionConvention::WallArea => match &self.profile.grid {
+                Grid::Cartesian1(_) => Ok(gamma),
+                _ => Ok(gamma / self.surface_area()?),
+            },
+        }
+    }
+
+    /// Return the volume of the pore.
+    ///
+    /// For slit pores, the volume of the whole pore (both halves) per unit
+    /// cross-sectional area is returned and for cylindrical pores the volume
+    /// per unit length. The potential offset is not included.
+    pub fn pore_volume(&self) -> QuantityScalar<U> {
+        match self.profile.grid {
+            Grid::Cartesian1(_) => 2.0 * self.profile.volume(),
+            _ => self.profile.volume(),
+        }
+    }
+
+    /// Return the wall area of the pore.
+    ///
+    /// Consistent with [PoreProfile::pore_volume], the result is given per
+    /// unit cross-sectional area for slit pores (i.e., 2 for both walls) and
+    /// per unit length for cylindrical pores. Only available for 1D pores.
+    pub fn surface_area(&self) -> EosResult<QuantityScalar<U>> {
+        match &self.profile.grid {
+            Grid::Cartesian1(_) => Ok(2.0 * U::reference_length().powi(0)),
+            Grid::Polar(r) => Ok(2.0 * PI * r.edges[r.grid.len()] * U::reference_length()),
+            Grid::Spherical(r) => {
+                Ok(4.0 * PI * (r.edges[r.grid.len()] * U::reference_length()).powi(2))
+            }
+            _ => Err(EosError::Error(
+                "The wall area is only defined for 1D pores. Provide a surface area instead."
+                    .into(),
+            )),
+        }
+    }
+
+    /// Solve the profile and return the partially converged profile together
+    /// with the error if the solver does not converge.
+    pub fn try_solve(mut self, solver: Option<&DFTSolver>) -> Result<Self, (Self, EosError)> {
+        match self.solve_inplace(solver, false) {
+            Ok(()) => Ok(self),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    /// Return the density profiles of the individual segments.
+    ///
+    /// For heterosegmented functionals, the rows correspond to the segments
+    /// and are mapped to components by `component_index` of the functional.
+    pub fn segment_density(&self) -> QuantityArray<U, D::Larger> {
+        self.profile.density.clone()
+    }
+
+    /// Return the adsorption selectivity of component `i` over component `j`,
+    /// i.e., $S_{ij}=\frac{N_i/N_j}{y_i/y_j}$ with the bulk mole fractions $y$.
+    pub fn selectivity(&self, i: usize, j: usize) -> EosResult<f64> {
+        let components = self.profile.dft.components();
+        if i >= components || j >= components {
+            return Err(EosError::Error(format!(
+                "Invalid components ({}, {}) for a system with {} components.",
+                i, j, components
+            )));
+        }
+        let moles = self.profile.moles();
+        let y = &self.profile.bulk.molefracs;
+        Ok(moles.get(i).to_reduced(moles.get(j))? * y[j] / y[i])
+    }
+
+    /// Return the local mole fractions of all components.
+    ///
+    /// At grid points where the total density is negligible compared to the
+    /// bulk density (e.g., inside a hard wall), the bulk composition is returned.
+    pub fn local_mole_fractions(&self) -> EosResult<Array<f64, D::Larger>> {
+        let rho = self.profile.component_densities_reduced();
+        let rho_total = rho.sum_axis(Axis_nd(0));
+        let rho_min = MIN_RELATIVE_DENSITY
+            * self
+                .profile
+                .bulk
+                .density
+                .to_reduced(U::reference_density())?;
+        let x_bulk = &self.profile.bulk.molefracs;
+        let mut x = rho;
+        for (mut x_i, &x_bulk_i) in x.outer_iter_mut().zip(x_bulk.iter()) {
+            Zip::from(&mut x_i).and(&rho_total).for_each(|x, &r| {
+                *x = if r > rho_min { *x / r } else { x_bulk_i };
+            });
+        }
+        Ok(x)
+    }
+
+    /// Return the grand potential density split into the contributions of
+    /// the individual components in reduced units.
+    ///
+    /// The ideal gas and external potential parts are attributed to the
+    /// components directly, the residual Helmholtz energy density according
+    /// to the local segment fractions. The sum over all components equals
+    /// [DFTProfile::grand_potential_density].
+    pub fn grand_potential_density_components(&self) -> EosResult<Array<f64, D::Larger>> {
+        let t = self
+            .profile
+            .temperature
+            .to_reduced(U::reference_temperature())?;
+        let rho = self.profile.density.to_reduced(U::reference_density())?;
+        let omega_segments = self.profile.dft.grand_potential_density_segments_reduced(
+            t,
+            &rho,
+            &self.profile.convolver,
+        )? * t;
+        let mut d = rho.raw_dim();
+        d[0] = self.profile.dft.components();
+        let mut omega = Array::zeros(d);
+        for (omega_s, &j) in omega_segments
+            .outer_iter()
+            .zip(self.profile.dft.component_index.iter())
+        {
+            let mut omega_j = omega.index_axis_mut(Axis_nd(0), j);
+            omega_j += &omega_s;
+        }
+        Ok(omega)
+    }
+
+    /// Return the local compressibility $\chi_\alpha(\mathbf{r})=\frac{\partial\rho_\alpha(\mathbf{r})}{\partial\beta\mu}$
+    /// of every segment in reduced units.
+    ///
+    /// The derivative is calculated by central finite differences, for which
+    /// the profile is solved again with the chemical potentials of all
+    /// components shifted by $\pm10^{-3}k_\mathrm{B}T$. The truncation error is
+    /// of second order in the step size, so the accuracy is mostly limited by
+    /// the tolerance of the solver.
+    pub fn local_compressibility(
+        &self,
+        solver: Option<&DFTSolver>,
+    ) -> EosResult<Array<f64, D::Larger>> {
+        let temperature = self.profile.temperature;
+        let mu = &self.profile.chemical_potential;
+        let delta_mu = DELTA_MU_COMPRESSIBILITY * U::gas_constant() * temperature;
+        let solve_shifted = |shift: f64| -> EosResult<Array<f64, D::Larger>> {
+            let mu_shifted =
+                QuantityArray1::from_shape_fn(mu.len(), |i| mu.get(i) + shift * delta_mu);
+            let mut bulk = self.profile.bulk.clone();
+            bulk.update_chemical_potential(&mu_shifted)?;
+            let profile = self.clone().update_bulk(&bulk).solve(solver)?;
+            profile.profile.density.to_reduced(U::reference_density())
+        };
+        let rho_plus = solve_shifted(1.0)?;
+        let rho_minus = solve_shifted(-1.0)?;
+        Ok((rho_plus - rho_minus) / (2.0 * DELTA_MU_COMPRESSIBILITY))
+    }
+
+    /// Return the reduced functional derivative of the residual Helmholtz
+    /// energy functional for every segment (see [DFTProfile::functional_derivative]).
+    ///
+    /// For a converged profile of a simple fluid, the sum of the functional
+    /// derivative, the logarithm of the density, and the reduced external
+    /// potential is constant and equal to the reduced chemical potential.
+    pub fn functional_derivative(&self) -> EosResult<Array<f64, D::Larger>> {
+        self.profile.functional_derivative()
+    }
+
+    /// Return the weighted densities of every functional contribution
+    /// together with the name of the contribution.
+    pub fn weighted_densities(&self) -> EosResult<Vec<(String, Array<f64, D::Larger>)>> {
+        self.profile.named_weighted_densities()
+    }
+
+    /// Return the fraction of non-bonded association sites, e.g., to analyze
+    /// the depletion of hydrogen bonds at a hydrophobic wall.
+    ///
+    /// Returns `None` for functionals without association.
+    pub fn association_fraction(&self) -> EosResult<Option<Array<f64, D::Larger>>> {
+        self.profile.association_fraction()
+    }
+
+    /// Check the local stability of the profile.
+    ///
+    /// The profile is stable, if the Hessian of the grand potential is positive
+    /// definite. A smallest eigenvalue close to zero indicates a spinodal.
+    pub fn is_stable(&self) -> EosResult<bool> {
+        Ok(self.profile.smallest_hessian_eigenvalue()? > 0.0)
+    }
+
+    /// Return a mask that is true for all grid points that are accessible
+    /// to at least one segment, i.e., that are iterated by the solver.
+    pub fn accessible_mask(&self) -> Array<bool, D> {
+        self.profile
+            .external_potential
+            .map_axis(Axis_nd(0), |v| v.iter().any(|&v| is_accessible(v)))
+    }
+
+    /// Return the grand potential of the pore relative to the grand potential
+    /// of the bulk fluid in the same volume, i.e., $\Omega+pV$.
+    ///
+    /// This is the value of [PoreProfile::interfacial_tension] and only
+    /// available for solved profiles.
+    pub fn excess_grand_potential(&self) -> Option<QuantityScalar<U>> {
+        self.interfacial_tension
+    }
+
+    /// Return the chemical potential of every component in equilibrium with the profile.
+    pub fn chemical_potential(&self) -> QuantityArray1<U> {
+        self.profile.chemical_potential.clone()
+    }
+
+    /// Return the fugacity
+    /// $f_i=\rho_i^\mathrm{b}RT\exp\left(\frac{\mu_i^\mathrm{res}}{RT}\right)$
+    /// of every component in the bulk fluid.
+    ///
+    /// The fugacities are equal in all bulk phases, so that, in contrast to
+    /// the pressure, no phase split of the bulk mixture is required.
+    pub fn bulk_fugacity(&self) -> QuantityArray1<U> {
+        let bulk = &self.profile.bulk;
+        let t = bulk.temperature;
+        let mu_res = bulk.chemical_potential(Contributions::Residual);
+        QuantityArray1::from_shape_fn(mu_res.len(), |i| {
+            let ln_phi = mu_res.get(i).to_reduced(U::gas_constant() * t).unwrap();
+            bulk.partial_density.get(i) * U::gas_constant() * t * ln_phi.exp()
+        })
+    }
+
+    /// Replace the bulk state and chemical potential of the profile.
+    ///
+    /// The external potential and the convolver are not updated, so the
+    /// temperature of `bulk` has to match the temperature of the profile. Use
+    /// [PoreProfile::update_temperature] to change the temperature.
+    pub fn update_bulk(mut self, bulk: &State<U, DFT<F>>) -> Self {
+        if bulk
+            .temperature
+            .to_reduced(self.profile.temperature)
+            .map_or(true, |t| (t - 1.0).abs() > TOL_TEMPERATURE)
+        {
+            warn!("The temperature of the bulk state differs from the temperature of the pore profile. The external potential is not updated, use update_temperature instead.");
+        }
+        self.profile.bulk = bulk.clone();
+        self.profile.chemical_potential = bulk.chemical_potential(Contributions::Total);
+        self.grand_potential = None;
+        self.interfacial_tension = None;
+        self
+    }
+
+    /// Change the bulk state of the profile including its temperature.
+    ///
+    /// The external potential and the weight functions are recalculated at
+    /// the new temperature from the pore specification (and the optional
+    /// external potential) that was used to initialize the profile. The
+    /// current density profile is kept as initial guess.
+    pub fn update_temperature<P: PoreSpecification<U, D, F>>(
+        self,
+        pore: &P,
+        bulk: &State<U, DFT<F>>,
+        external_potential: Option<&Array<f64, D::Larger>>,
+    ) -> EosResult<Self> {
+        pore.initialize_with_density(bulk, external_potential, &self.profile.density)
+    }
+}
+
+impl<U: EosUnit, D: Dimension + RemoveAxis + 'static, F: HelmholtzEnergyFunctional>
+    PoreProfile<U, D, F>
+where
+    D::Larger: Dimension<Smaller = D>,
+    D::Smaller: Dimension<Larger = D>,
+    <D::Larger as Dimension>::Larger: Dimension<Smaller = D::Larger>,
+{
+    /// Solve the profile on a sequence of successively finer grids.
+    ///
+    /// The profile is coarsened `levels - 1` times by `factor` (see
+    /// [DFTProfile::coarsen]). Starting from the coarsest grid, every converged
+    /// profile is interpolated to the next finer grid as initial guess. The final
+    /// profile is solved on the original grid, for which the number of grid points
+    /// along every axis has to be divisible by `factor.pow(levels - 1)`.
+    pub fn solve_multigrid(
+        &mut self,
+        levels: usize,
+        factor: usize,
+        solver: Option<&DFTSolver>,
+    ) -> EosResult<()> {
+        let mut coarse = Vec::with_capacity(levels.saturating_sub(1));
+        for l in 1..levels {
+            let finer = coarse.last().unwrap_or(&self.profile);
+            let profile = finer.coarsen(factor)?;
+            info!(
+                "Multigrid level {}: {:?} grid points.",
+                l,
+                profile
+                    .grid
+                    .grids()
+                    .iter()
+                    .map(|g| g.len())
+                    .collect::<Vec<_>>()
+            );
+            coarse.push(profile);
+        }
+
+        let mut previous: Option<DFTProfile<U, D, F>> = None;
+        for mut profile in coarse.into_iter().rev() {
+            if let Some(previous) = &previous {
+                profile.interpolate_density(previous)?;
+            }
+            profile.solve(solver, false)?;
+            previous = Some(profile);
+        }
+        if let Some(previous) = &previous {
+            self.profile.interpolate_density(previous)?;
+        }
+        self.solve_inplace(solver, false)
+    }
+
+    /// Return the enthalpy of immersion, i.e., the internal energy of the
+    /// profile relative to the internal energy of the bulk fluid in the same
+    /// volume.
+    ///
+    /// The profile should be in equilibrium with a bulk liquid. For slit pores,
+    /// the result is given per unit wall area.
+    pub fn enthalpy_of_immersion(&self) -> EosResult<QuantityScalar<U>> {
+        let bulk = &self.profile.bulk;
+        let u_bulk = bulk.molar_internal_energy(Contributions::Total) * bulk.density;
+        Ok(self.profile.internal_energy(Contributions::Total)? - u_bulk * self.profile.volume())
+    }
+
+    /// Solve the profile starting from the current density and from pores
+    /// filled with the bulk vapor and the bulk liquid at the same temperature
+    /// and pressure, and return all distinct solutions
+    /// ordered by their grand potential.
+    ///
+    /// Solutions whose relative [DFTProfile::l2_difference] is below 1e-4
+    /// are considered identical. The flag is true for the solution with the
+    /// lowest grand potential (the stable one), all other solutions are
+    /// metastable. Initial guesses that do not converge are skipped.
+    pub fn solve_all_branches(&self, solver: Option<&DFTSolver>) -> EosResult<Vec<(Self, bool)>> {
+        let bulk = &self.profile.bulk;
+        let initial = |partial_density: &QuantityArray1<U>| -> EosResult<Self> {
+            let rho = partial_density.to_reduced(U::reference_density())?;
+            let mut density = self
+                .profile
+                .external_potential
+                .mapv(|v| (-v).exp().min(1.0));
+            for (mut d, &c) in density
+                .outer_iter_mut()
+                .zip(self.profile.dft.component_index.iter())
+            {
+                d *= rho[c];
+            }
+            let mut profile = self.clone();
+            profile.profile.density = density * U::reference_density();
+            Ok(profile)
+        };
+        let mut guesses = vec![self.clone()];
+        let moles = &bulk.molefracs * U::reference_moles();
+        let vapor = StateBuilder::new(&self.profile.dft)
+            .temperature(bulk.temperature)
+            .pressure(bulk.pressure(Contributions::Total))
+            .moles(&moles)
+            .vapor()
+            .build();
+        if let Ok(vapor) = vapor {
+            guesses.push(initial(&vapor.partial_density)?);
+        }
+        let liquid = StateBuilder::new(&self.profile.dft)
+            .temperature(bulk.temperature)
+            .pressure(bulk.pressure(Contributions::Total))
+            .moles(&moles)
+            .liquid()
+            .build();
+        if let Ok(liquid) = liquid {
+            guesses.push(initial(&liquid.partial_density)?);
+        }
+
+        // solve all initial guesses and discard duplicates
+        let mut solutions: Vec<Self> = Vec::new();
+        let mut error = None;
+        for guess in guesses {
+            match guess.solve(solver) {
+                Ok(profile) => {
+                    let mut distinct = true;
+                    for s in &solutions {
+                        if s.profile.l2_difference(&profile.profile)? < TOL_DISTINCT_PROFILES {
+                            distinct = false;
+                        }
+                    }
+                    if distinct {
+                        solutions.push(profile);
+                    }
+                }
+                Err(e) => error = Some(e),
+            }
+        }
+        if solutions.is_empty() {
+            return Err(error.unwrap());
+        }
+
+        solutions.sort_by(|a, b| {
+            a.grand_potential
+                .partial_cmp(&b.grand_potential)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        Ok(solutions
+            .into_iter()
+            .enumerate()
+            .map(|(i, s)| (s, i == 0))
+            .collect())
+    }
+
+    /// Calculate the differential entropy of adsorption
+    /// $\Delta s=\left(\frac{\partial S}{\partial N}\right)_{T,V}-s^\mathrm{b}$
+    /// relative to the molar entropy of the bulk fluid.
+    ///
+    /// The entropy of the profile is obtained from the temperature derivative
+    /// of the Helmholtz energy functional. The derivative with respect to the
+    /// loading is calculated by central finite differences in the chemical
+    /// potential as in [PoreProfile::local_compressibility]. Only available
+    /// for pure components.
+    pub fn differential_entropy(&self, solver: Option<&DFTSolver>) -> EosResult<QuantityScalar<U>> {
+        if self.profile.dft.components() > 1 {
+            return Err(EosError::Error(
+                "The differential entropy is only available for pure components.".into(),
+            ));
+        }
+        let temperature = self.profile.temperature;
+        let mu = &self.profile.chemical_potential;
+        let delta_mu = DELTA_MU_COMPRESSIBILITY * U::gas_constant() * temperature;
+        let solve_shifted = |shift: f64| -> EosResult<(QuantityScalar<U>, QuantityScalar<U>)> {
+            let mu_shifted =
+                QuantityArray1::from_shape_fn(mu.len(), |i| mu.get(i) + shift * delta_mu);
+            let mut bulk = self.profile.bulk.clone();
+            bulk.update_chemical_potential(&mu_shifted)?;
+            let profile = self.clone().update_bulk(&bulk).solve(solver)?;
+            Ok((
+                profile.profile.entropy(Contributions::Total)?,
+                profile.profile.total_moles(),
+            ))
+        };
+        let (s_plus, n_plus) = solve_shifted(1.0)?;
+        let (s_minus, n_minus) = solve_shifted(-1.0)?;
+        let s_bulk = self.profile.bulk.molar_entropy(Contributions::Total);
+        Ok((s_plus - s_minus) / (n_plus - n_minus) - s_bulk)
+    }
+}
+
+/// Deviations of a converged pore profile from the pressure sum rules.
+pub struct SumRuleReport<U> {
+    /// Pressure of the bulk phase.
+    pub bulk_pressure: QuantityScalar<U>,
+    /// Pressure from the grand potential density in the center of the pore.
+    pub grand_potential_pressure: QuantityScalar<U>,
+    /// Relative deviation of the grand potential route from the bulk pressure.
+    pub grand_potential_deviation: f64,
+    /// Normal pressure from the wall sum rule (only for slit pores).
+    pub wall_pressure: Option<QuantityScalar<U>>,
+    /// Relative deviation of the wall sum rule from the bulk pressure.
+    pub wall_deviation: Option<f64>,
+    /// Suggested number of grid points, if a deviation exceeds the tolerance.
+    pub suggested_n_grid: Option<usize>,
+}
+
+/// Density at the wall and first density peak of a component in a 1D pore.
+pub struct ContactSummary<U> {
+    /// Density extrapolated to the edge of the region accessible to the fluid.
+    pub contact_density: QuantityScalar<U>,
+    /// Density in the first peak next to the wall.
+    pub peak_density: QuantityScalar<U>,
+    /// Distance of the first peak from the wall.
+    pub peak_distance: QuantityScalar<U>,
+}
+
+impl<U: EosUnit, F: HelmholtzEnergyFunctional> PoreProfile1D<U, F> {
+    /// Check the converged profile against the pressure sum rules.
+    ///
+    /// The grand potential route is evaluated in the center of the pore. For
+    /// slit pores, the normal pressure is additionally calculated from the
+    /// wall sum rule (the contact theorem for hard walls). Both routes only
+    /// reproduce the bulk pressure in pores that are wide enough to develop
+    /// a bulk-like region in their center.
+    pub fn validate_sum_rules(&self) -> EosResult<SumRuleReport<U>> {
+        let profile = &self.profile;
+        let bulk_pressure = profile.bulk.pressure(Contributions::Total);
+        let p_bulk = bulk_pressure.to_reduced(U::reference_pressure())?;
+
+        // grand potential route
+        let grand_potential_pressure = profile.local_pressure()?.get(0);
+        let grand_potential_deviation =
+            (grand_potential_pressure.to_reduced(U::reference_pressure())? / p_bulk - 1.0).abs();
+
+        // wall sum rule
+        let (wall_pressure, wall_deviation) = match profile.grid {
+            Grid::Cartesian1(_) => {
+                let p_wall = self.wall_sum_rule()?;
+                (
+                    Some(p_wall * U::reference_pressure()),
+                    Some((p_wall / p_bulk - 1.0).abs()),
+                )
+            }
+            _ => (None, None),
+        };
+
+        // suggest a finer grid if the sum rules are violated
+        let deviation = wall_deviation.map_or(grand_potential_deviation, |d| {
+            d.max(grand_potential_deviation)
+        });
+        let suggested_n_grid = if deviation > SUM_RULE_TOLERANCE {
+            Some(2 * profile.grid.grids()[0].len())
+        } else {
+            None
+        };
+
+        Ok(SumRuleReport {
+            bulk_pressure,
+            grand_potential_pressure,
+            grand_potential_deviation,
+            wall_pressure,
+            wall_deviation,
+            suggested_n_grid,
+        })
+    }
+
+    /// Reduced normal pressure on the wall of a slit pore from the wall sum rule.
+    fn wall_sum_rule(&self) -> EosResult<f64> {
+        // p = sum_i int rho_i dV_i/dz dz, evaluated with the smooth function
+        // y_i = rho_i exp(V_i) to be robust against steep (hard) walls
+        let profile = &self.profile;
+        let t = profile.temperature.to_reduced(U::reference_temperature())?;
+        let rho = profile.density.to_reduced(U::reference_density())?;
+        let exp_v = profile.external_potential.mapv(|v| (-v).exp());
+        let y = Zip::from(&rho)
+            .and(&exp_v)
+            .map_collect(|&r, &e| if e > 0.0 { r / e } else { 0.0 });
+        let n = rho.shape()[1];
+        Ok(t * (0..rho.shape()[0])
+            .map(|i| {
+                (0..n - 1)
+                    .map(|k| {
+                        0.5 * (y[[i, k]] + y[[i, k + 1]]) * (exp_v[[i, k]] - exp_v[[i, k + 1]])
+                    })
+                    .sum::<f64>()
+            })
+            .sum::<f64>())
+    }
+
+    /// Return the normal and the tangential component of the pressure tensor
+    /// in a slit pore.
+    ///
+    /// The normal component $p_\mathrm{N}$ is constant across the pore and is
+    /// calculated from the wall sum rule, i.e., the force per area exerted by the
+    /// fluid on the wall. Its difference to the bulk pressure is the solvation
+    /// pressure. The tangential component $p_\mathrm{T}(z)=-\omega(z)$ follows
+    /// from the grand potential density of the planar profile.
+    pub fn pressure_tensor(&self) -> EosResult<(QuantityScalar<U>, QuantityArray1<U>)> {
+        match self.profile.grid {
+            Grid::Cartesian1(_) => Ok((
+                self.wall_sum_rule()? * U::reference_pressure(),
+                self.profile.local_pressure()?,
+            )),
+            _ => Err(EosError::Error(
+                "The pressure tensor is only available for slit pores.".into(),
+            )),
+        }
+    }
+
+    /// Return the interfacial tension per unit wall area, i.e., the excess
+    /// grand potential divided by the area of a single wall for slit pores and
+    /// by the area of the curved wall for cylindrical and spherical pores.
+    pub fn interfacial_tension_per_area(&self) -> EosResult<QuantityScalar<U>> {
+        self.normalized_interfacial_tension(InterfacialTensionConvention::WallArea)
+    }
+
+    /// Return the solid-fluid interfacial tension, i.e., the excess grand
+    /// potential per unit wall area.
+    ///
+    /// Only available for solved profiles in slit pores. Because only one
+    /// half of the pore is resolved, the excess grand potential of the profile
+    /// already corresponds to a single wall.
+    pub fn solid_fluid_interfacial_tension(&self) -> EosResult<QuantityScalar<U>> {
+        match self.profile.grid {
+            Grid::Cartesian1(_) => self
+                .excess_grand_potential()
+                .ok_or_else(|| EosError::Error("The profile has to be solved first.".into())),
+            _ => Err(EosError::Error(
+                "The solid-fluid interfacial tension is only available for slit pores.".into(),
+            )),
+        }
+    }
+
+    /// Return the mean distance from the wall and the variance of the excess
+    /// density of the given component.
+    ///
+    /// The excess density is the density profile minus the bulk density, the
+    /// first moment can be interpreted as the effective thickness of an adsorbed
+    /// film. For slit pores, only one half of the (symmetric) pore is resolved
+    /// on the grid and thus analyzed. An error is returned if the excess of the
+    /// component vanishes, e.g., at bulk-like conditions.
+    pub fn film_moments(
+        &self,
+        component: usize,
+    ) -> EosResult<(QuantityScalar<U>, QuantityScalar<U>)> {
+        let profile = &self.profile;
+        let components = profile.dft.components();
+        if component >= components {
+            return Err(EosError::Error(format!(
+                "Component {} does not exist in a system with {} components.",
+                component, components
+            )));
+        }
+        let axis = profile.grid.axes()[0];
+        let weights = profile.grid.integration_weights()[0];
+        let n = axis.grid.len();
+
+        let wall = wall_position::<U>(axis)?;
+
+        // excess density of the component
+        let rho = profile.density.to_reduced(U::reference_density())?;
+        let rho_bulk = profile
+            .bulk
+            .partial_density
+            .to_reduced(U::reference_density())?;
+        let mut excess = Array1::zeros(n);
+        for (s, &c) in profile.dft.component_index.iter().enumerate() {
+            if c == component {
+                excess += &(&rho.index_axis(Axis_nd(0), s) - rho_bulk[c]);
+            }
+        }
+
+        // moments of the excess density inside the pore
+        let (mut m0, mut m1, mut m2) = (0.0, 0.0, 0.0);
+        for ((&z, &w), &drho) in axis.grid.iter().zip(weights.iter()).zip(excess.iter()) {
+            let d = wall - z;
+            if d >= 0.0 {
+                m0 += w * drho;
+                m1 += w * drho * d;
+                m2 += w * drho * d * d;
+            }
+        }
+        if m0.abs() < MIN_FILM_EXCESS {
+            return Err(EosError::Error(format!(
+                "The excess of component {} vanishes, its film moments are undefined.",
+                component
+            )));
+        }
+        let mean = m1 / m0;
+        let variance = m2 / m0 - mean * mean;
+
+        Ok((
+            mean * U::reference_length(),
+            variance * U::reference_length().powi(2),
+        ))
+    }
+
+    /// Return the contact density and the first density peak of every component.
+    ///
+    /// The contact density is extrapolated linearly from the first two grid
+    /// points that are accessible to the fluid (i.e., where the external potential
+    /// is finite). The first peak is the first local maximum of the density
+    /// starting from the wall.
+    pub fn contact_profile_summary(&self) -> EosResult<Vec<ContactSummary<U>>> {
+        let profile = &self.profile;
+        let axis = profile.grid.axes()[0];
+        let z = &axis.grid;
+        let n = z.len();
+        let wall = wall_position::<U>(axis)?;
+        let rho = profile.density.to_reduced(U::reference_density())?;
+
+        (0..profile.dft.components())
+            .map(|c| {
+                // density of the component and grid points accessible to all its segments
+                let mut rho_c = Array1::zeros(n);
+                let mut accessible = Array1::from_elem(n, true);
+                for (s, &i) in profile.dft.component_index.iter().enumerate() {
+                    if i == c {
+                        rho_c += &rho.index_axis(Axis_nd(0), s);
+                        Zip::from(&mut accessible)
+                            .and(profile.external_potential.index_axis(Axis_nd(0), s))
+                            .for_each(|a, &v| *a &= is_accessible(v));
+                    }
+                }
+
+                // accessible grid points inside the pore, starting from the wall
+                let points: Vec<_> = (0..n)
+                    .rev()
+                    .filter(|&k| z[k] <= wall && accessible[k])
+                    .collect();
+                if points.len() < 3 {
+                    return Err(EosError::Error(format!(
+                        "Not enough accessible grid points for component {}.",
+                        c
+                    )));
+                }
+
+                // linear extrapolation to the edge of the accessible region
+                let (k0, k1) = (points[0], points[1]);
+                let edge = if k0 + 1 < n && z[k0 + 1] <= wall {
+                    0.5 * (z[k0] + z[k0 + 1])
+                } else {
+                    wall
+                };
+                let contact_density = (rho_c[k0]
+                    + (rho_c[k0] - rho_c[k1]) / (z[k0] - z[k1]) * (edge - z[k0]))
+                    .max(0.0);
+
+                // first local maximum of the density
+                let peak = (1..points.len() - 1)
+                    .find(|&i| {
+                        rho_c[points[i]] >= rho_c[points[i - 1]]
+                            && rho_c[points[i]] > rho_c[points[i + 1]]
+                    })
+                    .map_or(k0, |i| points[i]);
+
+                Ok(ContactSummary {
+                    contact_density: contact_density * U::reference_density(),
+                    peak_density: rho_c[peak] * U::reference_density(),
+                    peak_distance: (wall - z[peak]) * U::reference_length(),
+                })
+            })
+            .collect()
+    }
+
+    /// Return a mask that is true for all grid points within the given
+    /// distance from the wall, e.g., for [DFTProfile::integrate_region].
+    pub fn wall_distance_mask(&self, distance: QuantityScalar<U>) -> EosResult<Array1<bool>> {
+        let axis = self.profile.grid.axes()[0];
+        let wall = wall_position::<U>(axis)?;
+        let distance = distance.to_reduced(U::reference_length())?;
+        Ok(axis.grid.mapv(|z| (0.0..=distance).contains(&(wall - z))))
+    }
+
+    /// Return the number of fluid molecules in the first layer at the wall.
+    ///
+    /// The layer extends from the wall to `shell_cutoff` or, by default, to
+    /// the first minimum of the total density after the first peak. The
+    /// result is given per unit area of one wall for slit pores and per unit
+    /// length for cylindrical pores.
+    pub fn coordination_number(
+        &self,
+        shell_cutoff: Option<QuantityScalar<U>>,
+    ) -> EosResult<QuantityScalar<U>> {
+        let shell_cutoff = match shell_cutoff {
+            Some(shell_cutoff) => shell_cutoff,
+            None => {
+                let axis = self.profile.grid.axes()[0];
+                let z = &axis.grid;
+                let wall = wall_position::<U>(axis)?;
+                let rho = self
+                    .profile
+                    .component_densities_reduced()
+                    .sum_axis(Axis_nd(0));
+
+                // first minimum after the first peak, starting from the wall
+                let points: Vec<_> = (0..z.len())
+                    .rev()
+                    .filter(|&k| z[k] <= wall && rho[k] > 0.0)
+                    .collect();
+                let peak = (1..points.len().saturating_sub(1)).find(|&i| {
+                    rho[points[i]] >= rho[points[i - 1]] && rho[points[i]] > rho[points[i + 1]]
+                });
+                let minimum = peak.and_then(|p| {
+                    (p + 1..points.len() - 1).find(|&i| {
+                        rho[points[i]] <= rho[points[i - 1]] && rho[points[i]] < rho[points[i + 1]]
+                    })
+                });
+                match minimum {
+                    Some(i) => (wall - z[points[i]]) * U::reference_length(),
+                    None => {
+                        return Err(EosError::Error(
+                            "The density profile has no minimum after the first peak.".into(),
+                        ))
+                    }
+                }
+            }
+        };
+        Ok(self
+            .profile
+            .integrate_region(&self.wall_distance_mask(shell_cutoff)?))
+    }
+}
+
+impl<U: EosUnit, F: HelmholtzEnergyFunctional> PoreProfile3D<U, F> {
+    /// Label the disconnected pores of the solid.
+    ///
+    /// Accessible grid points (see [PoreProfile::accessible_mask]) that share
+    /// a face belong to the same pore. Consistent with the convolutions, pores
+    /// are only connected across the faces of the grid for periodic grids.
+    /// Returns the labels
+    /// (0 for inaccessible points and 1 to $n$ for the pores) and the number
+    /// of pores $n$.
+    pub fn pore_labels(&self) -> (Array3<usize>, usize) {
+        let accessible = self.accessible_mask();
+        let (n0, n1, n2) = accessible.dim();
+        let periodic = matches!(
+            self.profile.grid,
+            Grid::Periodical3(_, _, _) | Grid::Triclinic { .. }
+        );
+        // neighbor of index i on an axis with n points, if it exists
+        let next = |i: usize, n: usize| (i + 1 < n || periodic).then(|| (i + 1) % n);
+        let prev = |i: usize, n: usize| (i > 0 || periodic).then(|| (i + n - 1) % n);
+        let mut labels = Array3::zeros(accessible.raw_dim());
+        let mut n_pores = 0;
+        let mut queue = VecDeque::new();
+        for (start, &a) in accessible.indexed_iter() {
+            if !a || labels[start] != 0 {
+                continue;
+            }
+            // flood fill of the pore that contains the starting point
+            n_pores += 1;
+            labels[start] = n_pores;
+            queue.push_back(start);
+            while let Some((i, j, k)) = queue.pop_front() {
+                let neighbors = [
+                    next(i, n0).map(|i| (i, j, k)),
+                    prev(i, n0).map(|i| (i, j, k)),
+                    next(j, n1).map(|j| (i, j, k)),
+                    prev(j, n1).map(|j| (i, j, k)),
+                    next(k, n2).map(|k| (i, j, k)),
+                    prev(k, n2).map(|k| (i, j, k)),
+                ];
+                for n in neighbors.into_iter().flatten() {
+                    if accessible[n] && labels[n] == 0 {
+                        labels[n] = n_pores;
+                        queue.push_back(n);
+                    }
+                }
+            }
+        }
+        (labels, n_pores)
+    }
 
-        // calculate grand potential density
-        let omega = self
-            .profile
-            .integrate(&self.profile.dft.grand_potential_density(
-                self.profile.temperature,
-                &self.profile.density,
-                &self.profile.convolver,
-            )?);
-        self.grand_potential = Some(omega);
+    /// Return the number of moles of every component in each pore.
+    ///
+    /// The result has the shape (components, pores), with the pores labeled
+    /// as in [PoreProfile3D::pore_labels].
+    pub fn loading_by_pore(&self) -> QuantityArray2<U> {
+        let (labels, n_pores) = self.pore_labels();
+        let rho = self.profile.component_densities_reduced();
+        QuantityArray2::from_shape_fn((rho.shape()[0], n_pores), |(c, p)| {
+            let rho_p = Zip::from(rho.index_axis(Axis_nd(0), c))
+                .and(&labels)
+                .map_collect(|&r, &l| if l == p + 1 { r } else { 0.0 });
+            self.profile.integrate(&(rho_p * U::reference_density()))
+        })
+    }
 
-        // calculate interfacial tension
-        self.interfacial_tension =
-            Some(omega + self.profile.bulk.pressure(Contributions::Total) * self.profile.volume());
+    /// Calculate the in-plane structure factor of the confined fluid.
+    ///
+    /// The segment densities are integrated along `plane_axis` (the direction
+    /// normal to the plane) and the structure factor
+    /// $S(q)=\frac{1}{N}\left|\int n(\mathbf{r})e^{-i\mathbf{q}\cdot\mathbf{r}}\mathrm{d}A\right|^2$
+    /// of the resulting areal density $n$ is averaged over shells of $|\mathbf{q}|$.
+    /// The forward scattering at $q=0$ is omitted and the in-plane axes are
+    /// assumed to be orthogonal.
+    ///
+    /// Returns the reduced wave numbers and the structure factor.
+    pub fn structure_factor(&self, plane_axis: usize) -> EosResult<(Array1<f64>, Array1<f64>)> {
+        if plane_axis > 2 {
+            return Err(EosError::Error(format!(
+                "The plane axis has to be 0, 1, or 2, but {} was given.",
+                plane_axis
+            )));
+        }
+        let density = self
+            .profile
+            .density
+            .to_reduced(U::reference_density())?
+            .sum_axis(Axis_nd(0));
+        let axes = self.profile.grid.axes();
+        let weights = self.profile.grid.integration_weights();
+        let areal = density.map_axis(Axis_nd(plane_axis), |rho| {
+            (&rho * weights[plane_axis]).sum()
+        });
+        let (l1, l2) = match plane_axis {
+            0 => (axes[1].length(), axes[2].length()),
+            1 => (axes[0].length(), axes[2].length()),
+            _ => (axes[0].length(), axes[1].length()),
+        };
+        let (n1, n2) = areal.dim();
+        let area_element = l1 * l2 / (n1 * n2) as f64;
+        let moles = areal.sum() * area_element;
 
-        Ok(())
-    }
+        // 2D Fourier transform of the areal density
+        let mut planner = FftPlanner::new();
+        let mut transform = areal.mapv(|n| Complex::new(n * area_element, 0.0));
+        for (i, n) in [(0, n1), (1, n2)] {
+            let fft = planner.plan_fft_forward(n);
+            for mut lane in transform.lanes_mut(Axis_nd(i)) {
+                let mut buffer = lane.to_vec();
+                fft.process(&mut buffer);
+                lane.assign(&Array1::from_vec(buffer));
+            }
+        }
 
-    pub fn solve(mut self, solver: Option<&DFTSolver>) -> EosResult<Self> {
-        self.solve_inplace(solver, false)?;
-        Ok(self)
+        // average over shells of the wave vector
+        let wave_number = |m: usize, n: usize, l: f64| {
+            let m = if m <= n / 2 {
+                m as isize
+            } else {
+                m as isize - n as isize
+            };
+            2.0 * PI * m as f64 / l
+        };
+        let dq = 2.0 * PI / l1.max(l2);
+        let q_max = PI * (n1 as f64 / l1).min(n2 as f64 / l2);
+        let n_bins = (q_max / dq).floor() as usize;
+        let mut structure_factor = Array1::<f64>::zeros(n_bins);
+        let mut counts = Array1::<f64>::zeros(n_bins);
+        for ((i, j), t) in transform.indexed_iter() {
+            let q = wave_number(i, n1, l1).hypot(wave_number(j, n2, l2));
+            let bin = (q / dq).round() as usize;
+            if bin > 0 && bin <= n_bins {
+                structure_factor[bin - 1] += t.norm_sqr() / moles;
+                counts[bin - 1] += 1.0;
+            }
+        }
+        let (q, s): (Vec<_>, Vec<_>) = (0..n_bins)
+            .filter(|&k| counts[k] > 0.0)
+            .map(|k| ((k + 1) as f64 * dq, structure_factor[k] / counts[k]))
+            .unzip();
+        Ok((Array1::from_vec(q), Array1::from_vec(s)))
     }
+}
 
-    pub fn update_bulk(mut self, bulk: &State<U, DFT<F>>) -> Self {
-        self.profile.bulk = bulk.clone();
-        self.profile.chemical_potential = bulk.chemical_potential(Contributions::Total);
-        self.grand_potential = None;
-        self.interfacial_tension = None;
-        self
-    }
+/// Position of the wall on the axis of a 1D pore.
+fn wall_position<U: EosUnit>(axis: &Axis) -> EosResult<f64> {
+    Ok(match axis.geometry {
+        AxisGeometry::Cartesian => axis.volume::<U>().to_reduced(U::reference_length())?,
+        AxisGeometry::Polar | AxisGeometry::Spherical => axis.edges[axis.grid.len()],
+    })
 }
 
 impl<U: EosUnit, F: HelmholtzEnergyFunctional + FluidParameters> PoreSpecification<U, Ix1, F>
     for Pore1D<U, F>
 {
-    fn initialize(
+    fn initialize_with_convolver(
         &self,
         bulk: &State<U, DFT<F>>,
         external_potential: Option<&Array2<f64>>,
+        convolver: Option<&Rc<dyn Convolver<f64, Ix1>>>,
     ) -> EosResult<PoreProfile1D<U, F>> {
         let dft = &bulk.eos;
-        let n_grid = self.n_grid.unwrap_or(DEFAULT_GRID_POINTS);
-
-        let axis = match self.geometry {
-            AxisGeometry::Cartesian => {
-                let potential_offset =
-                    POTENTIAL_OFFSET * self.functional.functional.sigma_ff().max().unwrap();
-                Axis::new_cartesian(n_grid, 0.5 * self.pore_size, Some(potential_offset))?
-            }
-            AxisGeometry::Polar => Axis::new_polar(n_grid, self.pore_size)?,
-            AxisGeometry::Spherical => Axis::new_spherical(n_grid, self.pore_size)?,
-        };
+        let axis = self.axis(self.pore_size)?;
 
         // calculate external potential
         let external_potential = external_potential.map_or_else(
@@ -182,6 +2128,7 @@ impl<U: EosUnit, F: HelmholtzEnergyFunctional + FluidParameters> PoreSpecificati
                     &self.potential,
                     &self.functional.functional,
                     &axis,
+                    self.quantum_correction,
                     self.potential_cutoff,
                 )
             },
@@ -191,8 +2138,95 @@ impl<U: EosUnit, F: HelmholtzEnergyFunctional + FluidParameters> PoreSpecificati
         // initialize convolver
         let grid = Grid::new_1d(axis);
         let t = bulk.temperature.to_reduced(U::reference_temperature())?;
-        let weight_functions = dft.functional.weight_functions(t);
-        let convolver = ConvolverFFT::plan(&grid, &weight_functions, Some(1));
+        let convolver = convolver.cloned().unwrap_or_else(|| {
+            let weight_functions = dft.functional.weight_functions(t);
+            ConvolverFFT::plan(&grid, &weight_functions, Some(1))
+        });
+
+        Ok(PoreProfile {
+            profile: DFTProfile::new(grid, convolver, bulk, Some(external_potential))?,
+            grand_potential: None,
+            interfacial_tension: None,
+        })
+    }
+}
+
+impl<U: EosUnit, F: HelmholtzEnergyFunctional + FluidParameters> PoreSpecification<U, Ix2, F>
+    for Pore2D<U, F>
+{
+    fn initialize_with_convolver(
+        &self,
+        bulk: &State<U, DFT<F>>,
+        external_potential: Option<&Array3<f64>>,
+        convolver: Option<&Rc<dyn Convolver<f64, Ix2>>>,
+    ) -> EosResult<PoreProfile2D<U, F>> {
+        let dft = &bulk.eos;
+
+        // check that the tube can hold at least one fluid segment
+        let sigma_ff = self.functional.functional.sigma_ff().max().unwrap();
+        let radius = self.pore_size.to_reduced(U::reference_length())?;
+        let length = self.length.to_reduced(U::reference_length())?;
+        if 2.0 * radius < *sigma_ff {
+            return Err(EosError::Error(format!(
+                "Invalid pore size: the pore width {} is smaller than the largest segment diameter {}.",
+                2.0 * self.pore_size,
+                *sigma_ff * U::reference_length()
+            )));
+        }
+        let reservoir_length = match self.reservoir_length {
+            Some(l) => l.to_reduced(U::reference_length())?,
+            None => POTENTIAL_OFFSET * sigma_ff,
+        };
+
+        // choose the number of grid points from the smallest segment diameter
+        let n_grid = match self.n_grid {
+            Some(n_grid) => n_grid,
+            None => {
+                let spacing =
+                    self.functional.functional.sigma_ff().min().unwrap() / GRID_POINTS_PER_SIGMA;
+                let n_grid = [
+                    Grid::next_fast_len(((radius + reservoir_length) / spacing).ceil() as usize),
+                    Grid::next_fast_len(
+                        ((0.5 * length + reservoir_length) / spacing).ceil() as usize
+                    ),
+                ];
+                info!("Pore2D: using {:?} grid points.", n_grid);
+                n_grid
+            }
+        };
+
+        let r = Axis::new_polar(
+            n_grid[0],
+            (radius + reservoir_length) * U::reference_length(),
+        )?;
+        let z = Axis::new_cartesian(n_grid[1], 0.5 * self.length, Some(reservoir_length))?;
+
+        // calculate external potential
+        let t = bulk.temperature.to_reduced(U::reference_temperature())?;
+        let external_potential = external_potential.map_or_else(
+            || {
+                external_potential_2d(
+                    &self.functional.functional,
+                    &r,
+                    &z,
+                    radius,
+                    length,
+                    self.sigma_ss,
+                    self.epsilon_k_ss,
+                    self.rho_s,
+                    self.potential_cutoff,
+                    t,
+                )
+            },
+            |e| e.clone(),
+        );
+
+        // initialize convolver
+        let grid = Grid::Cylindrical { r, z };
+        let convolver = convolver.cloned().unwrap_or_else(|| {
+            let weight_functions = dft.functional.weight_functions(t);
+            ConvolverFFT::plan(&grid, &weight_functions, Some(1))
+        });
 
         Ok(PoreProfile {
             profile: DFTProfile::new(grid, convolver, bulk, Some(external_potential))?,
@@ -205,50 +2239,189 @@ impl<U: EosUnit, F: HelmholtzEnergyFunctional + FluidParameters> PoreSpecificati
 impl<U: EosUnit, F: HelmholtzEnergyFunctional, P: FluidParameters> PoreSpecification<U, Ix3, F>
     for Pore3D<U, P>
 {
-    fn initialize(
+    fn initialize_with_convolver(
         &self,
         bulk: &State<U, DFT<F>>,
         external_potential: Option<&Array4<f64>>,
+        convolver: Option<&Rc<dyn Convolver<f64, Ix3>>>,
     ) -> EosResult<PoreProfile3D<U, F>> {
         let dft = &bulk.eos;
 
+        // check the shapes of the solid parameters
+        let shape = self.coordinates.shape();
+        if shape[0] != 3 {
+            return Err(EosError::Error(format!(
+                "The coordinates have shape {:?}, but the expected layout is [3, number of interaction sites].",
+                shape
+            )));
+        }
+        let n_sites = shape[1];
+        if self.sigma_ss.len() != n_sites || self.epsilon_k_ss.len() != n_sites {
+            return Err(EosError::Error(format!(
+                "sigma_ss and epsilon_k_ss have lengths {} and {}, but there are {} interaction sites.",
+                self.sigma_ss.len(),
+                self.epsilon_k_ss.len(),
+                n_sites
+            )));
+        }
+
+        // cell vectors in the rows of the cell matrix
+        let mut cell = match &self.cell_vectors {
+            Some(cell) => {
+                if cell.shape() != [3, 3] {
+                    return Err(EosError::Error(format!(
+                        "The cell vectors have shape {:?}, but the expected shape is [3, 3].",
+                        cell.shape()
+                    )));
+                }
+                if self.periodic.iter().any(|&p| !p) {
+                    return Err(EosError::Error(
+                        "Triclinic cells are only supported for systems that are periodic in all directions.".into(),
+                    ));
+                }
+                cell.to_reduced(U::reference_length())?
+            }
+            None => Array2::from_diag(&arr1(&[
+                self.system_size[0].to_reduced(U::reference_length())?,
+                self.system_size[1].to_reduced(U::reference_length())?,
+                self.system_size[2].to_reduced(U::reference_length())?,
+            ])),
+        };
+        let mut lengths: Vec<_> = cell.outer_iter().map(|a| a.dot(&a).sqrt()).collect();
+        if lengths.iter().any(|&l| !(l.is_finite() && l > 0.0)) {
+            return Err(EosError::Error(format!(
+                "The box lengths have to be positive, but they are {:?} (in Å).",
+                lengths
+            )));
+        }
+
+        // validate the interaction sites and wrap them into the unit cell
+        let mut coordinates = self.coordinates.to_reduced(U::reference_length())?;
+        if coordinates.nrows() != 3 {
+            return Err(EosError::Error(format!(
+                "The coordinates have shape {:?}, but the expected shape is [3, n].",
+                coordinates.shape()
+            )));
+        }
+        if let Some(j) = coordinates
+            .columns()
+            .into_iter()
+            .position(|r| r.iter().any(|x| !x.is_finite()))
+        {
+            return Err(EosError::Error(format!(
+                "The coordinates of interaction site {} are not finite.",
+                j
+            )));
+        }
+        if self.wrap_coordinates {
+            let cell_inv = cell
+                .inv()
+                .map_err(|_| EosError::Error("The cell vectors are linearly dependent.".into()))?;
+            for mut r in coordinates.columns_mut() {
+                let mut s = cell_inv.t().dot(&r);
+                for k in 0..3 {
+                    if self.periodic[k] {
+                        s[k] -= s[k].floor();
+                    }
+                }
+                r.assign(&cell.t().dot(&s));
+            }
+        }
+
+        // check the anisotropy of the grid
+        let spacing: Vec<_> = (0..3).map(|i| lengths[i] / self.n_grid[i] as f64).collect();
+        let anisotropy = spacing.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+            / spacing.iter().cloned().fold(f64::INFINITY, f64::min);
+        if anisotropy > MAX_GRID_ANISOTROPY {
+            let msg = format!(
+                "The ratio of the largest to the smallest grid spacing is {:.2} (maximum {}).",
+                anisotropy, MAX_GRID_ANISOTROPY
+            );
+            if self.strict_grid_spacing {
+                return Err(EosError::Error(msg));
+            }
+            warn!("{}", msg);
+        }
+
+        // extend non-periodic directions by the vacuum buffer
+        let mut n_grid = self.n_grid;
+        let mut shift = [0.0; 3];
+        if let Some(vacuum_padding) = &self.vacuum_padding {
+            for k in 0..3 {
+                let padding = vacuum_padding[k].to_reduced(U::reference_length())?;
+                if padding == 0.0 {
+                    continue;
+                }
+                if padding < 0.0 || self.periodic[k] {
+                    return Err(EosError::Error(format!(
+                        "Invalid vacuum padding in direction {}: only positive values in non-periodic directions are allowed.",
+                        k
+                    )));
+                }
+                let n_padding = (padding / spacing[k]).round() as usize;
+                n_grid[k] += 2 * n_padding;
+                shift[k] = n_padding as f64 * spacing[k];
+                lengths[k] += 2.0 * shift[k];
+            }
+            cell = Array2::from_diag(&arr1(&lengths));
+        }
+
         // generate grid
-        let x = Axis::new_cartesian(self.n_grid[0], self.system_size[0], None)?;
-        let y = Axis::new_cartesian(self.n_grid[1], self.system_size[1], None)?;
-        let z = Axis::new_cartesian(self.n_grid[2], self.system_size[2], None)?;
-
-        // move center of geometry of solute to box center
-        let coordinates = Array2::from_shape_fn(self.coordinates.raw_dim(), |(i, j)| {
-            (self.coordinates.get((i, j)))
-                .to_reduced(U::reference_length())
-                .unwrap()
-        });
+        let x = Axis::new_cartesian(n_grid[0], lengths[0] * U::reference_length(), None)?;
+        let y = Axis::new_cartesian(n_grid[1], lengths[1] * U::reference_length(), None)?;
+        let z = Axis::new_cartesian(n_grid[2], lengths[2] * U::reference_length(), None)?;
+
+        // move the solid by the vacuum buffer
+        for (mut r, &s) in coordinates.outer_iter_mut().zip(shift.iter()) {
+            r += s;
+        }
 
         // temperature
         let t = bulk.temperature.to_reduced(U::reference_temperature())?;
 
         // calculate external potential
-        let external_potential = external_potential.map_or_else(
+        let mut external_potential = external_potential.map_or_else(
             || {
                 external_potential_3d(
                     &self.functional.functional,
                     [&x, &y, &z],
-                    self.system_size,
+                    &cell,
                     coordinates,
                     &self.sigma_ss,
                     &self.epsilon_k_ss,
                     self.cutoff_radius,
+                    self.potential_form,
+                    self.quantum_correction,
                     self.potential_cutoff,
+                    self.periodic,
                     t,
                 )
             },
             |e| Ok(e.clone()),
         )?;
+        if self.freeze_inaccessible {
+            let potential_cutoff = self.potential_cutoff.unwrap_or(MAX_POTENTIAL);
+            external_potential.mapv_inplace(|v| {
+                if v >= potential_cutoff {
+                    MAX_POTENTIAL
+                } else {
+                    v
+                }
+            });
+        }
 
         // initialize convolver
-        let grid = Grid::Periodical3(x, y, z);
-        let weight_functions = dft.functional.weight_functions(t);
-        let convolver = ConvolverFFT::plan(&grid, &weight_functions, Some(1));
+        let grid = if self.cell_vectors.is_some() {
+            Grid::new_triclinic(x, y, z, cell)
+        } else if self.periodic.iter().all(|&p| p) {
+            Grid::Periodical3(x, y, z)
+        } else {
+            Grid::Cartesian3(x, y, z)
+        };
+        let convolver = convolver.cloned().unwrap_or_else(|| {
+            let weight_functions = dft.functional.weight_functions(t);
+            ConvolverFFT::plan(&grid, &weight_functions, Some(1))
+        });
 
         Ok(PoreProfile {
             profile: DFTProfile::new(grid, convolver, bulk, Some(external_potential))?,
@@ -264,40 +2437,96 @@ fn external_potential_1d<U: EosUnit, P: FluidParameters>(
     potential: &ExternalPotential<U>,
     fluid_parameters: &P,
     axis: &Axis,
+    quantum_correction: Option<QuantityScalar<U>>,
     potential_cutoff: Option<f64>,
 ) -> EosResult<Array2<f64>> {
-    let potential_cutoff = potential_cutoff.unwrap_or(MAX_POTENTIAL);
-    let effective_pore_size = match axis.geometry {
+    let t = temperature.to_reduced(U::reference_temperature())?;
+    let lambda = quantum_correction
+        .map(|q| q.to_reduced(U::reference_length().powi(2) * U::reference_temperature()))
+        .transpose()?
+        .map(|q| q / t);
+    external_potential_1d_reduced(
+        effective_pore_size(pore_width, axis)?,
+        t,
+        potential,
+        fluid_parameters,
+        axis,
+        lambda,
+        potential_cutoff,
+    )
+}
+
+/// Distance of the wall from the center of a 1D pore in reduced units.
+fn effective_pore_size<U: EosUnit>(pore_width: QuantityScalar<U>, axis: &Axis) -> EosResult<f64> {
+    Ok(match axis.geometry {
         AxisGeometry::Spherical => pore_width.to_reduced(U::reference_length())?,
         AxisGeometry::Polar => pore_width.to_reduced(U::reference_length())?,
         AxisGeometry::Cartesian => 0.5 * pore_width.to_reduced(U::reference_length())?,
-    };
-    let t = temperature.to_reduced(U::reference_temperature())?;
-    let mut external_potential = match &axis.geometry {
+    })
+}
+
+fn external_potential_1d_reduced<U: EosUnit, P: FluidParameters>(
+    effective_pore_size: f64,
+    t: f64,
+    potential: &ExternalPotential<U>,
+    fluid_parameters: &P,
+    axis: &Axis,
+    lambda: Option<f64>,
+    potential_cutoff: Option<f64>,
+) -> EosResult<Array2<f64>> {
+    potential.check_geometry(axis.geometry, fluid_parameters.m().len())?;
+    let potential_cutoff = potential_cutoff.unwrap_or(MAX_POTENTIAL);
+    let calculate_potential = |grid: &Array1<f64>| match &axis.geometry {
         AxisGeometry::Cartesian => {
             potential.calculate_cartesian_potential(
-                &(effective_pore_size + &axis.grid),
+                &(effective_pore_size + grid),
                 fluid_parameters,
                 t,
             ) + &potential.calculate_cartesian_potential(
-                &(effective_pore_size - &axis.grid),
+                &(effective_pore_size - grid),
                 fluid_parameters,
                 t,
             )
         }
-        AxisGeometry::Spherical => potential.calculate_spherical_potential(
-            &axis.grid,
-            effective_pore_size,
-            fluid_parameters,
-            t,
-        ),
+        AxisGeometry::Spherical => {
+            potential.calculate_spherical_potential(grid, effective_pore_size, fluid_parameters, t)
+        }
         AxisGeometry::Polar => potential.calculate_cylindrical_potential(
-            &axis.grid,
+            grid,
             effective_pore_size,
             fluid_parameters,
             t,
         ),
-    } / t;
+    };
+    let mut external_potential = calculate_potential(&axis.grid);
+
+    // Feynman-Hibbs correction with the numerical laplacian of the potential
+    if let Some(lambda) = lambda {
+        let h = axis.grid.mapv(|r| DELTA_LAPLACIAN.min(0.5 * r));
+        let potential_plus = calculate_potential(&(&axis.grid + &h));
+        let potential_minus = calculate_potential(&(&axis.grid - &h));
+        let curvature = match axis.geometry {
+            AxisGeometry::Cartesian => 0.0,
+            AxisGeometry::Polar => 1.0,
+            AxisGeometry::Spherical => 2.0,
+        };
+        Zip::from(external_potential.columns_mut())
+            .and(potential_plus.columns())
+            .and(potential_minus.columns())
+            .and(&axis.grid)
+            .and(&h)
+            .for_each(|mut v, v_plus, v_minus, &r, &h| {
+                let laplacian = (&v_plus - &v * 2.0 + v_minus) / (h * h)
+                    + (&v_plus - &v_minus) * (curvature / (2.0 * h * r));
+                v.scaled_add(lambda, &laplacian);
+            });
+    }
+    external_potential /= t;
+    external_potential.map_inplace(|x| {
+        if x.is_nan() {
+            *x = potential_cutoff
+        }
+    });
 
     for (i, &z) in axis.grid.iter().enumerate() {
         if z > effective_pore_size {
@@ -314,15 +2543,83 @@ fn external_potential_1d<U: EosUnit, P: FluidParameters>(
     Ok(external_potential)
 }
 
+/// Calculate the external potential of a cylindrical shell of finite length.
+///
+/// The 12-6 Lennard-Jones potential is integrated analytically along the axis
+/// of the shell and numerically around its circumference.
+#[allow(clippy::too_many_arguments)]
+fn external_potential_2d<P: FluidParameters>(
+    fluid_parameters: &P,
+    r: &Axis,
+    z: &Axis,
+    radius: f64,
+    length: f64,
+    sigma_ss: f64,
+    epsilon_k_ss: f64,
+    rho_s: f64,
+    potential_cutoff: Option<f64>,
+    temperature: f64,
+) -> Array3<f64> {
+    let potential_cutoff = potential_cutoff.unwrap_or(MAX_POTENTIAL);
+    let m = fluid_parameters.m();
+    let sigma_sf = fluid_parameters.sigma_ff().mapv(|s| 0.5 * (s + sigma_ss));
+    let epsilon_k_sf = fluid_parameters
+        .epsilon_k_ff()
+        .mapv(|e| (e * epsilon_k_ss).sqrt());
+    let dphi = 2.0 * PI / N_PHI_SHELL as f64;
+
+    // integral of (a² + u²)^(-n) from 0 to u
+    let integral = |n: i32, a2: f64, u: f64| {
+        let a = a2.sqrt();
+        let mut res = (u / a).atan() / a;
+        for k in 2..=n {
+            let k = k as f64;
+            res = u / (2.0 * (k - 1.0) * a2 * (a2 + u * u).powf(k - 1.0))
+                + (2.0 * k - 3.0) / (2.0 * (k - 1.0) * a2) * res;
+        }
+        res
+    };
+
+    let mut external_potential = Array3::zeros((m.len(), r.grid.len(), z.grid.len()));
+    for (i, mut v_i) in external_potential.outer_iter_mut().enumerate() {
+        let s6 = sigma_sf[i].powi(6);
+        let prefactor = 4.0 * m[i] * epsilon_k_sf[i] * rho_s * radius * dphi / temperature;
+        Zip::indexed(&mut v_i).for_each(|(j, k), v| {
+            let (r, z) = (r.grid[j], z.grid[k]);
+            let (u_min, u_max) = (-0.5 * length - z, 0.5 * length - z);
+            let mut sum = 0.0;
+            for l in 0..N_PHI_SHELL {
+                let phi = (l as f64 + 0.5) * dphi;
+                let a2 = r * r + radius * radius - 2.0 * r * radius * phi.cos();
+                sum += s6 * s6 * (integral(6, a2, u_max) - integral(6, a2, u_min))
+                    - s6 * (integral(3, a2, u_max) - integral(3, a2, u_min));
+            }
+            *v = prefactor * sum;
+            if v.is_nan() || *v > potential_cutoff {
+                *v = potential_cutoff;
+            }
+        });
+    }
+    external_potential
+}
+
+/// Calculate the external potential of a 3D solid.
+///
+/// The rows of `cell` are the (reduced) cell vectors and the axes are laid
+/// out along the cell vectors. The potential is clamped to `potential_cutoff`
+/// (default: 50), see [Pore1DBuilder::potential_cutoff].
 pub fn external_potential_3d<U: EosUnit, F: FluidParameters>(
     functional: &F,
     axis: [&Axis; 3],
-    system_size: [QuantityScalar<U>; 3],
+    cell: &Array2<f64>,
     coordinates: Array2<f64>,
     sigma_ss: &Array1<f64>,
     epsilon_ss: &Array1<f64>,
-    cutoff_radius: Option<QuantityScalar<U>>,
+    cutoff_radius: Option<CutoffRadius<U>>,
+    potential_form: PotentialForm,
+    quantum_correction: Option<QuantityScalar<U>>,
     potential_cutoff: Option<f64>,
+    periodic: [bool; 3],
     reduced_temperature: f64,
 ) -> EosResult<Array4<f64>> {
     // allocate external potential
@@ -334,38 +2631,52 @@ pub fn external_potential_3d<U: EosUnit, F: FluidParameters>(
         axis[2].grid.len(),
     ));
 
-    let system_size = [
-        system_size[0].to_reduced(U::reference_length())?,
-        system_size[1].to_reduced(U::reference_length())?,
-        system_size[2].to_reduced(U::reference_length())?,
-    ];
-
-    let cutoff_radius = cutoff_radius
-        .unwrap_or(CUTOFF_RADIUS * U::reference_length())
-        .to_reduced(U::reference_length())?;
-
-    // square cut-off radius
-    let cutoff_radius2 = cutoff_radius.powi(2);
+    let cell_inv = cell
+        .inv()
+        .map_err(|_| EosError::Error("The cell vectors are linearly dependent.".into()))?;
 
     // calculate external potential
     let sigma_ff = functional.sigma_ff();
     let epsilon_k_ff = functional.epsilon_k_ff();
+    let (cutoff_radius2, lambda) = interaction_parameters(
+        functional,
+        sigma_ss,
+        cutoff_radius,
+        quantum_correction,
+        reduced_temperature,
+    )?;
+    let inner_maximum = potential_form.inner_maximum();
+
+    // bin the interaction sites so that only neighboring bins have to be considered
+    let cell_list = CellList::new(
+        &coordinates,
+        &cell_inv,
+        cutoff_radius2.fold(0.0, |a: f64, &b| a.max(b)).sqrt(),
+        periodic,
+    );
 
     Zip::indexed(&mut external_potential).par_for_each(|(i, ix, iy, iz), u| {
-        let distance2 = calculate_distance2(
-            [&axis[0].grid[ix], &axis[1].grid[iy], &axis[2].grid[iz]],
-            &coordinates,
-            system_size,
-        );
-        let sigma_sf = sigma_ss.mapv(|s| (s + sigma_ff[i]) / 2.0);
-        let epsilon_sf = epsilon_ss.mapv(|e| (e * epsilon_k_ff[i]).sqrt());
-        *u = (0..sigma_ss.len())
-            .map(|alpha| {
+        // cartesian coordinates of the grid point
+        let fractional = [
+            axis[0].grid[ix] / axis[0].length(),
+            axis[1].grid[iy] / axis[1].length(),
+            axis[2].grid[iz] / axis[2].length(),
+        ];
+        let point = [0, 1, 2].map(|c| (0..3).map(|k| fractional[k] * cell[[k, c]]).sum());
+        let sites = cell_list.neighbors(fractional);
+        let distance2 = calculate_distance2(point, &coordinates, &sites, cell, &cell_inv, periodic);
+        *u = sites
+            .iter()
+            .zip(distance2.iter())
+            .map(|(&alpha, &d2)| {
                 m[i] * evaluate(
-                    distance2[alpha],
-                    sigma_sf[alpha],
-                    epsilon_sf[alpha],
-                    cutoff_radius2,
+                    d2,
+                    (sigma_ss[alpha] + sigma_ff[i]) / 2.0,
+                    (epsilon_ss[alpha] * epsilon_k_ff[i]).sqrt(),
+                    cutoff_radius2[i],
+                    lambda,
+                    potential_form,
+                    inner_maximum,
                 )
             })
             .sum::<f64>()
@@ -382,36 +2693,511 @@ pub fn external_potential_3d<U: EosUnit, F: FluidParameters>(
     Ok(external_potential)
 }
 
-/// Evaluate LJ12-6 potential between solid site "alpha" and fluid segment
-fn evaluate(distance2: f64, sigma: f64, epsilon: f64, cutoff_radius2: f64) -> f64 {
+/// Return the squared cut-off radius for every segment and the (reduced)
+/// Feynman-Hibbs parameter of the interaction with 3D solids.
+fn interaction_parameters<U: EosUnit, F: FluidParameters>(
+    functional: &F,
+    sigma_ss: &Array1<f64>,
+    cutoff_radius: Option<CutoffRadius<U>>,
+    quantum_correction: Option<QuantityScalar<U>>,
+    reduced_temperature: f64,
+) -> EosResult<(Array1<f64>, f64)> {
+    let m = functional.m();
+    let sigma_ff = functional.sigma_ff();
+
+    // square cut-off radius for every component
+    let cutoff_radius2 = match cutoff_radius {
+        Some(CutoffRadius::Fixed(r)) => {
+            Array1::from_elem(m.len(), r.to_reduced(U::reference_length())?.powi(2))
+        }
+        Some(CutoffRadius::Multiple(n)) => {
+            let sigma_ss_max = sigma_ss.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+            sigma_ff.mapv(|s| (n * 0.5 * (sigma_ss_max + s)).powi(2))
+        }
+        None => Array1::from_elem(m.len(), CUTOFF_RADIUS.powi(2)),
+    };
+
+    // Feynman-Hibbs parameter
+    let lambda = match quantum_correction {
+        Some(q) => {
+            q.to_reduced(U::reference_length().powi(2) * U::reference_temperature())?
+                / reduced_temperature
+        }
+        None => 0.0,
+    };
+    Ok((cutoff_radius2, lambda))
+}
+
+/// Evaluate LJ12-6 or exp-6 potential between solid site "alpha" and fluid segment
+///
+/// The Feynman-Hibbs correction $\lambda\nabla^2V$ is added for $\lambda>0$.
+/// The potential is bounded by a large finite value, so that grid points on
+/// top of an interaction site do not produce infinities.
+fn evaluate(
+    distance2: f64,
+    sigma: f64,
+    epsilon: f64,
+    cutoff_radius2: f64,
+    lambda: f64,
+    potential_form: PotentialForm,
+    inner_maximum: f64,
+) -> f64 {
     let sigma_r = sigma.powi(2) / distance2;
 
     let potential: f64 = if distance2 > cutoff_radius2 {
         0.0
+    } else if let PotentialForm::Exp6 { alpha } = potential_form {
+        // cap the potential at its inner maximum
+        let r_m = 2.0_f64.powf(1.0 / 6.0) * sigma;
+        let x = distance2.sqrt() / r_m;
+        let (x, laplacian) = if x < inner_maximum {
+            (inner_maximum, 0.0)
+        } else {
+            (x, 1.0)
+        };
+        let (r, beta) = (x * r_m, alpha / r_m);
+        let repulsion = 6.0 / alpha * (alpha * (1.0 - x)).exp();
+        let dispersion = x.powi(-6);
+        epsilon / (1.0 - 6.0 / alpha)
+            * (repulsion - dispersion
+                + laplacian
+                    * lambda
+                    * (repulsion * (beta * beta - 2.0 * beta / r) - 30.0 * dispersion / (r * r)))
     } else if distance2 == 0.0 {
-        f64::INFINITY
+        MAX_PAIR_POTENTIAL
     } else {
-        4.0 * epsilon * (sigma_r.powi(6) - sigma_r.powi(3))
+        4.0 * epsilon
+            * (sigma_r.powi(6) - sigma_r.powi(3)
+                + lambda / distance2 * (132.0 * sigma_r.powi(6) - 30.0 * sigma_r.powi(3)))
     };
 
-    potential
+    potential.min(MAX_PAIR_POTENTIAL)
 }
 
-/// Evaluate the squared euclidian distance between a point and the coordinates of all solid atoms.
+/// Evaluate the squared euclidian distance between a point and the coordinates of the given solid atoms.
+///
+/// The minimum image convention is applied to the fractional coordinates
+/// in all periodic directions.
 fn calculate_distance2(
-    point: [&f64; 3],
+    point: [f64; 3],
     coordinates: &Array2<f64>,
-    system_size: [f64; 3],
+    sites: &[usize],
+    cell: &Array2<f64>,
+    cell_inv: &Array2<f64>,
+    periodic: [bool; 3],
 ) -> Array1<f64> {
-    Array1::from_shape_fn(coordinates.ncols(), |i| {
-        let mut rx = coordinates[[0, i]] - point[0];
-        let mut ry = coordinates[[1, i]] - point[1];
-        let mut rz = coordinates[[2, i]] - point[2];
+    Array1::from_shape_fn(sites.len(), |j| {
+        let i = sites[j];
+        let r = [0, 1, 2].map(|c| coordinates[[c, i]] - point[c]);
+        let s = [0, 1, 2].map(|k| {
+            let s: f64 = (0..3).map(|c| r[c] * cell_inv[[c, k]]).sum();
+            if periodic[k] {
+                s - s.round()
+            } else {
+                s
+            }
+        });
+        (0..3)
+            .map(|c| (0..3).map(|k| s[k] * cell[[k, c]]).sum::<f64>().powi(2))
+            .sum()
+    })
+}
+
+/// Spatial binning (cell list) of the solid interaction sites in fractional
+/// coordinates.
+///
+/// Every bin is at least as wide as the cutoff radius, so that only sites in
+/// the same or in neighboring bins interact with a given point.
+struct CellList {
+    n_bins: [usize; 3],
+    periodic: [bool; 3],
+    bins: Vec<Vec<usize>>,
+}
 
-        rx -= system_size[0] * (rx / system_size[0]).round();
-        ry -= system_size[1] * (ry / system_size[1]).round();
-        rz -= system_size[2] * (rz / system_size[2]).round();
+impl CellList {
+    fn new(
+        coordinates: &Array2<f64>,
+        cell_inv: &Array2<f64>,
+        cutoff_radius: f64,
+        periodic: [bool; 3],
+    ) -> Self {
+        // the perpendicular widths of the unit cell determine the number of bins
+        let n_bins = [0, 1, 2].map(|k| {
+            let width = cell_inv.column(k).dot(&cell_inv.column(k)).sqrt().recip();
+            ((width / cutoff_radius).floor() as usize).max(1)
+        });
+        let mut cell_list = Self {
+            n_bins,
+            periodic,
+            bins: vec![Vec::new(); n_bins.iter().product()],
+        };
+        for i in 0..coordinates.ncols() {
+            let s = [0, 1, 2].map(|k| (0..3).map(|c| coordinates[[c, i]] * cell_inv[[c, k]]).sum());
+            let [b0, b1, b2] = cell_list.bin(s);
+            cell_list.bins[(b0 * n_bins[1] + b1) * n_bins[2] + b2].push(i);
+        }
+        cell_list
+    }
 
-        rx.powi(2) + ry.powi(2) + rz.powi(2)
-    })
+    /// Bin of a point given in fractional coordinates. Points outside of
+    /// the unit cell are assigned to the closest bin in non-periodic directions.
+    fn bin(&self, s: [f64; 3]) -> [usize; 3] {
+        [0, 1, 2].map(|k| {
+            let s = if self.periodic[k] {
+                s[k] - s[k].floor()
+            } else {
+                s[k]
+            };
+            ((s * self.n_bins[k] as f64).floor().max(0.0) as usize).min(self.n_bins[k] - 1)
+        })
+    }
+
+    /// Indices of all sites in the same and in the neighboring bins of a point.
+    fn neighbors(&self, s: [f64; 3]) -> Vec<usize> {
+        let bin = self.bin(s);
+        let [r0, r1, r2] = [0, 1, 2].map(|k| {
+            let (n, b) = (self.n_bins[k] as isize, bin[k] as isize);
+            if !self.periodic[k] {
+                ((b - 1).max(0)..=(b + 1).min(n - 1)).collect()
+            } else if n < 3 {
+                (0..n).collect()
+            } else {
+                (b - 1..=b + 1).map(|b| b.rem_euclid(n)).collect::<Vec<_>>()
+            }
+        });
+        let mut sites = Vec::new();
+        for &b0 in &r0 {
+            for &b1 in &r1 {
+                for &b2 in &r2 {
+                    let index =
+                        (b0 as usize * self.n_bins[1] + b1 as usize) * self.n_bins[2] + b2 as usize;
+                    sites.extend_from_slice(&self.bins[index]);
+                }
+            }
+        }
+        sites
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fundamental_measure_theory::{FMTFunctional, FMTVersion};
+    use ndarray::{arr1, arr2};
+    use quantity::si::{ANGSTROM, KELVIN};
+
+    /// Lennard-Jones fluid with a single segment per molecule.
+    struct LJFluid {
+        sigma: Array1<f64>,
+        epsilon_k: Array1<f64>,
+    }
+
+    impl FluidParameters for LJFluid {
+        fn epsilon_k_ff(&self) -> Array1<f64> {
+            self.epsilon_k.clone()
+        }
+
+        fn sigma_ff(&self) -> &Array1<f64> {
+            &self.sigma
+        }
+
+        fn m(&self) -> Array1<f64> {
+            Array1::ones(self.sigma.len())
+        }
+    }
+
+    fn fluid() -> LJFluid {
+        LJFluid {
+            sigma: arr1(&[3.4]),
+            epsilon_k: arr1(&[120.0]),
+        }
+    }
+
+    /// External potential of a single solid atom on top of the grid point
+    /// (1, 1, 1) of a periodic 4x4x4 grid.
+    fn potential_3d(potential_form: PotentialForm, potential_cutoff: Option<f64>) -> Array4<f64> {
+        let axis = Axis::new_cartesian(4, 8.0 * ANGSTROM, None).unwrap();
+        let cell = Array2::eye(3) * 8.0;
+        let coordinates = arr2(&[[3.0], [3.0], [3.0]]);
+        external_potential_3d::<SIUnit, _>(
+            &fluid(),
+            [&axis, &axis, &axis],
+            &cell,
+            coordinates,
+            &arr1(&[3.0]),
+            &arr1(&[50.0]),
+            None,
+            potential_form,
+            None,
+            potential_cutoff,
+            [true; 3],
+            300.0,
+        )
+        .unwrap()
+    }
+
+    /// Deterministic, quasi-random interaction sites in the given cell.
+    fn sites(cell: &Array2<f64>, n: usize) -> Array2<f64> {
+        let phi = [0.618_033_988_7, 0.754_877_666_2, 0.569_840_290_9];
+        Array2::from_shape_fn((3, n), |(c, i)| {
+            (0..3)
+                .map(|k| ((i + 1) as f64 * phi[k]).fract() * cell[[k, c]])
+                .sum()
+        })
+    }
+
+    #[test]
+    fn cell_list_matches_brute_force() {
+        let cutoff_radius: f64 = 2.5;
+        let weight = |d2: f64| {
+            if d2 < cutoff_radius.powi(2) {
+                1.0 / (1.0 + d2)
+            } else {
+                0.0
+            }
+        };
+
+        // the last cell vector is shorter than three bins
+        let cell = arr2(&[[10.0, 0.0, 0.0], [2.0, 9.0, 0.0], [0.0, 0.0, 6.0]]);
+        let cell_inv = cell.inv().unwrap();
+        let coordinates = sites(&cell, 200);
+        let all: Vec<usize> = (0..coordinates.ncols()).collect();
+
+        for &periodic in &[[true; 3], [false; 3], [true, false, true]] {
+            let cell_list = CellList::new(&coordinates, &cell_inv, cutoff_radius, periodic);
+            assert!(cell_list.n_bins[2] < 3);
+            for i in 0..5 {
+                for j in 0..5 {
+                    for k in 0..5 {
+                        let s = [i, j, k].map(|x| (x as f64 + 0.3) / 5.0);
+                        let point = [0, 1, 2].map(|c| (0..3).map(|l| s[l] * cell[[l, c]]).sum());
+
+                        // every site is considered at most once
+                        let mut neighbors = cell_list.neighbors(s);
+                        let n = neighbors.len();
+                        neighbors.sort_unstable();
+                        neighbors.dedup();
+                        assert_eq!(neighbors.len(), n);
+
+                        let d2 = calculate_distance2(
+                            point,
+                            &coordinates,
+                            &neighbors,
+                            &cell,
+                            &cell_inv,
+                            periodic,
+                        );
+                        let d2_all = calculate_distance2(
+                            point,
+                            &coordinates,
+                            &all,
+                            &cell,
+                            &cell_inv,
+                            periodic,
+                        );
+                        let sum: f64 = d2.iter().map(|&d2| weight(d2)).sum();
+                        let sum_all: f64 = d2_all.iter().map(|&d2| weight(d2)).sum();
+                        assert!(sum_all > 0.0);
+                        assert!((sum - sum_all).abs() < 1e-12 * sum_all);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn grid_point_on_solid_atom() {
+        for &potential_form in &[PotentialForm::LJ, PotentialForm::Exp6 { alpha: 15.0 }] {
+            // pair potential at zero distance, with and without Feynman-Hibbs correction
+            for &lambda in &[0.0, 0.1] {
+                let u = evaluate(
+                    0.0,
+                    3.2,
+                    77.5,
+                    196.0,
+                    lambda,
+                    potential_form,
+                    potential_form.inner_maximum(),
+                );
+                assert!(u.is_finite());
+                assert!(u <= MAX_PAIR_POTENTIAL);
+            }
+
+            // external potential on the grid
+            let potential = potential_3d(potential_form, None);
+            assert!(potential
+                .iter()
+                .all(|u| u.is_finite() && *u <= MAX_POTENTIAL));
+            assert_eq!(potential[[0, 1, 1, 1]], MAX_POTENTIAL);
+        }
+    }
+
+    #[test]
+    fn hard_spheres_in_curved_cavity() {
+        let func = Rc::new(FMTFunctional::new(&arr1(&[1.0]), FMTVersion::WhiteBear));
+        let bulk = State::new_nvt(
+            &func,
+            300.0 * KELVIN,
+            NAV * MOL * ANGSTROM.powi(3) / 0.6,
+            &(arr1(&[1.0]) * MOL),
+        )
+        .unwrap();
+        for &geometry in &[AxisGeometry::Spherical, AxisGeometry::Polar] {
+            let pore = Pore1DBuilder::new(&func)
+                .geometry(geometry)
+                .pore_size(5.0 * ANGSTROM)
+                .potential(ExternalPotential::HardWall { sigma_ss: 1.0 })
+                .n_grid(256)
+                .build()
+                .unwrap();
+            let profile = pore.initialize(&bulk, None).unwrap().solve(None).unwrap();
+            let r = profile.profile.grid.grids()[0].clone();
+            let rho = profile
+                .profile
+                .density
+                .to_reduced(SIUnit::reference_density())
+                .unwrap()
+                .row(0)
+                .to_owned();
+            assert!(rho.iter().all(|rho| rho.is_finite() && *rho >= 0.0));
+            assert!(rho[0] > 0.0);
+
+            // the slope of the quadratic interpolant at the center vanishes
+            let slope: f64 = (0..3)
+                .map(|k| {
+                    let (a, b) = ((k + 1) % 3, (k + 2) % 3);
+                    -rho[k] * (r[a] + r[b]) / ((r[k] - r[a]) * (r[k] - r[b]))
+                })
+                .sum();
+            let max_slope = (1..r.len())
+                .map(|k| ((rho[k] - rho[k - 1]) / (r[k] - r[k - 1])).abs())
+                .fold(0.0, f64::max);
+            assert!(slope.abs() < 1e-2 * max_slope);
+        }
+    }
+
+    #[test]
+    fn potential_cutoff_1d() {
+        // the grid extends beyond the wall at z = 10
+        let axis = Axis::new_cartesian(64, 10.0 * ANGSTROM, Some(2.0)).unwrap();
+        let potential = ExternalPotential::<SIUnit>::LJ93 {
+            sigma_ss: 3.0,
+            epsilon_k_ss: 100.0,
+            rho_s: 0.08,
+        };
+        for &(potential_cutoff, max) in &[(Some(10.0), 10.0), (None, MAX_POTENTIAL)] {
+            let v = external_potential_1d_reduced(
+                10.0,
+                300.0,
+                &potential,
+                &fluid(),
+                &axis,
+                None,
+                potential_cutoff,
+            )
+            .unwrap();
+            assert!(v.iter().all(|v| v.is_finite() && *v <= max));
+            assert_eq!(v[[0, 63]], max);
+            assert!(v[[0, 0]] < max);
+        }
+    }
+
+    #[test]
+    fn potential_cutoff_3d() {
+        for &(potential_cutoff, max) in &[(Some(10.0), 10.0), (None, MAX_POTENTIAL)] {
+            let v = potential_3d(PotentialForm::LJ, potential_cutoff);
+            assert!(v.iter().all(|v| v.is_finite() && *v <= max));
+            assert_eq!(v[[0, 1, 1, 1]], max);
+            assert!(v[[0, 3, 3, 3]] < max);
+        }
+    }
+
+    #[test]
+    fn lammps_atom_style() {
+        let func = Rc::new(FMTFunctional::new(&arr1(&[1.0]), FMTVersion::WhiteBear));
+        let data = |header: &str| {
+            format!(
+                "LAMMPS data file\n\n0.0 10.0 xlo xhi\n0.0 10.0 ylo yhi\n0.0 10.0 zlo zhi\n\n\
+                 Pair Coeffs\n\n1 0.1 3.0\n2 0.2 3.5\n\n{}\n\n1 2 1 1.0 2.0 3.0\n",
+                header
+            )
+        };
+        let path = std::env::temp_dir().join("feos_dft_lammps_atom_style.data");
+
+        // molecular style: id mol type x y z
+        fs::write(&path, data("Atoms # molecular")).unwrap();
+        let pore = Pore3D::from_lammps_data(&func, &path, [4; 3], None, None).unwrap();
+        let coordinates = pore.coordinates.to_reduced(ANGSTROM).unwrap();
+        assert_eq!(coordinates.column(0).to_vec(), vec![1.0, 2.0, 3.0]);
+        assert_eq!(pore.sigma_ss, arr1(&[3.0]));
+
+        // charge style: id type q x y z
+        fs::write(&path, data("Atoms # charge")).unwrap();
+        let pore = Pore3D::from_lammps_data(&func, &path, [4; 3], None, None).unwrap();
+        let coordinates = pore.coordinates.to_reduced(ANGSTROM).unwrap();
+        assert_eq!(coordinates.column(0).to_vec(), vec![1.0, 2.0, 3.0]);
+        assert_eq!(pore.sigma_ss, arr1(&[3.5]));
+
+        // six columns without a hint are ambiguous
+        fs::write(&path, data("Atoms")).unwrap();
+        assert!(Pore3D::from_lammps_data(&func, &path, [4; 3], None, None).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn pore_labels_non_periodic() {
+        let func = Rc::new(FMTFunctional::new(&arr1(&[1.0]), FMTVersion::WhiteBear));
+        let bulk = State::new_nvt(
+            &func,
+            300.0 * KELVIN,
+            NAV * MOL * ANGSTROM.powi(3) / 0.1,
+            &(arr1(&[1.0]) * MOL),
+        )
+        .unwrap();
+        // a solid slab in the middle of the z axis separates the first and
+        // the last layer of grid points
+        let external_potential = Array4::from_shape_fn((1, 4, 4, 4), |(_, _, _, k)| {
+            if k == 1 || k == 2 {
+                MAX_POTENTIAL
+            } else {
+                0.0
+            }
+        });
+        for &(periodic, n) in &[([true; 3], 1), ([true, true, false], 2)] {
+            let pore = Pore3D::new(
+                &func,
+                [8.0 * ANGSTROM; 3],
+                [4; 3],
+                arr2(&[[4.0], [4.0], [4.0]]) * ANGSTROM,
+                arr1(&[1.0]),
+                arr1(&[0.0]),
+                None,
+                None,
+            )
+            .periodic(periodic);
+            let profile = pore.initialize(&bulk, Some(&external_potential)).unwrap();
+            let (labels, n_pores) = profile.pore_labels();
+            assert_eq!(n_pores, n);
+            assert_eq!(labels[[0, 0, 0]], 1);
+            assert_eq!(labels[[0, 0, 1]], 0);
+            assert_eq!(labels[[0, 0, 3]], n);
+        }
+    }
+
+    #[test]
+    fn per_component_potential_length() {
+        let func = Rc::new(FMTFunctional::new(&arr1(&[1.0]), FMTVersion::WhiteBear));
+        let pore = |n| {
+            Pore1DBuilder::new(&func)
+                .geometry(AxisGeometry::Cartesian)
+                .pore_size(10.0 * ANGSTROM)
+                .potential(ExternalPotential::PerComponent(vec![
+                    ExternalPotential::HardWall { sigma_ss: 1.0 };
+                    n
+                ]))
+                .build()
+        };
+        assert!(pore(1).is_ok());
+        assert!(pore(2).is_err());
+    }
 }
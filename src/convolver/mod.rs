@@ -4,8 +4,10 @@ use ndarray::prelude::*;
 use ndarray::{Axis as Axis_nd, RemoveAxis, ScalarOperand, Slice};
 use num_dual::*;
 use rustdct::DctNum;
+use std::cell::Cell;
 use std::ops::{AddAssign, MulAssign, SubAssign};
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 mod periodic_convolver;
 mod transform;
@@ -34,6 +36,48 @@ pub trait Convolver<T, D: Dimension> {
     ) -> Array<T, D::Larger>;
 }
 
+/// Convolver that counts and times the convolutions of another convolver.
+pub(crate) struct TimedConvolver<T, D: Dimension> {
+    convolver: Rc<dyn Convolver<T, D>>,
+    pub(crate) count: Cell<usize>,
+    pub(crate) time: Cell<Duration>,
+}
+
+impl<T, D: Dimension> TimedConvolver<T, D> {
+    pub(crate) fn new(convolver: Rc<dyn Convolver<T, D>>) -> Self {
+        Self {
+            convolver,
+            count: Cell::new(0),
+            time: Cell::new(Duration::ZERO),
+        }
+    }
+
+    fn timed<R>(&self, f: impl FnOnce(&dyn Convolver<T, D>) -> R) -> R {
+        let start = Instant::now();
+        let result = f(&*self.convolver);
+        self.count.set(self.count.get() + 1);
+        self.time.set(self.time.get() + start.elapsed());
+        result
+    }
+}
+
+impl<T, D: Dimension> Convolver<T, D> for TimedConvolver<T, D> {
+    fn convolve(&self, profile: Array<T, D>, weight_function: &WeightFunction<T>) -> Array<T, D> {
+        self.timed(|c| c.convolve(profile, weight_function))
+    }
+
+    fn weighted_densities(&self, density: &Array<T, D::Larger>) -> Vec<Array<T, D::Larger>> {
+        self.timed(|c| c.weighted_densities(density))
+    }
+
+    fn functional_derivative(
+        &self,
+        partial_derivatives: &[Array<T, D::Larger>],
+    ) -> Array<T, D::Larger> {
+        self.timed(|c| c.functional_derivative(partial_derivatives))
+    }
+}
+
 /// Base structure to hold either information about the weight function through
 /// `WeightFunctionInfo` or the weight functions themselves via
 /// `FFTWeightFunctions`.
@@ -92,6 +136,11 @@ where
     <D::Larger as Dimension>::Larger: Dimension<Smaller = D::Larger>,
 {
     /// Create the appropriate FFT convolver for the given grid.
+    ///
+    /// The transforms are planned with `rustfft` and `rustdct`, which fall back
+    /// to Rader's or Bluestein's algorithm for lengths with large prime factors.
+    /// Arbitrary grid sizes are therefore supported in $O(N\log N)$, but lengths
+    /// that factor into small primes (see [Grid::next_fast_len]) are faster.
     pub fn plan(
         grid: &Grid,
         weight_functions: &[WeightFunctionInfo<T>],
@@ -101,14 +150,20 @@ where
             Grid::Polar(r) => CurvilinearConvolver::new(r, &[], weight_functions, lanczos),
             Grid::Spherical(r) => CurvilinearConvolver::new(r, &[], weight_functions, lanczos),
             Grid::Cartesian1(z) => Self::new(Some(z), &[], weight_functions, lanczos),
+            Grid::Periodical1(z) => PeriodicConvolver::new(&[z], None, weight_functions, lanczos),
             Grid::Cylindrical { r, z } => {
                 CurvilinearConvolver::new(r, &[z], weight_functions, lanczos)
             }
             Grid::Cartesian2(x, y) => Self::new(Some(x), &[y], weight_functions, lanczos),
-            Grid::Periodical2(x, y) => PeriodicConvolver::new(&[x, y], weight_functions, lanczos),
+            Grid::Periodical2(x, y) => {
+                PeriodicConvolver::new(&[x, y], None, weight_functions, lanczos)
+            }
             Grid::Cartesian3(x, y, z) => Self::new(Some(x), &[y, z], weight_functions, lanczos),
             Grid::Periodical3(x, y, z) => {
-                PeriodicConvolver::new(&[x, y, z], weight_functions, lanczos)
+                PeriodicConvolver::new(&[x, y, z], None, weight_functions, lanczos)
+            }
+            Grid::Triclinic { x, y, z, cell } => {
+                PeriodicConvolver::new(&[x, y, z], Some(cell), weight_functions, lanczos)
             }
         }
     }
@@ -639,3 +694,40 @@ where
         functional_derivative
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quantity::si::ANGSTROM;
+    use std::f64::consts::PI;
+
+    /// Convolve a cosine profile with a normalized Heaviside weight function
+    /// and return the amplitude of the cosine in the result.
+    fn convolved_amplitude(points: usize, length: f64, k: f64) -> f64 {
+        let axis = Axis::new_cartesian(points, length * ANGSTROM, None).unwrap();
+        let z = axis.grid.clone();
+        let convolver = ConvolverFFT::<f64, Ix1>::plan(&Grid::Cartesian1(axis), &[], None);
+        let weight_function = WeightFunction::new_scaled(arr1(&[1.5]), WeightFunctionShape::Theta);
+        let profile = z.mapv(|z| 1.0 + 0.5 * (k * z).cos());
+        let result = convolver.convolve(profile, &weight_function);
+        (result - 1.0)
+            .iter()
+            .zip(z.iter())
+            .map(|(r, z)| r * (k * z).cos())
+            .sum::<f64>()
+            * 2.0
+            / points as f64
+    }
+
+    #[test]
+    fn prime_grid_length() {
+        let length = 20.0;
+        let k = 3.0 * PI / length;
+        let x = 1.5 * k;
+        let exact = 0.5 * (x.sph_j0() + x.sph_j2());
+        let prime = convolved_amplitude(251, length, k);
+        let fast = convolved_amplitude(256, length, k);
+        assert!((fast - exact).abs() < 1e-12);
+        assert!((prime - fast).abs() < 1e-12);
+    }
+}
@@ -3,6 +3,7 @@ use crate::geometry::Axis;
 use crate::weight_functions::{WeightFunction, WeightFunctionInfo};
 use ndarray::Axis as Axis_nd;
 use ndarray::*;
+use ndarray_linalg::Inverse;
 use num_dual::DualNum;
 use rustfft::num_complex::Complex;
 use rustfft::{Fft, FftDirection, FftNum, FftPlanner};
@@ -31,8 +32,14 @@ where
     D::Larger: Dimension<Smaller = D>,
     <D::Larger as Dimension>::Larger: Dimension<Smaller = D::Larger>,
 {
+    /// Create a new periodic convolver.
+    ///
+    /// By default, the axes are orthogonal. For a non-orthogonal unit cell, the
+    /// (reduced) cell vectors are provided in the rows of `cell` and the axes are
+    /// laid out along the cell vectors.
     pub fn new(
         axes: &[&Axis],
+        cell: Option<&Array2<f64>>,
         weight_functions: &[WeightFunctionInfo<T>],
         lanczos: Option<i32>,
     ) -> Rc<dyn Convolver<T, D>> {
@@ -40,6 +47,7 @@ where
         let mut planner = FftPlanner::new();
         let mut forward_transforms = Vec::with_capacity(axes.len());
         let mut inverse_transforms = Vec::with_capacity(axes.len());
+        let mut m_vec = Vec::with_capacity(axes.len());
         let mut k_vec = Vec::with_capacity(axes.len());
         let mut lengths = Vec::with_capacity(axes.len());
         for ax in axes {
@@ -47,24 +55,33 @@ where
             forward_transforms.push(planner.plan_fft_forward(points));
             inverse_transforms.push(planner.plan_fft_inverse(points));
             let (min, max) = (-(points as isize / 2), (points as isize - 1) / 2);
-            let k_x: Array1<_> = (0..=max)
-                .chain(min..0)
-                .map(|i| 2.0 * PI * i as f64 / ax.length())
-                .collect();
+            let m_x: Array1<_> = (0..=max).chain(min..0).map(|i| i as f64).collect();
 
-            k_vec.push(k_x);
+            k_vec.push(&m_x * (2.0 * PI / ax.length()));
+            m_vec.push(m_x);
             lengths.push(ax.length());
         }
 
+        // Reciprocal vectors (in the columns) of the unit cell
+        let reciprocal = match cell {
+            Some(cell) => cell.inv().unwrap() * (2.0 * PI),
+            None => Array2::from_diag(&lengths.iter().map(|l| 2.0 * PI / l).collect::<Array1<_>>()),
+        };
+
         // Calculate the full k vectors
         let mut dim = vec![k_vec.len()];
         k_vec.iter().for_each(|k_x| dim.push(k_x.len()));
         let mut k: Array<_, D::Larger> = Array::zeros(dim).into_dimensionality().unwrap();
         let mut k_abs = Array::zeros(k.raw_dim().remove_axis(Axis_nd(0)));
-        for (i, (mut k_i, k_x)) in k.outer_iter_mut().zip(k_vec.iter()).enumerate() {
-            k_i.lanes_mut(Axis_nd(i))
-                .into_iter()
-                .for_each(|mut l| l.assign(k_x));
+        for (i, mut k_i) in k.outer_iter_mut().enumerate() {
+            for (j, m_x) in m_vec.iter().enumerate() {
+                let b = reciprocal[(i, j)];
+                if b != 0.0 {
+                    k_i.lanes_mut(Axis_nd(j))
+                        .into_iter()
+                        .for_each(|mut l| l.scaled_add(b, m_x));
+                }
+            }
             k_abs.add_assign(&k_i.mapv(|k| k.powi(2)));
         }
         k_abs.map_inplace(|k| *k = k.sqrt());
@@ -33,6 +33,8 @@ pub(super) trait FourierTransform<T: DualNum<f64>> {
     fn back_transform(&self, f_k: ArrayViewMut1<T>, f_r: ArrayViewMut1<T>, scalar: bool);
 }
 
+/// Cosine/sine transforms for cartesian axes, which imply reflective
+/// boundaries at both ends of the axis.
 pub(super) struct CartesianTransform<T> {
     dct: Arc<dyn TransformType2And3<T>>,
 }
@@ -124,6 +126,10 @@ impl<T: DualNum<f64> + DctNum + ScalarOperand> FourierTransform<T> for Cartesian
     }
 }
 
+/// Spherical Bessel transforms calculated from sine/cosine transforms of
+/// $rf(r)$. The odd extension of $rf(r)$ implies that scalar profiles are
+/// even and vector profiles are odd at $r=0$, i.e., the flux through the
+/// center vanishes by construction.
 pub(super) struct SphericalTransform<T> {
     r_grid: Array1<f64>,
     k_grid: Array1<f64>,
@@ -213,6 +219,10 @@ impl<T: DualNum<f64> + DctNum + ScalarOperand> FourierTransform<T> for Spherical
     }
 }
 
+/// Logarithmic Hankel transforms for polar axes. The Bessel functions of
+/// order 0 (scalar) and 1 (vector) imply that scalar profiles are even and vector
+/// profiles are odd at $r=0$, so the center does not require a separate
+/// boundary condition.
 pub(super) struct PolarTransform<T: DctNum> {
     r_grid: Array1<f64>,
     k_grid: Array1<f64>,
@@ -0,0 +1,175 @@
+use crate::geometry::{Axis, Grid};
+use feos_core::{EosError, EosResult, EosUnit};
+use ndarray::{s, Array, Array1, Array2, Dimension, Ix2};
+use quantity::QuantityScalar;
+use std::f64::consts::PI;
+
+/// $\frac{e^2}{4\pi\varepsilon_0k_\mathrm{B}}$ in units of Å K.
+const BJERRUM_LENGTH_TEMPERATURE: f64 = 167_101.0;
+const MAX_ITER_NEUTRALITY: usize = 50;
+const TOL_NEUTRALITY: f64 = 1e-12;
+const MAX_STEP_NEUTRALITY: f64 = 5.0;
+
+/// Mean-field electrostatics of ions in a charged slit.
+///
+/// The reduced electrostatic potential $\psi=\frac{e\phi}{k_\mathrm{B}T}$ is obtained
+/// from the 1D Poisson equation
+/// $$\frac{\mathrm{d}^2\psi}{\mathrm{d}z^2}=-4\pi l_\mathrm{B}\left(\sum_\alpha z_\alpha\rho_\alpha(z)+\sigma\delta(z-z_\mathrm{w})\right)$$
+/// with the Bjerrum length $l_\mathrm{B}$ and the surface charge density $\sigma$ of the
+/// wall at $z_\mathrm{w}$. As in slit pores, the first grid point lies next to the
+/// symmetry plane of the system, where the electric field vanishes and the potential
+/// is set to 0. The potential is solved in every iteration of the DFT solver and
+/// added to the external potential of the segments.
+///
+/// Thermodynamic properties of the profile (e.g., the grand potential) do not
+/// include the electrostatic energy.
+///
+/// Optionally, electroneutrality of the system, i.e., the balance of the total
+/// charge of the fluid and the surface charge of the wall, is enforced. In every
+/// iteration, a uniform (Donnan) potential $\Delta\psi$ is added to the
+/// electrostatic potential so that the updated density profile is neutral.
+#[derive(Clone)]
+pub struct Electrostatics {
+    valences: Array1<f64>,
+    relative_permittivity: f64,
+    surface_charge_density: f64,
+    wall_position: f64,
+    charge_neutrality: bool,
+}
+
+impl Electrostatics {
+    /// Create a new electrostatics specification.
+    ///
+    /// The valences are given per component and the surface charge density as
+    /// number of elementary charges per area.
+    pub fn new<U: EosUnit>(
+        valences: Array1<f64>,
+        relative_permittivity: f64,
+        surface_charge_density: QuantityScalar<U>,
+        wall_position: QuantityScalar<U>,
+    ) -> EosResult<Self> {
+        Ok(Self {
+            valences,
+            relative_permittivity,
+            surface_charge_density: surface_charge_density
+                .to_reduced(U::reference_length().powi(-2))?,
+            wall_position: wall_position.to_reduced(U::reference_length())?,
+            charge_neutrality: false,
+        })
+    }
+
+    /// Enforce the electroneutrality of the system.
+    pub fn charge_neutrality(mut self, charge_neutrality: bool) -> Self {
+        self.charge_neutrality = charge_neutrality;
+        self
+    }
+
+    pub(crate) fn enforces_charge_neutrality(&self) -> bool {
+        self.charge_neutrality
+    }
+
+    fn axis<'a>(&self, grid: &'a Grid, component_index: &Array1<usize>) -> EosResult<&'a Axis> {
+        let axis = match grid {
+            Grid::Cartesian1(z) => z,
+            _ => {
+                return Err(EosError::Error(
+                    "Electrostatics are only implemented for 1D cartesian grids.".into(),
+                ))
+            }
+        };
+        let components = component_index.iter().max().map_or(0, |&c| c + 1);
+        if self.valences.len() != components {
+            return Err(EosError::Error(format!(
+                "{} valences were provided for {} components.",
+                self.valences.len(),
+                components
+            )));
+        }
+        Ok(axis)
+    }
+
+    /// Calculate the reduced energy $z_\alpha\Delta\psi$ of every segment in the
+    /// uniform potential $\Delta\psi$ that makes the system neutral.
+    ///
+    /// `density` is the (unconstrained) segment density that results from the
+    /// current potential. The potential shift is found by Newton's method from
+    /// $\sum_\alpha z_\alpha\int\rho_\alpha(z)e^{-z_\alpha\Delta\psi/m_\alpha}\mathrm{d}z+\sigma=0$.
+    pub(crate) fn neutralizing_potential<D: Dimension>(
+        &self,
+        grid: &Grid,
+        component_index: &Array1<usize>,
+        m: &Array1<f64>,
+        density: &Array<f64, D>,
+    ) -> EosResult<Array1<f64>> {
+        let axis = self.axis(grid, component_index)?;
+        let density = density.view().into_dimensionality::<Ix2>().unwrap();
+        let valence = component_index.mapv(|c| self.valences[c]);
+        let width = &axis.edges.slice(s![1..]) - &axis.edges.slice(s![..-1]);
+        let moles = density.dot(&width);
+        let surface_charge = if self.wall_position < axis.grid[axis.grid.len() - 1] {
+            self.surface_charge_density
+        } else {
+            0.0
+        };
+
+        let mut delta_psi = 0.0;
+        for _ in 0..MAX_ITER_NEUTRALITY {
+            let mut charge = surface_charge;
+            let mut derivative = 0.0;
+            for ((&z, &n), &m) in valence.iter().zip(moles.iter()).zip(m.iter()) {
+                let q = z * n * (-z * delta_psi / m).exp();
+                charge += q;
+                derivative -= q * z / m;
+            }
+            if charge.abs() < TOL_NEUTRALITY {
+                return Ok(&valence * delta_psi);
+            }
+            if derivative == 0.0 {
+                break;
+            }
+            let step = (charge / derivative).clamp(-MAX_STEP_NEUTRALITY, MAX_STEP_NEUTRALITY);
+            delta_psi -= step;
+        }
+        Err(EosError::NotConverged("Electroneutrality".into()))
+    }
+
+    /// Calculate the reduced electrostatic energy $z_\alpha\psi$ of every segment.
+    pub(crate) fn potential<D: Dimension>(
+        &self,
+        grid: &Grid,
+        component_index: &Array1<usize>,
+        density: &Array<f64, D>,
+        temperature: f64,
+    ) -> EosResult<Array<f64, D>> {
+        let axis = self.axis(grid, component_index)?;
+        let density = density.view().into_dimensionality::<Ix2>().unwrap();
+        let valence = component_index.mapv(|c| self.valences[c]);
+        let charge = valence.dot(&density);
+        let bjerrum_length =
+            BJERRUM_LENGTH_TEMPERATURE / (self.relative_permittivity * temperature);
+
+        // enclosed charge and derivative of the potential at every grid point
+        let z = &axis.grid;
+        let edges = &axis.edges;
+        let mut enclosed = 0.0;
+        let field = Array1::from_shape_fn(z.len(), |k| {
+            let width = edges[k + 1] - edges[k];
+            let mut q = enclosed + 0.5 * charge[k] * width;
+            enclosed += charge[k] * width;
+            if self.wall_position < z[k] {
+                q += self.surface_charge_density;
+            }
+            -4.0 * PI * bjerrum_length * q
+        });
+
+        // integrate the field starting from the symmetry plane
+        let mut psi = Array1::zeros(z.len());
+        psi[0] = 0.5 * field[0] * (z[0] - edges[0]);
+        for k in 1..z.len() {
+            psi[k] = psi[k - 1] + 0.5 * (field[k - 1] + field[k]) * (z[k] - z[k - 1]);
+        }
+
+        let potential = Array2::from_shape_fn(density.raw_dim(), |(s, k)| valence[s] * psi[k]);
+        Ok(potential.into_dimensionality().unwrap())
+    }
+}
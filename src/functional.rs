@@ -136,6 +136,14 @@ pub trait HelmholtzEnergyFunctional: Sized {
             .map(|c| c.weight_functions(temperature))
             .collect()
     }
+
+    /// Return the largest radius of all weight functions of all contributions
+    /// and components at the given (reduced) temperature.
+    fn max_weight_function_radius(&self, temperature: f64) -> f64 {
+        self.weight_functions(temperature)
+            .iter()
+            .fold(0.0, |acc, w| acc.max(w.max_kernel_radius()))
+    }
 }
 
 impl<T: HelmholtzEnergyFunctional> DFT<T> {
@@ -150,23 +158,101 @@ impl<T: HelmholtzEnergyFunctional> DFT<T> {
         D: Dimension,
         D::Larger: Dimension<Smaller = D>,
     {
-        // Calculate residual Helmholtz energy density and functional derivative
         let t = temperature.to_reduced(U::reference_temperature()).unwrap();
         let rho = density.to_reduced(U::reference_density()).unwrap();
-        let (mut f, dfdrho) = self.functional_derivative(t, &rho, convolver)?;
+        Ok(self.grand_potential_density_reduced(t, &rho, convolver)? * t * U::reference_pressure())
+    }
+
+    /// Return the grand potential density divided by the temperature.
+    ///
+    /// The element-wise operations are parallelized over the grid points.
+    pub(crate) fn grand_potential_density_reduced<D>(
+        &self,
+        temperature: f64,
+        density: &Array<f64, D::Larger>,
+        convolver: &Rc<dyn Convolver<f64, D>>,
+    ) -> EosResult<Array<f64, D>>
+    where
+        D: Dimension,
+        D::Larger: Dimension<Smaller = D>,
+    {
+        // Calculate residual Helmholtz energy density and functional derivative
+        let (mut f, dfdrho) = self.functional_derivative(temperature, density, convolver)?;
 
         // calculate the grand potential density
-        for ((rho, dfdrho), &m) in rho.outer_iter().zip(dfdrho.outer_iter()).zip(self.m.iter()) {
-            f -= &((&dfdrho + m) * &rho);
+        let bond_lengths = self.functional.bond_lengths(temperature);
+        for (i, ((rho, dfdrho), &m)) in density
+            .outer_iter()
+            .zip(dfdrho.outer_iter())
+            .zip(self.m.iter())
+            .enumerate()
+        {
+            let bonds = bond_lengths
+                .node_indices()
+                .find(|s| s.index() == i)
+                .map_or(0, |s| bond_lengths.neighbors(s).count());
+            let c = 0.5 * bonds as f64;
+            Zip::from(&mut f)
+                .and(&rho)
+                .and(&dfdrho)
+                .par_for_each(|f, &rho, &dfdrho| *f -= (dfdrho + m - c) * rho);
         }
+        Ok(f)
+    }
 
-        let bond_lengths = self.functional.bond_lengths(t);
-        for segment in bond_lengths.node_indices() {
-            let n = bond_lengths.neighbors(segment).count();
-            f += &(&rho.index_axis(Axis(0), segment.index()) * (0.5 * n as f64));
+    /// Return the grand potential density divided by the temperature split
+    /// into the contributions of the individual segments.
+    ///
+    /// The terms $-\rho_\alpha\left(\frac{\delta\beta F^\mathrm{res}}{\delta\rho_\alpha}+m_\alpha-\frac{n_\alpha^\mathrm{bonds}}{2}\right)$
+    /// are strictly segment-wise. The residual Helmholtz energy density is
+    /// attributed to the segments according to the local segment fractions
+    /// $\rho_\alpha/\sum_\beta\rho_\beta$ (evenly where the density
+    /// vanishes), so that the sum over all segments equals the grand potential
+    /// density.
+    pub(crate) fn grand_potential_density_segments_reduced<D>(
+        &self,
+        temperature: f64,
+        density: &Array<f64, D::Larger>,
+        convolver: &Rc<dyn Convolver<f64, D>>,
+    ) -> EosResult<Array<f64, D::Larger>>
+    where
+        D: Dimension,
+        D::Larger: Dimension<Smaller = D>,
+    {
+        // Calculate residual Helmholtz energy density and functional derivative
+        let (f, dfdrho) = self.functional_derivative(temperature, density, convolver)?;
+        let rho_total = density.sum_axis(Axis(0));
+        let x_empty = 1.0 / self.m.len() as f64;
+
+        let bond_lengths = self.functional.bond_lengths(temperature);
+        let mut omega = Array::zeros(density.raw_dim());
+        for (i, (((mut omega, rho), dfdrho), &m)) in omega
+            .outer_iter_mut()
+            .zip(density.outer_iter())
+            .zip(dfdrho.outer_iter())
+            .zip(self.m.iter())
+            .enumerate()
+        {
+            let bonds = bond_lengths
+                .node_indices()
+                .find(|s| s.index() == i)
+                .map_or(0, |s| bond_lengths.neighbors(s).count());
+            let c = 0.5 * bonds as f64;
+            Zip::from(&mut omega)
+                .and(&f)
+                .and(&rho_total)
+                .and(&rho)
+                .and(&dfdrho)
+                .par_for_each(|o, &f, &rho_total, &rho, &dfdrho| {
+                    let x = if rho_total > 0.0 {
+                        rho / rho_total
+                    } else {
+                        x_empty
+                    };
+                    *o = x * f - (dfdrho + m - c) * rho;
+                });
         }
-
-        Ok(f * t * U::reference_pressure())
+        Ok(omega)
     }
 
     fn intrinsic_helmholtz_energy_density<D, N>(
@@ -265,6 +351,44 @@ impl<T: HelmholtzEnergyFunctional> DFT<T> {
             .collect())
     }
 
+    /// Return the reduced Helmholtz energy density of every contribution of the
+    /// functional together with its name. The first entry is the ideal chain
+    /// contribution, the remaining entries follow [HelmholtzEnergyFunctional::contributions].
+    pub fn helmholtz_energy_density_contributions<D>(
+        &self,
+        temperature: f64,
+        density: &Array<f64, D::Larger>,
+        convolver: &Rc<dyn Convolver<f64, D>>,
+        contributions: Contributions,
+    ) -> EosResult<Vec<(String, Array<f64, D>)>>
+    where
+        D: Dimension,
+        D::Larger: Dimension<Smaller = D>,
+    {
+        let weighted_densities = convolver.weighted_densities(density);
+        let functional_contributions = self.functional.contributions();
+        let mut helmholtz_energy_density = Vec::with_capacity(functional_contributions.len() + 1);
+        helmholtz_energy_density.push((
+            self.ideal_chain_contribution.to_string(),
+            self.ideal_chain_contribution
+                .calculate_helmholtz_energy_density::<D, f64>(density, contributions)?,
+        ));
+        for (c, wd) in functional_contributions.iter().zip(weighted_densities) {
+            let nwd = wd.shape()[0];
+            let ngrid = wd.len() / nwd;
+            helmholtz_energy_density.push((
+                c.to_string(),
+                c.calculate_helmholtz_energy_density(
+                    temperature,
+                    wd.into_shape((nwd, ngrid)).unwrap().view(),
+                )?
+                .into_shape(density.raw_dim().remove_axis(Axis(0)))
+                .unwrap(),
+            ));
+        }
+        Ok(helmholtz_energy_density)
+    }
+
     pub fn internal_energy_density<D>(
         &self,
         temperature: f64,
@@ -410,3 +534,125 @@ impl<T: HelmholtzEnergyFunctional> DFT<T> {
         i
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convolver::ConvolverFFT;
+    use crate::fundamental_measure_theory::{FMTContribution, FMTProperties, FMTVersion};
+    use crate::geometry::Grid;
+    use quantity::si::ANGSTROM;
+    use std::fmt;
+
+    struct HardSpheres;
+
+    impl FMTProperties for HardSpheres {
+        fn component_index(&self) -> Array1<usize> {
+            arr1(&[0])
+        }
+
+        fn chain_length(&self) -> Array1<f64> {
+            arr1(&[1.0])
+        }
+
+        fn hs_diameter<N: DualNum<f64>>(&self, _: N) -> Array1<N> {
+            arr1(&[N::from(1.0)])
+        }
+    }
+
+    /// Mean-field dipolar term $\beta f=-\frac{c\rho^2}{T}$ in place of a
+    /// polar contribution.
+    struct Dipole {
+        strength: f64,
+    }
+
+    impl<N: DualNum<f64>> FunctionalContributionDual<N> for Dipole {
+        fn weight_functions(&self, _: N) -> WeightFunctionInfo<N> {
+            WeightFunctionInfo::new(arr1(&[0]), true)
+        }
+
+        fn calculate_helmholtz_energy_density(
+            &self,
+            temperature: N,
+            weighted_densities: ArrayView2<N>,
+        ) -> EosResult<Array1<N>> {
+            Ok(weighted_densities
+                .index_axis(Axis(0), 0)
+                .mapv(|rho| -rho * rho * self.strength / temperature))
+        }
+    }
+
+    impl fmt::Display for Dipole {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "Dipole")
+        }
+    }
+
+    struct TestFunctional {
+        contributions: Vec<Box<dyn FunctionalContribution>>,
+    }
+
+    impl TestFunctional {
+        fn new(polar: bool) -> DFT<Self> {
+            let mut contributions: Vec<Box<dyn FunctionalContribution>> = vec![Box::new(
+                FMTContribution::new(&Rc::new(HardSpheres), FMTVersion::WhiteBear),
+            )];
+            if polar {
+                contributions.push(Box::new(Dipole { strength: 50.0 }));
+            }
+            DFT::new_homosegmented(Self { contributions }, &arr1(&[1.0]))
+        }
+    }
+
+    impl HelmholtzEnergyFunctional for TestFunctional {
+        fn contributions(&self) -> &[Box<dyn FunctionalContribution>] {
+            &self.contributions
+        }
+
+        fn subset(&self, _: &[usize]) -> DFT<Self> {
+            unimplemented!()
+        }
+
+        fn compute_max_density(&self, _: &Array1<f64>) -> f64 {
+            1.0
+        }
+    }
+
+    #[test]
+    fn helmholtz_energy_density_contributions() {
+        let (temperature, density) = (300.0, 0.3);
+        let contribution_names = |polar| {
+            let dft = TestFunctional::new(polar);
+            let axis = crate::geometry::Axis::new_cartesian(16, 10.0 * ANGSTROM, None).unwrap();
+            let convolver = ConvolverFFT::<f64, Ix1>::plan(
+                &Grid::Cartesian1(axis),
+                &dft.functional.weight_functions(temperature),
+                None,
+            );
+            let contributions = dft
+                .helmholtz_energy_density_contributions(
+                    temperature,
+                    &Array2::from_elem((1, 16), density),
+                    &convolver,
+                    Contributions::Residual,
+                )
+                .unwrap();
+
+            // the contributions add up to the residual Helmholtz energy density
+            let total = dft.evaluate_residual(&StateHD::new(temperature, 1.0, arr1(&[density])));
+            let sum = contributions
+                .iter()
+                .fold(Array1::<f64>::zeros(16), |acc, (_, f)| acc + f);
+            assert!(sum.iter().all(|f| (f - total).abs() < 1e-10 * total.abs()));
+            contributions
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        };
+        let apolar = contribution_names(false);
+        let polar = contribution_names(true);
+        assert!(!apolar.contains(&"Dipole".to_string()));
+        assert_eq!(&polar[..apolar.len()], &apolar[..]);
+        assert_eq!(&polar[apolar.len()..], &["Dipole".to_string()]);
+    }
+}
@@ -75,6 +75,19 @@ pub trait FunctionalContribution:
     + FunctionalContributionDual<Dual3<Dual64, f64>>
     + Display
 {
+    /// Return the fraction of non-bonded association sites, with the sites in
+    /// the first axis and the grid points in the second axis.
+    ///
+    /// Overwrite this in association contributions. The default implementation
+    /// returns `None` for contributions without association sites.
+    fn association_fraction(
+        &self,
+        _temperature: f64,
+        _weighted_densities: ArrayView2<f64>,
+    ) -> EosResult<Option<Array2<f64>>> {
+        Ok(None)
+    }
+
     fn first_partial_derivatives(
         &self,
         temperature: f64,
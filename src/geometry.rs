@@ -1,19 +1,32 @@
-use feos_core::{EosResult, EosUnit};
-use ndarray::Array1;
+use feos_core::{EosError, EosResult, EosUnit};
+use ndarray::{Array1, Array2};
 use quantity::{QuantityArray1, QuantityArray2, QuantityScalar};
 use std::f64::consts::{FRAC_PI_3, PI};
 
 /// Grids with up to three dimensions.
+///
+/// Cartesian grids have reflective boundaries at both ends of every axis,
+/// periodical grids are periodic in every direction.
 #[derive(Clone)]
 pub enum Grid {
     Cartesian1(Axis),
+    Periodical1(Axis),
     Cartesian2(Axis, Axis),
     Periodical2(Axis, Axis),
     Cartesian3(Axis, Axis, Axis),
     Periodical3(Axis, Axis, Axis),
     Spherical(Axis),
     Polar(Axis),
-    Cylindrical { r: Axis, z: Axis },
+    Cylindrical {
+        r: Axis,
+        z: Axis,
+    },
+    Triclinic {
+        x: Axis,
+        y: Axis,
+        z: Axis,
+        cell: Array2<f64>,
+    },
 }
 
 impl Grid {
@@ -25,11 +38,26 @@ impl Grid {
         }
     }
 
+    /// Create a periodic grid in a triclinic unit cell.
+    ///
+    /// The rows of `cell` are the (reduced) cell vectors along which the axes
+    /// are laid out. The lengths of the axes have to match the lengths of the
+    /// cell vectors.
+    pub fn new_triclinic(mut x: Axis, y: Axis, z: Axis, cell: Array2<f64>) -> Self {
+        let norms: f64 = cell.outer_iter().map(|a| a.dot(&a).sqrt()).product();
+        let det = cell[(0, 0)] * (cell[(1, 1)] * cell[(2, 2)] - cell[(1, 2)] * cell[(2, 1)])
+            - cell[(0, 1)] * (cell[(1, 0)] * cell[(2, 2)] - cell[(1, 2)] * cell[(2, 0)])
+            + cell[(0, 2)] * (cell[(1, 0)] * cell[(2, 1)] - cell[(1, 1)] * cell[(2, 0)]);
+        x.integration_weights *= det.abs() / norms;
+        Self::Triclinic { x, y, z, cell }
+    }
+
     pub fn axes(&self) -> Vec<&Axis> {
         match self {
-            Self::Cartesian1(x) => vec![x],
+            Self::Cartesian1(x) | Self::Periodical1(x) => vec![x],
             Self::Cartesian2(x, y) | Self::Periodical2(x, y) => vec![x, y],
             Self::Cartesian3(x, y, z) | Self::Periodical3(x, y, z) => vec![x, y, z],
+            Self::Triclinic { x, y, z, .. } => vec![x, y, z],
             Self::Spherical(r) | Self::Polar(r) => vec![r],
             Self::Cylindrical { r, z } => vec![r, z],
         }
@@ -37,18 +65,105 @@ impl Grid {
 
     pub fn axes_mut(&mut self) -> Vec<&mut Axis> {
         match self {
-            Self::Cartesian1(x) => vec![x],
+            Self::Cartesian1(x) | Self::Periodical1(x) => vec![x],
             Self::Cartesian2(x, y) | Self::Periodical2(x, y) => vec![x, y],
             Self::Cartesian3(x, y, z) | Self::Periodical3(x, y, z) => vec![x, y, z],
+            Self::Triclinic { x, y, z, .. } => vec![x, y, z],
             Self::Spherical(r) | Self::Polar(r) => vec![r],
             Self::Cylindrical { r, z } => vec![r, z],
         }
     }
 
+    /// Return the name of the grid variant.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cartesian1(_) => "Cartesian1",
+            Self::Periodical1(_) => "Periodical1",
+            Self::Cartesian2(_, _) => "Cartesian2",
+            Self::Periodical2(_, _) => "Periodical2",
+            Self::Cartesian3(_, _, _) => "Cartesian3",
+            Self::Periodical3(_, _, _) => "Periodical3",
+            Self::Spherical(_) => "Spherical",
+            Self::Polar(_) => "Polar",
+            Self::Cylindrical { .. } => "Cylindrical",
+            Self::Triclinic { .. } => "Triclinic",
+        }
+    }
+
     pub fn grids(&self) -> Vec<&Array1<f64>> {
         self.axes().iter().map(|ax| &ax.grid).collect()
     }
 
+    /// Return the smallest number of grid points that is at least `n` and
+    /// factors into the primes 2, 3, and 5, for which FFTs are most efficient.
+    pub fn next_fast_len(n: usize) -> usize {
+        (n.max(1)..)
+            .find(|&m| {
+                let mut m = m;
+                for p in [2, 3, 5].iter() {
+                    while m % p == 0 {
+                        m /= p;
+                    }
+                }
+                m == 1
+            })
+            .unwrap()
+    }
+
+    /// Ratio of the actual volume of the grid and the product of the lengths
+    /// of its axes, which differs from 1 only for triclinic cells.
+    pub(crate) fn volume_factor(&self) -> f64 {
+        match self {
+            Self::Triclinic { x, .. } => x.integration_weights.sum() / x.length(),
+            _ => 1.0,
+        }
+    }
+
+    /// Return a grid that spans the same domain with the given number of
+    /// grid points along every axis.
+    pub fn with_points<U: EosUnit>(&self, points: &[usize]) -> EosResult<Self> {
+        let axes = self
+            .axes()
+            .iter()
+            .zip(points)
+            .map(|(ax, &n)| ax.with_points::<U>(n))
+            .collect::<EosResult<_>>()?;
+        Ok(self.with_axes(axes))
+    }
+
+    /// Return the same grid with the integration weights of all axes
+    /// determined by the given rule.
+    pub fn with_integration_rule<U: EosUnit>(&self, rule: IntegrationRule) -> EosResult<Self> {
+        let axes = self
+            .axes()
+            .iter()
+            .map(|ax| ax.with_integration_rule::<U>(rule))
+            .collect::<EosResult<_>>()?;
+        Ok(self.with_axes(axes))
+    }
+
+    fn with_axes(&self, axes: Vec<Axis>) -> Self {
+        let mut axes = axes.into_iter();
+        let mut next = || axes.next().unwrap();
+        match self {
+            Self::Cartesian1(_) => Self::Cartesian1(next()),
+            Self::Periodical1(_) => Self::Periodical1(next()),
+            Self::Cartesian2(_, _) => Self::Cartesian2(next(), next()),
+            Self::Periodical2(_, _) => Self::Periodical2(next(), next()),
+            Self::Cartesian3(_, _, _) => Self::Cartesian3(next(), next(), next()),
+            Self::Periodical3(_, _, _) => Self::Periodical3(next(), next(), next()),
+            Self::Spherical(_) => Self::Spherical(next()),
+            Self::Polar(_) => Self::Polar(next()),
+            Self::Cylindrical { .. } => Self::Cylindrical {
+                r: next(),
+                z: next(),
+            },
+            Self::Triclinic { cell, .. } => {
+                Self::new_triclinic(next(), next(), next(), cell.clone())
+            }
+        }
+    }
+
     pub(crate) fn integration_weights(&self) -> Vec<&Array1<f64>> {
         self.axes()
             .iter()
@@ -73,7 +188,7 @@ pub enum AxisGeometry {
 }
 
 impl AxisGeometry {
-    fn dimension(&self) -> i32 {
+    pub(crate) fn dimension(&self) -> i32 {
         match self {
             Self::Cartesian => 1,
             Self::Polar => 2,
@@ -82,6 +197,19 @@ impl AxisGeometry {
     }
 }
 
+/// Quadrature rules for the integration along an axis.
+///
+/// `Default` uses the volumes of the grid cells. `Trapezoidal` and `Simpson`
+/// integrate the piecewise linear and piecewise quadratic interpolants of the
+/// profile (extrapolated to the edges of the axis) multiplied by the Jacobian
+/// ($2\pi r$ for polar and $4\pi r^2$ for spherical axes).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum IntegrationRule {
+    Default,
+    Trapezoidal,
+    Simpson,
+}
+
 /// An individual discretized axis.
 #[derive(Clone)]
 pub struct Axis {
@@ -90,6 +218,7 @@ pub struct Axis {
     pub edges: Array1<f64>,
     integration_weights: Array1<f64>,
     potential_offset: f64,
+    integration_rule: IntegrationRule,
 }
 
 impl Axis {
@@ -114,6 +243,7 @@ impl Axis {
             edges,
             integration_weights,
             potential_offset,
+            integration_rule: IntegrationRule::Default,
         })
     }
 
@@ -132,6 +262,7 @@ impl Axis {
             edges,
             integration_weights,
             potential_offset: 0.0,
+            integration_rule: IntegrationRule::Default,
         })
     }
 
@@ -178,6 +309,7 @@ impl Axis {
             edges,
             integration_weights,
             potential_offset: 0.0,
+            integration_rule: IntegrationRule::Default,
         })
     }
 
@@ -199,11 +331,104 @@ impl Axis {
             * U::reference_length();
         (match self.geometry {
             AxisGeometry::Cartesian => 1.0,
-            AxisGeometry::Polar => 4.0 * PI,
+            AxisGeometry::Polar => PI,
             AxisGeometry::Spherical => 4.0 * FRAC_PI_3,
         }) * length.powi(self.geometry.dimension())
     }
 
+    /// Return an axis of the same geometry and extent with the given number
+    /// of grid points.
+    pub fn with_points<U: EosUnit>(&self, points: usize) -> EosResult<Self> {
+        self.with_default_rule::<U>(points)?
+            .apply_integration_rule(self.integration_rule)
+    }
+
+    /// Return the same axis with the integration weights determined by the
+    /// given rule.
+    pub fn with_integration_rule<U: EosUnit>(&self, rule: IntegrationRule) -> EosResult<Self> {
+        self.with_default_rule::<U>(self.grid.len())?
+            .apply_integration_rule(rule)
+    }
+
+    /// Return the quadrature rule used for the integration weights.
+    pub fn integration_rule(&self) -> IntegrationRule {
+        self.integration_rule
+    }
+
+    fn with_default_rule<U: EosUnit>(&self, points: usize) -> EosResult<Self> {
+        let length = (self.edges[self.grid.len()] - self.edges[0]) * U::reference_length();
+        match self.geometry {
+            AxisGeometry::Cartesian => Self::new_cartesian(
+                points,
+                length - self.potential_offset * U::reference_length(),
+                Some(self.potential_offset),
+            ),
+            AxisGeometry::Polar => Self::new_polar(points, length),
+            AxisGeometry::Spherical => Self::new_spherical(points, length),
+        }
+    }
+
+    /// Replace the integration weights by the integrals of the Lagrange
+    /// polynomials of the piecewise interpolant times the Jacobian, which are
+    /// evaluated exactly with a three-point Gauss-Legendre quadrature.
+    fn apply_integration_rule(mut self, rule: IntegrationRule) -> EosResult<Self> {
+        let degree = match rule {
+            IntegrationRule::Default => return Ok(self),
+            IntegrationRule::Trapezoidal => 1,
+            IntegrationRule::Simpson => 2,
+        };
+        let n = self.grid.len();
+        if n <= degree {
+            return Err(EosError::Error(format!(
+                "The {:?} rule requires at least {} grid points.",
+                rule,
+                degree + 1
+            )));
+        }
+        let gauss_nodes = [-(0.6f64).sqrt(), 0.0, (0.6f64).sqrt()];
+        let gauss_weights = [5.0 / 9.0, 8.0 / 9.0, 5.0 / 9.0];
+        let geometry = self.geometry;
+        let jacobian = |r: f64| match geometry {
+            AxisGeometry::Cartesian => 1.0,
+            AxisGeometry::Polar => 2.0 * PI * r,
+            AxisGeometry::Spherical => 4.0 * PI * r * r,
+        };
+
+        let grid = &self.grid;
+        let mut weights = Array1::zeros(n);
+        let mut start = 0;
+        while start + 1 < n {
+            // nodes of the interpolant and the interval on which it is used
+            let first = start.min(n - 1 - degree);
+            let end = (start + degree).min(n - 1);
+            let lo = if start == 0 {
+                self.edges[0]
+            } else {
+                grid[start]
+            };
+            let hi = if end == n - 1 {
+                self.edges[n]
+            } else {
+                grid[end]
+            };
+            for (&t, &w) in gauss_nodes.iter().zip(gauss_weights.iter()) {
+                let x = 0.5 * (lo + hi) + 0.5 * (hi - lo) * t;
+                let f = 0.5 * (hi - lo) * w * jacobian(x);
+                for k in first..=first + degree {
+                    let l: f64 = (first..=first + degree)
+                        .filter(|&j| j != k)
+                        .map(|j| (x - grid[j]) / (grid[k] - grid[j]))
+                        .product();
+                    weights[k] += f * l;
+                }
+            }
+            start = end;
+        }
+        self.integration_weights = weights;
+        self.integration_rule = rule;
+        Ok(self)
+    }
+
     /// Interpolate a function on the given axis.
     pub fn interpolate<U: EosUnit>(
         &self,
@@ -236,3 +461,53 @@ impl Axis {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quantity::si::{SIUnit, ANGSTROM};
+
+    /// Absolute error of the integral of $e^{-r/2}$ on an axis of length 5.
+    fn integration_error(geometry: AxisGeometry, points: usize, rule: IntegrationRule) -> f64 {
+        let (a, l) = (0.5, 5.0);
+        let axis = match geometry {
+            AxisGeometry::Cartesian => Axis::new_cartesian(points, l * ANGSTROM, None),
+            AxisGeometry::Polar => Axis::new_polar(points, l * ANGSTROM),
+            AxisGeometry::Spherical => Axis::new_spherical(points, l * ANGSTROM),
+        }
+        .unwrap()
+        .with_integration_rule::<SIUnit>(rule)
+        .unwrap();
+        let e = (-a * l).exp();
+        let exact = match geometry {
+            AxisGeometry::Cartesian => (1.0 - e) / a,
+            AxisGeometry::Polar => 2.0 * PI * (1.0 / a.powi(2) - e * (l / a + 1.0 / a.powi(2))),
+            AxisGeometry::Spherical => {
+                4.0 * PI
+                    * (2.0 / a.powi(3) - e * (l * l / a + 2.0 * l / a.powi(2) + 2.0 / a.powi(3)))
+            }
+        };
+        let integral = (axis.grid.mapv(|r| (-a * r).exp()) * &axis.integration_weights).sum();
+        (integral - exact).abs()
+    }
+
+    #[test]
+    fn simpson_converges_faster_than_trapezoidal() {
+        for &geometry in &[
+            AxisGeometry::Cartesian,
+            AxisGeometry::Polar,
+            AxisGeometry::Spherical,
+        ] {
+            let order = |rule| {
+                let coarse = integration_error(geometry, 64, rule);
+                let fine = integration_error(geometry, 128, rule);
+                (fine, (coarse / fine).log2())
+            };
+            let (error_trapezoidal, order_trapezoidal) = order(IntegrationRule::Trapezoidal);
+            let (error_simpson, order_simpson) = order(IntegrationRule::Simpson);
+            assert!(order_trapezoidal > 1.0);
+            assert!(order_simpson > order_trapezoidal + 0.5);
+            assert!(error_simpson < error_trapezoidal);
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use crate::adsorption::{ExternalPotential, FluidParameters};
+use crate::convolver::ConvolverFFT;
+use crate::functional::{HelmholtzEnergyFunctional, DFT};
+use crate::geometry::{Axis, AxisGeometry, Grid};
+use crate::profile::{DFTProfile, DFTSpecifications, MAX_POTENTIAL};
+use crate::solver::DFTSolver;
+use feos_core::{EosError, EosResult, EosUnit, PhaseEquilibrium};
+use ndarray::{Array2, Array3, Axis as Axis_nd, Ix2};
+use quantity::QuantityScalar;
+use std::f64::consts::PI;
+
+/// Extent of the domain behind the wall in units of the largest segment diameter.
+const WALL_OFFSET: f64 = 2.0;
+/// Minimum distance from the wall (in units of the largest segment diameter) of
+/// the layers that are used to fit the equimolar surface.
+const MIN_FIT_DISTANCE: f64 = 2.0;
+
+/// Density profile and contact angle of a sessile droplet on a planar wall.
+///
+/// The droplet is described on an axisymmetric (cylindrical) grid with the
+/// wall perpendicular to the symmetry axis and stabilized by fixing the total
+/// number of molecules. The microscopic contact angle follows from a circle
+/// that is fitted to the equimolar surface of the droplet close to the wall.
+pub struct MeniscusProfile<U: EosUnit, F: HelmholtzEnergyFunctional> {
+    pub profile: DFTProfile<U, Ix2, F>,
+    pub vle: PhaseEquilibrium<U, DFT<F>, 2>,
+    pub contact_angle: Option<f64>,
+    pub radius_of_curvature: Option<QuantityScalar<U>>,
+    wall_position: f64,
+}
+
+impl<U: EosUnit, F: HelmholtzEnergyFunctional> Clone for MeniscusProfile<U, F> {
+    fn clone(&self) -> Self {
+        Self {
+            profile: self.profile.clone(),
+            vle: self.vle.clone(),
+            contact_angle: self.contact_angle,
+            radius_of_curvature: self.radius_of_curvature,
+            wall_position: self.wall_position,
+        }
+    }
+}
+
+impl<U: EosUnit, F: HelmholtzEnergyFunctional + FluidParameters> MeniscusProfile<U, F> {
+    pub fn solve_inplace(&mut self, solver: Option<&DFTSolver>, debug: bool) -> EosResult<()> {
+        // Solve the profile
+        self.profile.solve(solver, debug)?;
+
+        // Contact angle from the equimolar surface
+        let (radius, contact_angle) = self.fit_equimolar_surface()?;
+        self.radius_of_curvature = Some(radius * U::reference_length());
+        self.contact_angle = Some(contact_angle);
+        Ok(())
+    }
+
+    pub fn solve(mut self, solver: Option<&DFTSolver>) -> EosResult<Self> {
+        self.solve_inplace(solver, false)?;
+        Ok(self)
+    }
+
+    /// Fit a spherical cap $r^2+(h-h_c)^2=R^2$ to the equimolar radii of all
+    /// layers parallel to the wall and return its radius $R$ and the contact
+    /// angle $\cos\theta=-\frac{h_c}{R}$ (in reduced units and radians).
+    fn fit_equimolar_surface(&self) -> EosResult<(f64, f64)> {
+        let m = &self.profile.dft.m;
+        let indices = &self.profile.dft.component_index;
+        let rho = self.profile.density.to_reduced(U::reference_density())?;
+        let rho_l = self
+            .vle
+            .liquid()
+            .partial_density
+            .to_reduced(U::reference_density())?;
+        let rho_v = self
+            .vle
+            .vapor()
+            .partial_density
+            .to_reduced(U::reference_density())?;
+
+        // local fraction of the liquid phase from the total segment density
+        let shape = rho.shape();
+        let mut rho_total = Array2::zeros((shape[1], shape[2]));
+        let (mut rho_l_total, mut rho_v_total) = (0.0, 0.0);
+        for (i, &c) in indices.iter().enumerate() {
+            rho_total += &(&rho.index_axis(Axis_nd(0), i) * m[i]);
+            rho_l_total += rho_l[c] * m[i];
+            rho_v_total += rho_v[c] * m[i];
+        }
+        let x = (rho_total - rho_v_total) / (rho_l_total - rho_v_total);
+
+        // equimolar radius of every layer parallel to the wall
+        let sigma = *self.profile.dft.functional.sigma_ff().max().unwrap();
+        let weights = self.profile.grid.integration_weights()[0];
+        let mut points = Vec::new();
+        for (j, &z) in self.profile.grid.grids()[1].iter().enumerate() {
+            let h = self.wall_position - z;
+            if h < MIN_FIT_DISTANCE * sigma {
+                continue;
+            }
+            let area = (&x.index_axis(Axis_nd(1), j) * weights).sum();
+            let radius = (area.max(0.0) / PI).sqrt();
+            if radius > sigma {
+                points.push((radius, h));
+            }
+        }
+        if points.len() < 3 {
+            return Err(EosError::Error(
+                "The equimolar surface of the droplet could not be determined.".into(),
+            ));
+        }
+
+        // linear least squares fit of r^2 + h^2 = 2 h_c h + R^2 - h_c^2
+        let (mut s_hh, mut s_h, mut s_1, mut s_yh, mut s_y) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for &(r, h) in &points {
+            let y = r * r + h * h;
+            s_hh += h * h;
+            s_h += h;
+            s_1 += 1.0;
+            s_yh += y * h;
+            s_y += y;
+        }
+        let det = s_hh * s_1 - s_h * s_h;
+        let h_c = 0.5 * (s_yh * s_1 - s_h * s_y) / det;
+        let radius = ((s_hh * s_y - s_h * s_yh) / det + h_c * h_c).sqrt();
+        Ok((radius, (-h_c / radius).max(-1.0).min(1.0).acos()))
+    }
+}
+
+impl<U: EosUnit, F: HelmholtzEnergyFunctional + FluidParameters> MeniscusProfile<U, F> {
+    /// Initialize a droplet as a spherical cap with the given radius and
+    /// contact angle (in radians) on a wall described by `potential`.
+    ///
+    /// The wall is located at the end of the axial direction of a box with
+    /// the given radius and height. Distances from the wall are measured from
+    /// the origin of the wall potential.
+    pub fn new(
+        vle: &PhaseEquilibrium<U, DFT<F>, 2>,
+        potential: &ExternalPotential<U>,
+        n_grid: [usize; 2],
+        box_size: [QuantityScalar<U>; 2],
+        radius: QuantityScalar<U>,
+        contact_angle: f64,
+        potential_cutoff: Option<f64>,
+    ) -> EosResult<Self> {
+        let dft = &vle.vapor().eos;
+        let sigma = *dft.functional.sigma_ff().max().unwrap();
+        let potential_cutoff = potential_cutoff.unwrap_or(MAX_POTENTIAL);
+
+        // generate grid
+        let r = Axis::new_polar(n_grid[0], box_size[0])?;
+        let z = Axis::new_cartesian(n_grid[1], box_size[1], Some(WALL_OFFSET * sigma))?;
+        let wall_position = box_size[1].to_reduced(U::reference_length())?;
+
+        // calculate external potential
+        let t = vle
+            .vapor()
+            .temperature
+            .to_reduced(U::reference_temperature())?;
+        potential.check_geometry(AxisGeometry::Cartesian, dft.functional.m().len())?;
+        let wall_potential =
+            potential.calculate_cartesian_potential(&(wall_position - &z.grid), &dft.functional, t)
+                / t;
+        let n_segments = wall_potential.shape()[0];
+        let external_potential =
+            Array3::from_shape_fn((n_segments, r.grid.len(), z.grid.len()), |(i, _, j)| {
+                let v = wall_potential[[i, j]];
+                if z.grid[j] > wall_position || v.is_nan() {
+                    potential_cutoff
+                } else {
+                    v.min(potential_cutoff)
+                }
+            });
+
+        // initialize convolver
+        let grid = Grid::Cylindrical { r, z };
+        let weight_functions = dft.functional.weight_functions(t);
+        let convolver = ConvolverFFT::plan(&grid, &weight_functions, Some(1));
+        let mut profile = DFTProfile::new(grid, convolver, vle.vapor(), Some(external_potential))?;
+
+        // spherical cap with its center on the symmetry axis
+        let r0 = radius.to_reduced(U::reference_length())?;
+        let h_c = -r0 * contact_angle.cos();
+        let indices = &profile.dft.component_index;
+        let rho_l = vle
+            .liquid()
+            .partial_density
+            .to_reduced(U::reference_density())?;
+        let rho_v = vle
+            .vapor()
+            .partial_density
+            .to_reduced(U::reference_density())?;
+        let grids = profile.grid.grids();
+        let density = Array3::from_shape_fn(profile.external_potential.raw_dim(), |(i, k, j)| {
+            let h = wall_position - grids[1][j];
+            let d = (grids[0][k].powi(2) + (h - h_c).powi(2)).sqrt();
+            let x = 0.5 * (1.0 + ((r0 - d) / sigma).tanh());
+            let c = indices[i];
+            (rho_v[c] + x * (rho_l[c] - rho_v[c]))
+                * (-profile.external_potential[[i, k, j]]).exp().min(1.0)
+        });
+        profile.density = density * U::reference_density();
+
+        // specify specification
+        profile.specification = DFTSpecifications::total_moles_from_profile(&profile)?;
+
+        Ok(Self {
+            profile,
+            vle: vle.clone(),
+            contact_angle: None,
+            radius_of_curvature: None,
+            wall_position,
+        })
+    }
+}
@@ -1,18 +1,28 @@
-//! Density profiles at planar interfaces and interfacial tensions.
+//! Density profiles at planar and spherical interfaces, droplets on walls, and interfacial tensions.
 use crate::convolver::ConvolverFFT;
 use crate::functional::{HelmholtzEnergyFunctional, DFT};
 use crate::geometry::{Axis, Grid};
 use crate::profile::{DFTProfile, DFTSpecifications};
 use crate::solver::DFTSolver;
-use feos_core::{Contributions, EosError, EosResult, EosUnit, PhaseEquilibrium};
+use feos_core::{
+    Contributions, EosError, EosResult, EosUnit, EquationOfState, PhaseEquilibrium, State,
+    VLEOptions,
+};
 use ndarray::{s, Array, Array1, Array2, Axis as Axis_nd, Ix1};
 use quantity::{QuantityArray1, QuantityArray2, QuantityScalar};
+use std::rc::Rc;
 
+mod meniscus;
+mod spherical_interface;
 mod surface_tension_diagram;
+pub use meniscus::MeniscusProfile;
+pub use spherical_interface::SphericalInterface;
 pub use surface_tension_diagram::SurfaceTensionDiagram;
 
 const RELATIVE_WIDTH: f64 = 6.0;
 const MIN_WIDTH: f64 = 100.0;
+const DEFAULT_GRID_POINTS: usize = 2048;
+const RELATIVE_DELTA_TEMPERATURE: f64 = 1e-3;
 
 /// Density profile and properties of a planar interface.
 pub struct PlanarInterface<U: EosUnit, F: HelmholtzEnergyFunctional> {
@@ -171,6 +181,139 @@ impl<U: EosUnit, F: HelmholtzEnergyFunctional> PlanarInterface<U, F> {
     }
 }
 
+impl<U: EosUnit, F: HelmholtzEnergyFunctional> PlanarInterface<U, F> {
+    /// Calculate the surface tension of a pure component at all given
+    /// temperatures.
+    ///
+    /// Every interface is initialized from the (scaled) profile at the
+    /// previous temperature. The width of the domain increases with the width
+    /// of the interface when the critical point is approached. For temperatures
+    /// above the critical temperature or if the calculation fails, NaN is returned.
+    pub fn surface_tension_curve(
+        functional: &Rc<DFT<F>>,
+        temperatures: &QuantityArray1<U>,
+        n_grid: Option<usize>,
+        solver: Option<&DFTSolver>,
+    ) -> EosResult<QuantityArray1<U>> {
+        if functional.components() != 1 {
+            return Err(EosError::Error(
+                "Surface tension curves are only available for pure components.".into(),
+            ));
+        }
+        let n_grid = n_grid.unwrap_or(DEFAULT_GRID_POINTS);
+        let critical_point = State::critical_point(functional, None, None, VLEOptions::default())?;
+        let critical_temperature = critical_point.temperature;
+
+        let mut previous: Option<Self> = None;
+        let mut surface_tension = Array1::from_elem(temperatures.len(), f64::NAN);
+        for (i, gamma) in surface_tension.iter_mut().enumerate() {
+            let temperature = temperatures.get(i);
+            let reduced_temperature = temperature.to_reduced(critical_temperature)?;
+            if reduced_temperature >= 1.0 {
+                continue;
+            }
+            let interface =
+                PhaseEquilibrium::pure_t(functional, temperature, None, VLEOptions::default())
+                    .and_then(|vle| {
+                        if functional.component_index.len() == 1 {
+                            Self::from_pdgt(&vle, n_grid)
+                        } else {
+                            // the interfacial width diverges with the critical exponent 0.63
+                            let l_grid =
+                                MIN_WIDTH * (1.0 - reduced_temperature).powf(-0.63).max(1.0);
+                            Self::from_tanh(
+                                &vle,
+                                n_grid,
+                                l_grid * U::reference_length(),
+                                critical_temperature,
+                            )
+                        }
+                    })
+                    .map(|mut interface| {
+                        if let Some(init) = &previous {
+                            interface.set_density_inplace(&init.profile.density, true);
+                        }
+                        interface
+                    })
+                    .and_then(|interface| interface.solve(solver));
+            if let Ok(interface) = interface {
+                *gamma = interface
+                    .surface_tension
+                    .unwrap()
+                    .to_reduced(U::reference_surface_tension())?;
+                previous = Some(interface);
+            }
+        }
+        Ok(surface_tension * U::reference_surface_tension())
+    }
+
+    /// Calculate the temperature derivative $\frac{\mathrm{d}\gamma}{\mathrm{d}T}$
+    /// of the surface tension along the coexistence curve of a pure component.
+    ///
+    /// The derivative is calculated by central finite differences, for which
+    /// the interface is solved again at slightly shifted temperatures on the
+    /// same grid, initialized with the (scaled) current profile.
+    fn surface_tension_temperature_derivative(
+        &self,
+        solver: Option<&DFTSolver>,
+    ) -> EosResult<QuantityScalar<U>> {
+        let functional = &self.profile.dft;
+        if functional.components() != 1 {
+            return Err(EosError::Error(
+                "Surface excess properties are only available for pure components.".into(),
+            ));
+        }
+        let axis = &self.profile.grid.axes()[0];
+        let n_grid = axis.grid.len();
+        let l_grid = axis.length() * U::reference_length();
+        let temperature = self.profile.temperature;
+        let delta_t = temperature * RELATIVE_DELTA_TEMPERATURE;
+
+        let surface_tension = |temperature: QuantityScalar<U>| -> EosResult<QuantityScalar<U>> {
+            let vle =
+                PhaseEquilibrium::pure_t(functional, temperature, None, VLEOptions::default())?;
+            let mut interface = Self::new(&vle, n_grid, l_grid)?;
+            interface.set_density_inplace(&self.profile.density, true);
+            interface.profile.specification =
+                DFTSpecifications::total_moles_from_profile(&interface.profile)?;
+            Ok(interface.solve(solver)?.surface_tension.unwrap())
+        };
+        let gamma_plus = surface_tension(temperature + delta_t)?;
+        let gamma_minus = surface_tension(temperature - delta_t)?;
+        Ok((gamma_plus - gamma_minus) / (2.0 * delta_t))
+    }
+
+    /// Calculate the surface excess entropy
+    /// $s^\mathrm{s}=-\frac{\mathrm{d}\gamma}{\mathrm{d}T}$ per area of a pure
+    /// component.
+    ///
+    /// The result refers to the equimolar dividing surface. The temperature
+    /// derivative of the surface tension is obtained from interfaces that are
+    /// solved again at slightly shifted temperatures.
+    pub fn surface_excess_entropy(
+        &self,
+        solver: Option<&DFTSolver>,
+    ) -> EosResult<QuantityScalar<U>> {
+        Ok(self.surface_tension_temperature_derivative(solver)? * (-1.0))
+    }
+
+    /// Calculate the surface excess internal energy
+    /// $u^\mathrm{s}=\gamma+Ts^\mathrm{s}=\gamma-T\frac{\mathrm{d}\gamma}{\mathrm{d}T}$
+    /// per area of a pure component.
+    ///
+    /// The result refers to the equimolar dividing surface. The interface has
+    /// to be solved before.
+    pub fn surface_excess_energy(
+        &self,
+        solver: Option<&DFTSolver>,
+    ) -> EosResult<QuantityScalar<U>> {
+        let surface_tension = self
+            .surface_tension
+            .ok_or_else(|| EosError::Error("The interface has to be solved first.".into()))?;
+        Ok(surface_tension + self.profile.temperature * self.surface_excess_entropy(solver)?)
+    }
+}
+
 impl<U: EosUnit, F: HelmholtzEnergyFunctional> PlanarInterface<U, F> {
     pub fn shift_equimolar_inplace(&mut self) {
         let s = self.profile.density.shape();
@@ -321,3 +464,133 @@ fn interp(
     }
     y_new
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::functional_contribution::{FunctionalContribution, FunctionalContributionDual};
+    use crate::fundamental_measure_theory::{FMTContribution, FMTProperties, FMTVersion};
+    use crate::weight_functions::{WeightFunction, WeightFunctionInfo, WeightFunctionShape};
+    use ndarray::{arr1, ArrayView2};
+    use num_dual::DualNum;
+    use quantity::si::{SIUnit, ANGSTROM, KELVIN};
+    use std::fmt;
+
+    struct HardSpheres;
+
+    impl FMTProperties for HardSpheres {
+        fn component_index(&self) -> Array1<usize> {
+            arr1(&[0])
+        }
+
+        fn chain_length(&self) -> Array1<f64> {
+            arr1(&[1.0])
+        }
+
+        fn hs_diameter<N: DualNum<f64>>(&self, _: N) -> Array1<N> {
+            arr1(&[N::from(1.0)])
+        }
+    }
+
+    /// Mean-field attraction $\beta f=-\frac{a\rho\bar\rho}{T}$ with the
+    /// density $\bar\rho$ averaged over a sphere with the diameter $2\sigma$.
+    struct MeanFieldAttraction {
+        a: f64,
+    }
+
+    impl<N: DualNum<f64>> FunctionalContributionDual<N> for MeanFieldAttraction {
+        fn weight_functions(&self, _: N) -> WeightFunctionInfo<N> {
+            WeightFunctionInfo::new(arr1(&[0]), true).add(
+                WeightFunction::new_scaled(arr1(&[N::from(1.0)]), WeightFunctionShape::Theta),
+                false,
+            )
+        }
+
+        fn calculate_helmholtz_energy_density(
+            &self,
+            temperature: N,
+            weighted_densities: ArrayView2<N>,
+        ) -> EosResult<Array1<N>> {
+            let rho = weighted_densities.index_axis(Axis_nd(0), 0);
+            let rho_bar = weighted_densities.index_axis(Axis_nd(0), 1);
+            Ok(Array1::from_shape_fn(rho.len(), |i| {
+                -rho[i] * rho_bar[i] * self.a / temperature
+            }))
+        }
+    }
+
+    impl fmt::Display for MeanFieldAttraction {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "Mean-field attraction")
+        }
+    }
+
+    /// Hard spheres with a diameter of 1 Å and a critical temperature of
+    /// about 300 K.
+    struct SimpleFluid {
+        contributions: Vec<Box<dyn FunctionalContribution>>,
+    }
+
+    impl SimpleFluid {
+        fn new() -> DFT<Self> {
+            let contributions: Vec<Box<dyn FunctionalContribution>> = vec![
+                Box::new(FMTContribution::new(
+                    &Rc::new(HardSpheres),
+                    FMTVersion::WhiteBear,
+                )),
+                Box::new(MeanFieldAttraction { a: 1665.0 }),
+            ];
+            DFT::new_homosegmented(Self { contributions }, &arr1(&[1.0]))
+        }
+    }
+
+    impl HelmholtzEnergyFunctional for SimpleFluid {
+        fn contributions(&self) -> &[Box<dyn FunctionalContribution>] {
+            &self.contributions
+        }
+
+        fn subset(&self, _: &[usize]) -> DFT<Self> {
+            unimplemented!()
+        }
+
+        fn compute_max_density(&self, _: &Array1<f64>) -> f64 {
+            1.2
+        }
+    }
+
+    #[test]
+    fn surface_excess_energy() {
+        let functional = Rc::new(SimpleFluid::new());
+        let temperature = 240.0 * KELVIN;
+        let vle = PhaseEquilibrium::pure_t(&functional, temperature, None, VLEOptions::default())
+            .unwrap();
+        let interface = PlanarInterface::from_tanh(&vle, 1024, 40.0 * ANGSTROM, 300.0 * KELVIN)
+            .unwrap()
+            .solve(None)
+            .unwrap();
+        let reduced =
+            |x: QuantityScalar<SIUnit>| x.to_reduced(SIUnit::reference_surface_tension()).unwrap();
+        let gamma = reduced(interface.surface_tension.unwrap());
+        let ts = reduced(temperature * interface.surface_excess_entropy(None).unwrap());
+        let u = reduced(interface.surface_excess_energy(None).unwrap());
+        assert!((u - (gamma + ts)).abs() < 1e-10 * u.abs());
+
+        // the surface excess of the residual internal energy of the profile
+        // with respect to the equimolar dividing surface
+        let (liquid, vapor) = (vle.liquid(), vle.vapor());
+        let u_liquid = liquid.internal_energy(Contributions::Residual) / liquid.volume;
+        let u_vapor = vapor.internal_energy(Contributions::Residual) / vapor.volume;
+        let length = interface.profile.volume();
+        let x = (interface.profile.total_moles() - vapor.density * length)
+            / (liquid.density - vapor.density);
+        let u_profile = reduced(
+            interface
+                .profile
+                .internal_energy(Contributions::Residual)
+                .unwrap()
+                - u_liquid * x
+                - u_vapor * (length - x),
+        );
+        assert!((u - u_profile).abs() < 1e-4 * u.abs());
+    }
+}
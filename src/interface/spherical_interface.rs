@@ -0,0 +1,203 @@
+use crate::convolver::ConvolverFFT;
+use crate::functional::{HelmholtzEnergyFunctional, DFT};
+use crate::geometry::{Axis, Grid};
+use crate::profile::{DFTProfile, DFTSpecifications};
+use crate::solver::DFTSolver;
+use feos_core::{Contributions, EosError, EosResult, EosUnit, PhaseEquilibrium};
+use ndarray::{Array1, Ix1};
+use quantity::{QuantityArray1, QuantityArray2, QuantityScalar};
+use std::f64::consts::PI;
+
+/// Density profile and properties of a spherical droplet.
+///
+/// The droplet is stabilized in a finite spherical box by fixing the total
+/// number of molecules. The surface tension is evaluated at the surface of
+/// tension, which follows from the grand potential and the Laplace equation.
+pub struct SphericalInterface<U: EosUnit, F: HelmholtzEnergyFunctional> {
+    pub profile: DFTProfile<U, Ix1, F>,
+    pub vle: PhaseEquilibrium<U, DFT<F>, 2>,
+    pub surface_tension: Option<QuantityScalar<U>>,
+    pub equimolar_radius: Option<QuantityScalar<U>>,
+    pub radius_of_tension: Option<QuantityScalar<U>>,
+}
+
+impl<U: EosUnit, F: HelmholtzEnergyFunctional> Clone for SphericalInterface<U, F> {
+    fn clone(&self) -> Self {
+        Self {
+            profile: self.profile.clone(),
+            vle: self.vle.clone(),
+            surface_tension: self.surface_tension,
+            equimolar_radius: self.equimolar_radius,
+            radius_of_tension: self.radius_of_tension,
+        }
+    }
+}
+
+impl<U: EosUnit, F: HelmholtzEnergyFunctional> SphericalInterface<U, F> {
+    pub fn solve_inplace(&mut self, solver: Option<&DFTSolver>, debug: bool) -> EosResult<()> {
+        // Solve the profile
+        self.profile.solve(solver, debug)?;
+
+        // bulk phases at the chemical potential of the droplet
+        let vapor = &self.profile.bulk;
+        let mut liquid = self.vle.liquid().clone();
+        liquid.update_chemical_potential(&self.profile.chemical_potential)?;
+        let p_v = vapor.pressure(Contributions::Total);
+        let delta_p = liquid.pressure(Contributions::Total) - p_v;
+        let volume = self.profile.volume();
+
+        // excess grand potential with respect to a box filled with vapor
+        let omega = self
+            .profile
+            .integrate(&self.profile.grand_potential_density()?)
+            + p_v * volume;
+
+        // surface of tension: omega = 2/3 pi R_s^3 delta_p and gamma_s = delta_p R_s / 2
+        let radius_of_tension = (1.5 * omega / (PI * delta_p))
+            .to_reduced(U::reference_length().powi(3))?
+            .cbrt()
+            * U::reference_length();
+        self.radius_of_tension = Some(radius_of_tension);
+        self.surface_tension = Some(0.5 * delta_p * radius_of_tension);
+
+        // equimolar dividing surface
+        let excess_moles = self.profile.total_moles() - vapor.density * volume;
+        let delta_rho = liquid.density - vapor.density;
+        self.equimolar_radius = Some(
+            (0.75 * excess_moles / (PI * delta_rho))
+                .to_reduced(U::reference_length().powi(3))?
+                .cbrt()
+                * U::reference_length(),
+        );
+
+        Ok(())
+    }
+
+    pub fn solve(mut self, solver: Option<&DFTSolver>) -> EosResult<Self> {
+        self.solve_inplace(solver, false)?;
+        Ok(self)
+    }
+
+    /// Return the distance between the equimolar dividing surface and the
+    /// surface of tension, which is an estimate for the Tolman length.
+    pub fn tolman_length(&self) -> Option<QuantityScalar<U>> {
+        Some(self.equimolar_radius? - self.radius_of_tension?)
+    }
+}
+
+impl<U: EosUnit, F: HelmholtzEnergyFunctional> SphericalInterface<U, F> {
+    pub fn new(
+        vle: &PhaseEquilibrium<U, DFT<F>, 2>,
+        n_grid: usize,
+        l_grid: QuantityScalar<U>,
+    ) -> EosResult<Self> {
+        let dft = &vle.vapor().eos;
+
+        // generate grid
+        let grid = Grid::Spherical(Axis::new_spherical(n_grid, l_grid)?);
+
+        // initialize convolver
+        let t = vle
+            .vapor()
+            .temperature
+            .to_reduced(U::reference_temperature())?;
+        let weight_functions = dft.functional.weight_functions(t);
+        let convolver = ConvolverFFT::plan(&grid, &weight_functions, None);
+
+        Ok(Self {
+            profile: DFTProfile::new(grid, convolver, vle.vapor(), None)?,
+            vle: vle.clone(),
+            surface_tension: None,
+            equimolar_radius: None,
+            radius_of_tension: None,
+        })
+    }
+
+    /// Initialize a droplet with the given radius from a tanh profile.
+    pub fn from_tanh(
+        vle: &PhaseEquilibrium<U, DFT<F>, 2>,
+        n_grid: usize,
+        l_grid: QuantityScalar<U>,
+        radius: QuantityScalar<U>,
+        critical_temperature: QuantityScalar<U>,
+    ) -> EosResult<Self> {
+        let mut profile = Self::new(vle, n_grid, l_grid)?;
+
+        // calculate segment indices
+        let indices = &profile.profile.dft.component_index;
+
+        // calculate density profile
+        let r0 = radius.to_reduced(U::reference_length())?;
+        let reduced_temperature = vle.vapor().temperature.to_reduced(critical_temperature)?;
+        profile.profile.density =
+            QuantityArray2::from_shape_fn(profile.profile.density.raw_dim(), |(i, r)| {
+                let rho_v = profile.vle.vapor().partial_density.get(indices[i]);
+                let rho_l = profile.vle.liquid().partial_density.get(indices[i]);
+                0.5 * (rho_l - rho_v)
+                    * ((r0 - profile.profile.grid.grids()[0][r]) / 3.0
+                        * (2.4728 - 2.3625 * reduced_temperature))
+                        .tanh()
+                    + 0.5 * (rho_l + rho_v)
+            });
+
+        // specify specification
+        profile.profile.specification =
+            DFTSpecifications::total_moles_from_profile(&profile.profile)?;
+
+        Ok(profile)
+    }
+
+    /// Solve droplets for all given initial radii. Droplets for which the
+    /// solver does not converge are omitted.
+    pub fn from_radii(
+        vle: &PhaseEquilibrium<U, DFT<F>, 2>,
+        radii: &QuantityArray1<U>,
+        n_grid: usize,
+        l_grid: QuantityScalar<U>,
+        critical_temperature: QuantityScalar<U>,
+        solver: Option<&DFTSolver>,
+    ) -> Vec<Self> {
+        (0..radii.len())
+            .filter_map(|i| {
+                Self::from_tanh(vle, n_grid, l_grid, radii.get(i), critical_temperature)
+                    .and_then(|profile| profile.solve(solver))
+                    .ok()
+            })
+            .collect()
+    }
+
+    /// Fit $\gamma(R)=\gamma_\infty\left(1-\frac{2\delta}{R}\right)$ to the surface
+    /// tensions of droplets with different equimolar radii $R$.
+    ///
+    /// Returns the planar surface tension $\gamma_\infty$ and the Tolman length $\delta$.
+    pub fn fit_tolman_length(
+        interfaces: &[Self],
+    ) -> EosResult<(QuantityScalar<U>, QuantityScalar<U>)> {
+        let mut x = Vec::with_capacity(interfaces.len());
+        let mut y = Vec::with_capacity(interfaces.len());
+        for interface in interfaces {
+            if let (Some(gamma), Some(radius)) =
+                (interface.surface_tension, interface.equimolar_radius)
+            {
+                x.push(radius.to_reduced(U::reference_length())?.recip());
+                y.push(gamma.to_reduced(U::reference_surface_tension())?);
+            }
+        }
+        if x.len() < 2 {
+            return Err(EosError::Error(
+                "At least two solved droplets are required to fit the Tolman length.".into(),
+            ));
+        }
+
+        // linear least squares fit of gamma over 1/R
+        let (x, y) = (Array1::from_vec(x), Array1::from_vec(y));
+        let dx = &x - x.mean().unwrap();
+        let dy = &y - y.mean().unwrap();
+        let slope = (&dx * &dy).sum() / (&dx * &dx).sum();
+        let gamma_inf = y.mean().unwrap() - slope * x.mean().unwrap();
+        Ok((
+            gamma_inf * U::reference_surface_tension(),
+            -0.5 * slope / gamma_inf * U::reference_length(),
+        ))
+    }
+}
@@ -5,6 +5,7 @@
 
 pub mod adsorption;
 mod convolver;
+mod electrostatics;
 mod functional;
 mod functional_contribution;
 pub mod fundamental_measure_theory;
@@ -18,11 +19,12 @@ mod solver;
 mod weight_functions;
 
 pub use convolver::{Convolver, ConvolverFFT};
+pub use electrostatics::Electrostatics;
 pub use functional::{HelmholtzEnergyFunctional, DFT};
 pub use functional_contribution::{FunctionalContribution, FunctionalContributionDual};
-pub use geometry::{Axis, AxisGeometry, Grid};
+pub use geometry::{Axis, AxisGeometry, Grid, IntegrationRule};
 pub use profile::{DFTProfile, DFTSpecification, DFTSpecifications};
-pub use solver::DFTSolver;
+pub use solver::{DFTSolver, ProgressObserver, SolverStats, StdoutObserver};
 pub use weight_functions::{WeightFunction, WeightFunctionInfo, WeightFunctionShape};
 
 #[cfg(feature = "python")]
@@ -1,21 +1,37 @@
-use crate::convolver::{Convolver, ConvolverFFT};
+use crate::convolver::{Convolver, ConvolverFFT, TimedConvolver};
+use crate::electrostatics::Electrostatics;
 use crate::functional::{HelmholtzEnergyFunctional, DFT};
-use crate::geometry::Grid;
-use crate::solver::DFTSolver;
+use crate::geometry::{AxisGeometry, Grid};
+use crate::solver::{read_checkpoint, DFTSolver, SolverStats};
 use crate::weight_functions::WeightFunctionInfo;
 use feos_core::{Contributions, EosError, EosResult, EosUnit, EquationOfState, State};
 use log::{info, warn};
+use ndarray::parallel::prelude::*;
 use ndarray::{
-    s, Array, Array1, ArrayBase, ArrayViewMut, ArrayViewMut1, Axis as Axis_nd, Data, Dimension,
-    Ix1, Ix2, Ix3, RemoveAxis,
+    indices, s, Array, Array1, Array2, ArrayBase, ArrayViewMut, ArrayViewMut1, Axis as Axis_nd,
+    Data, Dimension, IntoDimension, Ix1, Ix2, Ix3, RemoveAxis, Zip,
 };
+use ndarray_linalg::{EigValsh, UPLO};
 use num_dual::Dual64;
 use quantity::{Quantity, QuantityArray, QuantityArray1, QuantityScalar};
 use std::ops::MulAssign;
+use std::path::Path;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "hdf5")]
+use hdf5::types::VarLenUnicode;
+#[cfg(feature = "hdf5")]
+use quantity::si::{SIUnit, JOULE, KELVIN, METER, MOL};
 
 pub(crate) const MAX_POTENTIAL: f64 = 50.0;
 pub(crate) const CUTOFF_RADIUS: f64 = 14.0;
+/// Upper bound (in K) of solid-fluid pair potentials, which replaces the
+/// singularity at vanishing distances.
+pub(crate) const MAX_PAIR_POTENTIAL: f64 = 1e10;
+const MAX_ITER_LANCZOS: usize = 100;
+const TOL_LANCZOS: f64 = 1e-10;
+const FD_STEP_HESSIAN: f64 = 1e-6;
 
 /// General specifications for the chemical potential in a DFT calculation.
 ///
@@ -131,6 +147,9 @@ pub struct DFTProfile<U, D: Dimension, F> {
     pub chemical_potential: QuantityArray1<U>,
     pub specification: Rc<dyn DFTSpecification<U, D, F>>,
     pub external_potential: Array<f64, D::Larger>,
+    pub electrostatics: Option<Electrostatics>,
+    /// Grid points at which the density is held constant by the solver.
+    pub frozen_mask: Option<Array<bool, D>>,
     pub bulk: State<U, DFT<F>>,
 }
 
@@ -183,6 +202,35 @@ impl<U: EosUnit, F> DFTProfile<U, Ix3, F> {
     }
 }
 
+/// Reference quantities that are used to calculate the dimensionless
+/// quantities of the profile, e.g., in external potentials.
+impl<U: EosUnit, D: Dimension, F> DFTProfile<U, D, F> {
+    /// Return the temperature in units of the reference temperature.
+    pub fn reduced_temperature(&self) -> EosResult<f64> {
+        Ok(self.temperature.to_reduced(U::reference_temperature())?)
+    }
+
+    /// Return the density profile in units of the reference density.
+    pub fn reduced_density(&self) -> EosResult<Array<f64, D::Larger>> {
+        Ok(self.density.to_reduced(U::reference_density())?)
+    }
+
+    /// Return the length that corresponds to one reduced unit of the grid.
+    pub fn reference_length(&self) -> QuantityScalar<U> {
+        U::reference_length()
+    }
+
+    /// Return the temperature that corresponds to one reduced unit.
+    pub fn reference_temperature(&self) -> QuantityScalar<U> {
+        U::reference_temperature()
+    }
+
+    /// Return the density that corresponds to one reduced unit.
+    pub fn reference_density(&self) -> QuantityScalar<U> {
+        U::reference_density()
+    }
+}
+
 impl<U: EosUnit, D: Dimension, F: HelmholtzEnergyFunctional> DFTProfile<U, D, F>
 where
     <D as Dimension>::Larger: Dimension<Smaller = D>,
@@ -210,8 +258,34 @@ where
             Array::zeros(n_grid).into_dimensionality().unwrap()
         });
 
-        // intitialize density
+        // check that the weight functions do not wrap around onto themselves,
+        // which happens on periodic axes and, after the reflection at both
+        // ends, on reflective cartesian axes of twice the length
         let t = bulk.temperature.to_reduced(U::reference_temperature())?;
+        let range = 2.0 * dft.functional.max_weight_function_radius(t);
+        let periodic = matches!(
+            grid,
+            Grid::Periodical1(_)
+                | Grid::Periodical2(_, _)
+                | Grid::Periodical3(_, _, _)
+                | Grid::Triclinic { .. }
+        );
+        for ax in grid.axes() {
+            let period = match ax.geometry {
+                AxisGeometry::Cartesian if periodic => ax.length(),
+                AxisGeometry::Cartesian => 2.0 * ax.length(),
+                AxisGeometry::Polar | AxisGeometry::Spherical => continue,
+            };
+            if range > period {
+                return Err(EosError::Error(format!(
+                    "The range of the weight functions ({}) exceeds the period of the domain ({}). Increase the size of the domain.",
+                    range * U::reference_length(),
+                    period * U::reference_length()
+                )));
+            }
+        }
+
+        // intitialize density
         let isaft = dft
             .isaft_integrals(t, &external_potential, &convolver)
             .mapv(f64::abs)
@@ -233,6 +307,8 @@ where
             chemical_potential: bulk.chemical_potential(Contributions::Total),
             specification: Rc::new(DFTSpecifications::ChemicalPotential),
             external_potential,
+            electrostatics: None,
+            frozen_mask: None,
             bulk: bulk.clone(),
         })
     }
@@ -241,11 +317,19 @@ where
         let integration_weights = self.grid.integration_weights();
 
         for (i, w) in integration_weights.into_iter().enumerate() {
-            for mut l in profile.lanes_mut(Axis_nd(i)) {
-                l.mul_assign(w);
-            }
+            Zip::from(profile.lanes_mut(Axis_nd(i))).par_for_each(|mut l| l.mul_assign(w));
         }
-        profile.sum()
+        profile.par_iter().sum()
+    }
+
+    /// Unit of integrals over the grid, which depends on the geometry.
+    fn integration_unit(&self) -> QuantityScalar<U> {
+        self.grid
+            .axes()
+            .iter()
+            .fold(U::reference_length().powi(0), |acc, ax| {
+                acc * U::reference_length().powi(ax.geometry.dimension())
+            })
     }
 
     fn integrate_reduced_comp(&self, profile: &Array<f64, D::Larger>) -> Array1<f64> {
@@ -265,6 +349,42 @@ where
                 Some(acc.map_or(ax.volume(), |acc| acc * ax.volume()))
             })
             .unwrap()
+            * self.grid.volume_factor()
+    }
+
+    /// Iterate over all grid points together with their position and the
+    /// (reduced) external potential of every segment at that point.
+    ///
+    /// The position contains the coordinates of all axes of the grid, e.g.,
+    /// $(r,z)$ for cylindrical grids. For triclinic grids, the Cartesian
+    /// position is returned.
+    pub fn grid_points(
+        &self,
+    ) -> impl Iterator<Item = (D::Pattern, QuantityArray1<U>, Array1<f64>)> + '_ {
+        let axes = self.grid.axes();
+        let cell = match &self.grid {
+            Grid::Triclinic { cell, .. } => Some(cell),
+            _ => None,
+        };
+        let dim = self.external_potential.raw_dim().remove_axis(Axis_nd(0));
+        indices(dim)
+            .into_iter()
+            .zip(self.external_potential.lanes(Axis_nd(0)))
+            .map(move |(index, potential)| {
+                let i = index.clone().into_dimension();
+                let coordinates = Array1::from_shape_fn(axes.len(), |k| axes[k].grid[i[k]]);
+                let position = match cell {
+                    Some(cell) => (0..3).fold(Array1::zeros(3), |acc, k| {
+                        acc + &cell.row(k) * (coordinates[k] / axes[k].length())
+                    }),
+                    None => coordinates,
+                };
+                (
+                    index,
+                    position * U::reference_length(),
+                    potential.to_owned(),
+                )
+            })
     }
 
     /// Integrate a given profile over the iteration domain.
@@ -285,8 +405,8 @@ where
         })
     }
 
-    /// Return the number of moles of each component in the system.
-    pub fn moles(&self) -> QuantityArray1<U> {
+    /// Reduced densities of the components (instead of the segments).
+    pub(crate) fn component_densities_reduced(&self) -> Array<f64, D::Larger> {
         let rho = self.density.to_reduced(U::reference_density()).unwrap();
         let mut d = rho.raw_dim();
         d[0] = self.dft.components();
@@ -296,13 +416,30 @@ where
                 .index_axis_mut(Axis_nd(0), j)
                 .assign(&rho.index_axis(Axis_nd(0), i));
         }
-        self.integrate_comp(&(density_comps * U::reference_density()))
+        density_comps
+    }
+
+    /// Return the number of moles of each component in the system.
+    pub fn moles(&self) -> QuantityArray1<U> {
+        self.integrate_comp(&(self.component_densities_reduced() * U::reference_density()))
     }
 
     /// Return the total number of moles in the system.
     pub fn total_moles(&self) -> QuantityScalar<U> {
         self.moles().sum()
     }
+
+    /// Return the total number of moles in the part of the system where
+    /// `mask` is true, e.g., in the first adsorbed layer.
+    pub fn integrate_region(&self, mask: &Array<bool, D>) -> QuantityScalar<U> {
+        let mut rho = self.component_densities_reduced().sum_axis(Axis_nd(0));
+        rho.zip_mut_with(mask, |r, &m| {
+            if !m {
+                *r = 0.0
+            }
+        });
+        self.integrate(&(rho * U::reference_density()))
+    }
 }
 
 impl<U: Clone, D: Dimension, F> Clone for DFTProfile<U, D, F> {
@@ -316,6 +453,8 @@ impl<U: Clone, D: Dimension, F> Clone for DFTProfile<U, D, F> {
             chemical_potential: self.chemical_potential.clone(),
             specification: self.specification.clone(),
             external_potential: self.external_potential.clone(),
+            electrostatics: self.electrostatics.clone(),
+            frozen_mask: self.frozen_mask.clone(),
             bulk: self.bulk.clone(),
         }
     }
@@ -334,6 +473,76 @@ where
             .weighted_densities(&self.density.to_reduced(U::reference_density())?))
     }
 
+    /// Return the weighted densities of every functional contribution
+    /// together with the name of the contribution.
+    pub fn named_weighted_densities(&self) -> EosResult<Vec<(String, Array<f64, D::Larger>)>> {
+        Ok(self
+            .dft
+            .functional
+            .contributions()
+            .iter()
+            .map(|c| c.to_string())
+            .zip(self.weighted_densities()?)
+            .collect())
+    }
+
+    /// Return the Helmholtz energy density of every contribution of the
+    /// functional together with the name of the contribution.
+    ///
+    /// The first entry is the ideal chain contribution (which depends on
+    /// `contributions`), followed by the residual contributions of the
+    /// functional, e.g., hard spheres, dispersion, and polar terms.
+    pub fn helmholtz_energy_density_contributions(
+        &self,
+        contributions: Contributions,
+    ) -> EosResult<Vec<(String, QuantityArray<U, D>)>> {
+        let t = self.temperature.to_reduced(U::reference_temperature())?;
+        Ok(self
+            .dft
+            .helmholtz_energy_density_contributions(
+                t,
+                &self.density.to_reduced(U::reference_density())?,
+                &self.convolver,
+                contributions,
+            )?
+            .into_iter()
+            .map(|(name, f)| (name, f * (t * U::reference_pressure())))
+            .collect())
+    }
+
+    /// Return the fraction of non-bonded association sites at every grid point,
+    /// or `None` if no contribution of the functional models association.
+    ///
+    /// The fraction is evaluated from the weighted densities of the first
+    /// contribution that provides it (see
+    /// [FunctionalContribution::association_fraction](crate::FunctionalContribution::association_fraction)).
+    pub fn association_fraction(&self) -> EosResult<Option<Array<f64, D::Larger>>> {
+        let t = self.temperature.to_reduced(U::reference_temperature())?;
+        for (c, wd) in self
+            .dft
+            .functional
+            .contributions()
+            .iter()
+            .zip(self.weighted_densities()?)
+        {
+            let mut shape = wd.raw_dim();
+            let n_points = wd.len() / shape[0];
+            let wd = wd.into_shape((shape[0], n_points)).unwrap();
+            if let Some(x) = c.association_fraction(t, wd.view())? {
+                shape[0] = x.shape()[0];
+                return Ok(Some(x.into_shape(shape).unwrap()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return the functional derivative of the residual Helmholtz energy
+    /// functional for every segment.
+    ///
+    /// The result is reduced with the thermal energy, i.e., it is
+    /// $\beta\frac{\delta F^\mathrm{res}}{\delta\rho_\alpha(\mathbf{r})}$, which is
+    /// the negative of the one-body direct correlation function
+    /// $c_\alpha^{(1)}(\mathbf{r})$.
     pub fn functional_derivative(&self) -> EosResult<Array<f64, D::Larger>> {
         let (_, dfdrho) = self.dft.functional_derivative(
             self.temperature.to_reduced(U::reference_temperature())?,
@@ -343,6 +552,167 @@ where
         Ok(dfdrho)
     }
 
+    /// Return the grand potential of the profile.
+    ///
+    /// Equivalent to integrating [DFTProfile::grand_potential_density], but the
+    /// evaluation and the integration are parallelized over the grid points.
+    pub fn grand_potential(&self) -> EosResult<QuantityScalar<U>> {
+        let t = self.temperature.to_reduced(U::reference_temperature())?;
+        let omega = self.dft.grand_potential_density_reduced(
+            t,
+            &self.density.to_reduced(U::reference_density())?,
+            &self.convolver,
+        )?;
+        Ok(self.integrate_reduced(omega) * t * U::reference_pressure() * self.integration_unit())
+    }
+
+    /// Return the excess grand potential $\Omega+pV$ of the profile with
+    /// respect to the bulk phase, where $V$ is the volume of the whole grid.
+    ///
+    /// The bulk pressure is added to the grand potential density before the
+    /// integration, so that the integrand vanishes in bulk-like regions and
+    /// no accuracy is lost in the difference of two large numbers. In contrast
+    /// to [PoreProfile::excess_grand_potential](crate::adsorption::PoreProfile::excess_grand_potential),
+    /// $V$ includes regions of the grid outside of the pore, e.g., the potential
+    /// offset of slit pores.
+    pub fn grid_excess_grand_potential(&self) -> EosResult<QuantityScalar<U>> {
+        let t = self.temperature.to_reduced(U::reference_temperature())?;
+        let p = self
+            .bulk
+            .pressure(Contributions::Total)
+            .to_reduced(U::reference_pressure())?;
+        let omega = self.dft.grand_potential_density_reduced(
+            t,
+            &self.density.to_reduced(U::reference_density())?,
+            &self.convolver,
+        )? + p / t;
+        Ok(self.integrate_reduced(omega) * t * U::reference_pressure() * self.integration_unit())
+    }
+
+    /// Return the volume of the whole grid, which in contrast to
+    /// [DFTProfile::volume] includes potential offsets.
+    pub(crate) fn grid_volume(&self) -> QuantityScalar<U> {
+        let ones = Array::ones(self.external_potential.raw_dim().remove_axis(Axis_nd(0)));
+        self.integrate_reduced(ones) * self.integration_unit()
+    }
+
+    /// Return the grand potential density of the profile.
+    ///
+    /// The integral of the grand potential density over the domain is the
+    /// grand potential of the system.
+    pub fn grand_potential_density(&self) -> EosResult<QuantityArray<U, D>> {
+        self.dft
+            .grand_potential_density(self.temperature, &self.density, &self.convolver)
+    }
+
+    /// Return the local pressure, i.e., the negative grand potential density.
+    pub fn local_pressure(&self) -> EosResult<QuantityArray<U, D>> {
+        Ok(self.grand_potential_density()? * (-1.0))
+    }
+
+    /// Return the smallest eigenvalue of the Hessian of the grand potential.
+    ///
+    /// The eigenvalue is estimated with a matrix-free Lanczos iteration, in which
+    /// the second functional derivative is evaluated by central differences of the
+    /// functional derivative. The Hessian is symmetrized with the integration weights
+    /// and scaled with the density, so that the eigenvalue is 1 for an ideal gas of
+    /// spheres. Grid points at which the external potential is overwhelming are
+    /// excluded.
+    pub fn smallest_hessian_eigenvalue(&self) -> EosResult<f64> {
+        let temperature = self.temperature.to_reduced(U::reference_temperature())?;
+        let density = self.density.to_reduced(U::reference_density())?;
+
+        // integration weights on the full grid
+        let mut weights: Array<f64, D> = Array::ones(density.raw_dim().remove_axis(Axis_nd(0)));
+        for (i, w) in self.grid.integration_weights().into_iter().enumerate() {
+            for mut l in weights.lanes_mut(Axis_nd(i)) {
+                l.mul_assign(w);
+            }
+        }
+
+        // scaling of the Hessian
+        let mut sqrt_rho_w = density.clone();
+        let mut sqrt_rho_per_w = density.clone();
+        for (mut a, mut b) in sqrt_rho_w
+            .outer_iter_mut()
+            .zip(sqrt_rho_per_w.outer_iter_mut())
+        {
+            a.zip_mut_with(&weights, |r, &w| *r = (*r * w).sqrt());
+            b.zip_mut_with(&weights, |r, &w| *r = (*r / w).sqrt());
+        }
+        let mask = self
+            .external_potential
+            .mapv(|p| if is_accessible(p) { 1.0 } else { 0.0 });
+        let rho_max = density.iter().fold(0.0, |acc: f64, &r| acc.max(r));
+
+        // product of the scaled Hessian with a vector
+        let hessian = |v: &Array<f64, D::Larger>| -> EosResult<Array<f64, D::Larger>> {
+            let u = v * &sqrt_rho_per_w * &mask;
+            let u_max = u.iter().fold(0.0, |acc: f64, &x| acc.max(x.abs()));
+            let h = FD_STEP_HESSIAN * rho_max / u_max;
+            let (_, df_plus) = self.dft.functional_derivative(
+                temperature,
+                &(&density + &(&u * h)),
+                &self.convolver,
+            )?;
+            let (_, df_minus) = self.dft.functional_derivative(
+                temperature,
+                &(&density - &(&u * h)),
+                &self.convolver,
+            )?;
+            let mut hv = (df_plus - df_minus) / (2.0 * h) * &sqrt_rho_w;
+            for ((mut hv_i, v_i), &m) in hv
+                .outer_iter_mut()
+                .zip(v.outer_iter())
+                .zip(self.dft.m.iter())
+            {
+                hv_i += &(&v_i * m);
+            }
+            Ok(hv * &mask)
+        };
+
+        // Lanczos iteration starting from a pseudo-random vector
+        let mut k = 0.0;
+        let mut v = density.mapv(|_| {
+            k += 1.0;
+            ((k * 12.9898_f64).sin() * 43758.5453).fract()
+        }) * &mask;
+        v /= (&v * &v).sum().sqrt();
+        let mut v_old = Array::zeros(v.raw_dim());
+        let mut alpha = Vec::with_capacity(MAX_ITER_LANCZOS);
+        let mut beta = Vec::with_capacity(MAX_ITER_LANCZOS);
+        let mut b = 0.0;
+        for _ in 0..MAX_ITER_LANCZOS {
+            let mut w = hessian(&v)?;
+            let a = (&w * &v).sum();
+            w = w - &(&v * a) - &(&v_old * b);
+            alpha.push(a);
+            b = (&w * &w).sum().sqrt();
+            if b < TOL_LANCZOS {
+                break;
+            }
+            beta.push(b);
+            v_old = v;
+            v = w / b;
+        }
+
+        // smallest eigenvalue of the tridiagonal matrix
+        let n = alpha.len();
+        let tridiagonal = Array2::from_shape_fn((n, n), |(i, j)| {
+            if i == j {
+                alpha[i]
+            } else if i == j + 1 {
+                beta[j]
+            } else if j == i + 1 {
+                beta[i]
+            } else {
+                0.0
+            }
+        });
+        let eigenvalues = tridiagonal.eigvalsh(UPLO::Lower)?;
+        Ok(eigenvalues.iter().fold(f64::INFINITY, |acc, &e| acc.min(e)))
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn residual(&self, log: bool) -> EosResult<(Array<f64, D::Larger>, Array1<f64>)> {
         // Read from profile
@@ -396,14 +766,50 @@ where
 
         // calculate total functional derivative
         dfdrho += &self.external_potential;
+        if let Some(electrostatics) = &self.electrostatics {
+            dfdrho += &electrostatics.potential(
+                &self.grid,
+                &self.dft.component_index,
+                density,
+                temperature,
+            )?;
+        }
 
         // calculate isaft integrals
-        let isaft = self
+        let mut isaft = self
             .dft
             .isaft_integrals(temperature, &dfdrho, &self.convolver);
 
-        // Euler-Lagrange equation
+        // shift the electrostatic potential to keep the updated profile neutral
         let m = &self.dft.m;
+        if let Some(electrostatics) = &self.electrostatics {
+            if electrostatics.enforces_charge_neutrality() {
+                let mut rho = Array::zeros(density.raw_dim());
+                for ((((mut rho, df), &mu), &m), is) in rho
+                    .outer_iter_mut()
+                    .zip(dfdrho.outer_iter())
+                    .zip(chemical_potential.iter())
+                    .zip(m.iter())
+                    .zip(isaft.outer_iter())
+                {
+                    rho.assign(&(((mu - &df) / m).mapv(f64::exp) * is));
+                }
+                let shift = electrostatics.neutralizing_potential(
+                    &self.grid,
+                    &self.dft.component_index,
+                    m,
+                    &rho,
+                )?;
+                for (mut df, &shift) in dfdrho.outer_iter_mut().zip(shift.iter()) {
+                    df += shift;
+                }
+                isaft = self
+                    .dft
+                    .isaft_integrals(temperature, &dfdrho, &self.convolver);
+            }
+        }
+
+        // Euler-Lagrange equation
         res_rho
             .outer_iter_mut()
             .zip(dfdrho.outer_iter())
@@ -426,11 +832,22 @@ where
             .iter_mut()
             .zip(self.external_potential.iter())
             .for_each(|(r, &p)| {
-                if p + f64::EPSILON >= MAX_POTENTIAL {
+                if !is_accessible(p) {
                     *r = 0.0;
                 }
             });
 
+        // set residual to 0 at frozen grid points
+        if let Some(mask) = &self.frozen_mask {
+            for mut res in res_rho.outer_iter_mut() {
+                Zip::from(&mut res).and(mask).for_each(|r, &f| {
+                    if f {
+                        *r = 0.0
+                    }
+                });
+            }
+        }
+
         // Additional residuals for the calculation of the chemical potential
         let z: Array1<_> = dfdrho
             .outer_iter()
@@ -453,7 +870,27 @@ where
         Ok(())
     }
 
+    /// Solve the density profile.
+    ///
+    /// If the solver does not converge, the profile still contains the last
+    /// iterate, so that the calculation can be continued, e.g., with a
+    /// different solver. An error is returned unless `debug` is set.
     pub fn solve(&mut self, solver: Option<&DFTSolver>, debug: bool) -> EosResult<()> {
+        self.solve_internal(solver, debug, None)
+    }
+
+    /// Solve the density profile and record the number of iterations and
+    /// the time spent in the residual evaluations in `stats`.
+    fn solve_internal(
+        &mut self,
+        solver: Option<&DFTSolver>,
+        debug: bool,
+        mut stats: Option<&mut SolverStats>,
+    ) -> EosResult<()> {
+        let timed = stats.is_some();
+        let mut residual_evaluations = 0;
+        let mut residual_time = Duration::ZERO;
+
         // unwrap solver
         let solver = solver.cloned().unwrap_or_default();
 
@@ -469,7 +906,8 @@ where
 
         // initialize x-vector
         let n_rho = density.len();
-        let mut x = Array1::zeros(n_rho + density.shape()[0]);
+        let n_x = n_rho + density.shape()[0];
+        let mut x = Array1::zeros(n_x);
         x.slice_mut(s![..n_rho])
             .assign(&density.view().into_shape(n_rho).unwrap());
         x.slice_mut(s![n_rho..])
@@ -478,6 +916,8 @@ where
         // Residual function
         let mut residual =
             |x: &Array1<f64>, mut res: ArrayViewMut1<f64>, log: bool| -> EosResult<()> {
+                let start = if timed { Some(Instant::now()) } else { None };
+
                 // Read density and chemical potential from solution vector
                 density.assign(&x.slice(s![..n_rho]).into_shape(density.shape()).unwrap());
                 chemical_potential.assign(&x.slice(s![n_rho..]).mapv(f64::ln));
@@ -487,7 +927,7 @@ where
                 let res_rho = res_rho.into_shape(density.raw_dim()).unwrap();
 
                 // Calculate residual
-                self.calculate_residual(
+                let result = self.calculate_residual(
                     temperature,
                     &density,
                     &chemical_potential,
@@ -495,29 +935,138 @@ where
                     res_rho,
                     res_mu,
                     log,
-                )
+                );
+                if let Some(start) = start {
+                    residual_evaluations += 1;
+                    residual_time += start.elapsed();
+                }
+                result
             };
 
+        // Residuals of the individual components (relative to the bulk densities)
+        let n_grid = n_rho / self.dft.component_index.len();
+        let components = self.dft.components();
+        let bulk_density = self
+            .bulk
+            .partial_density
+            .to_reduced(U::reference_density())?;
+        let component_index = self.dft.component_index.clone();
+        let component_residuals = |res: &Array1<f64>, log: bool| {
+            let mut norm = Array1::<f64>::zeros(components);
+            let mut points = Array1::<f64>::zeros(components);
+            for (s, &c) in component_index.iter().enumerate() {
+                norm[c] += res
+                    .slice(s![s * n_grid..(s + 1) * n_grid])
+                    .mapv(|r| r * r)
+                    .sum();
+                points[c] += n_grid as f64;
+            }
+            let rms = (norm / points).mapv(f64::sqrt);
+            if log {
+                rms
+            } else {
+                rms / &bulk_density
+            }
+        };
+
+        // Diagonal preconditioner 1/(1+rho*c2) estimated from the bulk
+        let preconditioner = || -> EosResult<Array1<f64>> {
+            let dmu_dn = self
+                .bulk
+                .dmu_dni(Contributions::Total)
+                .to_reduced(U::reference_molar_energy() / U::reference_moles())?;
+            let volume = self.bulk.volume.to_reduced(U::reference_volume())?;
+            let mut preconditioner = Array1::ones(n_x);
+            for (s, &c) in component_index.iter().enumerate() {
+                let diagonal = dmu_dn[[c, c]] * volume * bulk_density[c] / temperature;
+                if diagonal > 0.0 {
+                    preconditioner
+                        .slice_mut(s![s * n_grid..(s + 1) * n_grid])
+                        .fill(diagonal.recip());
+                }
+            }
+            Ok(preconditioner)
+        };
+
         // Call solver(s)
-        let (converged, iterations) = solver.solve(&mut x, &mut residual)?;
+        let (converged, iterations, res) = solver.solve(
+            &mut x,
+            &mut residual,
+            components,
+            component_residuals,
+            preconditioner,
+        )?;
+        if let Some(stats) = stats.as_mut() {
+            stats.iterations = iterations;
+            stats.residual_evaluations = residual_evaluations;
+            stats.functional_time = residual_time;
+        }
+
+        // Update profile (with the last iterate, if the solver did not converge)
+        self.density = density * U::reference_density();
+        for (s, &c) in self.dft.component_index.iter().enumerate() {
+            mu_comp[c] = chemical_potential[s];
+        }
+        self.chemical_potential = mu_comp * temperature * U::reference_molar_energy();
+        self.bulk = bulk;
+
         if converged {
             info!("DFT solved in {} iterations", iterations);
         } else if debug {
-            warn!("DFT not converged in {} iterations", iterations);
+            warn!(
+                "DFT not converged in {} iterations (residual: {:e})",
+                iterations, res
+            );
         } else {
-            return Err(EosError::NotConverged(String::from("DFT")));
+            return Err(EosError::NotConverged(format!(
+                "DFT after {} iterations (residual: {:e})",
+                iterations, res
+            )));
         }
 
-        // Update profile
+        Ok(())
+    }
+
+    /// Restore the density profile and the chemical potential from a checkpoint
+    /// written by a [DFTSolver] with [DFTSolver::checkpoint].
+    ///
+    /// The profile has to be initialized with the same grid and functional as
+    /// the profile from which the checkpoint was written.
+    pub fn load_checkpoint<P: AsRef<Path>>(&mut self, path: P) -> EosResult<()> {
+        let x = read_checkpoint(path.as_ref())?;
+        let n_rho = self.density.len();
+        let segments = self.dft.component_index.len();
+        if x.len() != n_rho + segments {
+            return Err(EosError::Error(format!(
+                "The checkpoint contains {} values, but the profile requires {}.",
+                x.len(),
+                n_rho + segments
+            )));
+        }
+        let density = x
+            .slice(s![..n_rho])
+            .to_owned()
+            .into_shape(self.density.raw_dim())
+            .unwrap();
         self.density = density * U::reference_density();
+
+        let temperature = self.temperature.to_reduced(U::reference_temperature())?;
+        let mut mu_comp = Array1::zeros(self.dft.components());
         for (s, &c) in self.dft.component_index.iter().enumerate() {
-            mu_comp[c] = chemical_potential[s];
+            mu_comp[c] = x[n_rho + s].ln();
         }
         self.chemical_potential = mu_comp * temperature * U::reference_molar_energy();
-        self.bulk = bulk;
-
         Ok(())
     }
+
+    /// Solve the density profile and return the partially converged profile
+    /// together with the error if the solver does not converge.
+    pub fn try_solve(mut self, solver: Option<&DFTSolver>) -> Result<Self, (Self, EosError)> {
+        match self.solve(solver, false) {
+            Ok(()) => Ok(self),
+            Err(e) => Err((self, e)),
+        }
+    }
 }
 
 impl<U: EosUnit, D: Dimension + RemoveAxis + 'static, F: HelmholtzEnergyFunctional>
@@ -527,6 +1076,33 @@ where
     D::Smaller: Dimension<Larger = D>,
     <D::Larger as Dimension>::Larger: Dimension<Smaller = D::Larger>,
 {
+    /// Solve the density profile like [DFTProfile::solve] and return the
+    /// number of iterations and convolutions and the time spent in the
+    /// individual phases of the calculation.
+    pub fn solve_with_stats(
+        &mut self,
+        solver: Option<&DFTSolver>,
+        debug: bool,
+    ) -> EosResult<SolverStats> {
+        let start = Instant::now();
+        let convolver = Rc::new(TimedConvolver::new(self.convolver.clone()));
+        let timed: Rc<dyn Convolver<f64, D>> = convolver.clone();
+        let original = std::mem::replace(&mut self.convolver, timed);
+        let mut stats = SolverStats::default();
+        let result = self.solve_internal(solver, debug, Some(&mut stats));
+        self.convolver = original;
+        result?;
+
+        // the functional time recorded by the solver includes the convolutions
+        let residual_time = stats.functional_time;
+        stats.convolutions = convolver.count.get();
+        stats.convolution_time = convolver.time.get();
+        stats.functional_time = residual_time.saturating_sub(stats.convolution_time);
+        stats.total_time = start.elapsed();
+        stats.mixing_time = stats.total_time.saturating_sub(residual_time);
+        Ok(stats)
+    }
+
     pub fn entropy_density(&self, contributions: Contributions) -> EosResult<QuantityArray<U, D>> {
         // initialize convolver
         let t = self.temperature.to_reduced(U::reference_temperature())?;
@@ -568,4 +1144,306 @@ where
         )? * U::reference_pressure();
         Ok(self.integrate(&internal_energy_density))
     }
+
+    /// Return a copy of the profile on a grid with `factor` times fewer grid
+    /// points along every axis.
+    ///
+    /// The density is averaged over blocks of `factor` grid points, weighted
+    /// with the integration weights (i.e., the volume of the grid cells) of the
+    /// fine grid. The external potential is Boltzmann averaged over the same
+    /// blocks, so that inaccessible regions stay inaccessible. For polar axes
+    /// the logarithmic grids of both resolutions are not nested and the
+    /// averaging is only approximate. The convolver is planned for the coarse grid.
+    pub fn coarsen(&self, factor: usize) -> EosResult<Self> {
+        if factor == 0 {
+            return Err(EosError::Error(
+                "The coarsening factor has to be positive.".into(),
+            ));
+        }
+        let points = self
+            .grid
+            .axes()
+            .iter()
+            .map(|ax| {
+                let n = ax.grid.len();
+                if n % factor != 0 {
+                    Err(EosError::Error(format!(
+                        "The number of grid points {} is not divisible by {}.",
+                        n, factor
+                    )))
+                } else {
+                    Ok(n / factor)
+                }
+            })
+            .collect::<EosResult<Vec<_>>>()?;
+        let grid = self.grid.with_points::<U>(&points)?;
+
+        let weights = self.grid.integration_weights();
+        let mut density = self.density.to_reduced(U::reference_density())?;
+        let mut boltzmann = (-&self.external_potential).mapv(f64::exp);
+        for (i, w) in weights.into_iter().enumerate() {
+            density = Self::block_average(&density, i + 1, w, factor);
+            boltzmann = Self::block_average(&boltzmann, i + 1, w, factor);
+        }
+        let external_potential = boltzmann.mapv(|b| (-b.ln()).min(MAX_POTENTIAL));
+
+        let t = self.temperature.to_reduced(U::reference_temperature())?;
+        let weight_functions = self.dft.functional.weight_functions(t);
+        let convolver = ConvolverFFT::plan(&grid, &weight_functions, Some(1));
+
+        Ok(Self {
+            grid,
+            convolver,
+            dft: self.dft.clone(),
+            temperature: self.temperature,
+            density: density * U::reference_density(),
+            chemical_potential: self.chemical_potential.clone(),
+            specification: self.specification.clone(),
+            external_potential,
+            electrostatics: self.electrostatics.clone(),
+            frozen_mask: None,
+            bulk: self.bulk.clone(),
+        })
+    }
+
+    /// Set the density by linear interpolation of the density of another
+    /// profile on the same domain, e.g., a converged profile on a coarser grid.
+    pub fn interpolate_density(&mut self, profile: &Self) -> EosResult<()> {
+        let mut density = profile.density.to_reduced(U::reference_density())?;
+        for (i, (from, to)) in profile
+            .grid
+            .grids()
+            .into_iter()
+            .zip(self.grid.grids())
+            .enumerate()
+        {
+            density = Self::interpolate_axis(&density, i + 1, from, to);
+        }
+
+        // keep the density at frozen grid points
+        if let Some(mask) = &self.frozen_mask {
+            let frozen = self.density.to_reduced(U::reference_density())?;
+            for (mut rho, rho_frozen) in density.outer_iter_mut().zip(frozen.outer_iter()) {
+                Zip::from(&mut rho)
+                    .and(&rho_frozen)
+                    .and(mask)
+                    .for_each(|rho, &rho_frozen, &f| {
+                        if f {
+                            *rho = rho_frozen
+                        }
+                    });
+            }
+        }
+        self.density = density * U::reference_density();
+        Ok(())
+    }
+
+    /// Hold the density constant at the grid points where `mask` is `true`.
+    ///
+    /// If `density` is provided, its values at the frozen grid points are
+    /// imposed on the profile, otherwise the current density is kept. The solver
+    /// only updates the density at the remaining grid points, e.g., to clamp a
+    /// reservoir region to the bulk density.
+    pub fn freeze(
+        &mut self,
+        mask: Array<bool, D>,
+        density: Option<&QuantityArray<U, D::Larger>>,
+    ) -> EosResult<()> {
+        if mask.shape() != &self.density.shape()[1..] {
+            return Err(EosError::Error(format!(
+                "The shape {:?} of the mask does not match the grid {:?}.",
+                mask.shape(),
+                &self.density.shape()[1..]
+            )));
+        }
+        if let Some(density) = density {
+            if density.shape() != self.density.shape() {
+                return Err(EosError::Error(
+                    "The shape of the frozen density does not match the profile.".into(),
+                ));
+            }
+            let density = density.to_reduced(U::reference_density())?;
+            let mut rho = self.density.to_reduced(U::reference_density())?;
+            for (mut rho, rho_frozen) in rho.outer_iter_mut().zip(density.outer_iter()) {
+                Zip::from(&mut rho)
+                    .and(&rho_frozen)
+                    .and(&mask)
+                    .for_each(|rho, &rho_frozen, &f| {
+                        if f {
+                            *rho = rho_frozen
+                        }
+                    });
+            }
+            self.density = rho * U::reference_density();
+        }
+        self.frozen_mask = Some(mask);
+        Ok(())
+    }
+
+    /// Return the difference between the densities of this and another profile.
+    ///
+    /// The other profile is interpolated linearly onto the grid of this profile
+    /// and the difference is evaluated as the integration-weighted L2 norm
+    /// $\sqrt{\frac{1}{V}\int\sum_\alpha\left(\frac{\rho_\alpha(\mathbf{r})-\rho_\alpha'(\mathbf{r})}{\rho^\mathrm{b}}\right)^2\mathrm{d}\mathbf{r}}$
+    /// relative to the total bulk density of this profile.
+    pub fn l2_difference(&self, other: &Self) -> EosResult<f64> {
+        if self.grid.name() != other.grid.name() {
+            return Err(EosError::Error(format!(
+                "The geometries of the profiles ({} and {}) differ.",
+                self.grid.name(),
+                other.grid.name()
+            )));
+        }
+        if self.density.shape()[0] != other.density.shape()[0] {
+            return Err(EosError::Error(
+                "The profiles have different numbers of segments.".into(),
+            ));
+        }
+        let mut density = other.density.to_reduced(U::reference_density())?;
+        for (i, (from, to)) in other
+            .grid
+            .grids()
+            .into_iter()
+            .zip(self.grid.grids())
+            .enumerate()
+        {
+            density = Self::interpolate_axis(&density, i + 1, from, to);
+        }
+        let bulk_density = self.bulk.density.to_reduced(U::reference_density())?;
+        let diff = ((self.density.to_reduced(U::reference_density())? - density) / bulk_density)
+            .mapv(|d| d * d)
+            .sum_axis(Axis_nd(0));
+        let volume = self.integrate_reduced(Array::ones(diff.raw_dim()));
+        Ok((self.integrate_reduced(diff) / volume).sqrt())
+    }
+
+    /// Linearly interpolate an array along the given axis from the points `from`
+    /// to the points `to`. Values outside of `from` are extrapolated constantly.
+    fn interpolate_axis<E: RemoveAxis>(
+        array: &Array<f64, E>,
+        axis: usize,
+        from: &Array1<f64>,
+        to: &Array1<f64>,
+    ) -> Array<f64, E> {
+        let n = from.len();
+        let mut shape = array.raw_dim();
+        shape[axis] = to.len();
+        let mut result = Array::zeros(shape);
+        for (mut lane, &x) in result.axis_iter_mut(Axis_nd(axis)).zip(to) {
+            let j = from.as_slice().unwrap().partition_point(|&f| f <= x);
+            if j == 0 {
+                lane.assign(&array.index_axis(Axis_nd(axis), 0));
+            } else if j == n {
+                lane.assign(&array.index_axis(Axis_nd(axis), n - 1));
+            } else {
+                let w = (x - from[j - 1]) / (from[j] - from[j - 1]);
+                lane.assign(&array.index_axis(Axis_nd(axis), j - 1));
+                lane *= 1.0 - w;
+                lane.scaled_add(w, &array.index_axis(Axis_nd(axis), j));
+            }
+        }
+        result
+    }
+
+    /// Average blocks of `factor` consecutive entries along the given axis with the given weights.
+    fn block_average<E: RemoveAxis>(
+        array: &Array<f64, E>,
+        axis: usize,
+        weights: &Array1<f64>,
+        factor: usize,
+    ) -> Array<f64, E> {
+        let mut shape = array.raw_dim();
+        shape[axis] /= factor;
+        let mut result = Array::zeros(shape);
+        for (j, mut lane) in result.axis_iter_mut(Axis_nd(axis)).enumerate() {
+            let block = j * factor..(j + 1) * factor;
+            for k in block.clone() {
+                lane.scaled_add(weights[k], &array.index_axis(Axis_nd(axis), k));
+            }
+            lane /= weights.slice(s![block]).sum();
+        }
+        result
+    }
+}
+
+/// Check whether a grid point with the given reduced external potential is
+/// accessible to a segment. The density at inaccessible points is not iterated
+/// by the solver.
+pub(crate) fn is_accessible(external_potential: f64) -> bool {
+    external_potential + f64::EPSILON < MAX_POTENTIAL
+}
+
+#[cfg(feature = "hdf5")]
+impl<D: Dimension, F> DFTProfile<SIUnit, D, F>
+where
+    D::Larger: Dimension<Smaller = D>,
+{
+    /// Write the profile to an HDF5 file.
+    ///
+    /// The file contains the datasets `density` (reduced), `density_si` (in mol/m³),
+    /// `external_potential` (reduced), and the coordinates `grid_0`, `grid_1`, ...
+    /// (in Å) of all axes. The temperature (in K), the chemical potential (in J/mol),
+    /// the bulk mole fractions, and the geometry are stored as attributes of the file.
+    pub fn write_hdf5<P: AsRef<Path>>(&self, path: P) -> EosResult<()> {
+        let write = || -> Result<(), Box<dyn std::error::Error>> {
+            let file = hdf5::File::create(path.as_ref())?;
+            let density = self.density.to_reduced(SIUnit::reference_density())?;
+            let density_si = self.density.to_reduced(MOL / METER.powi(3))?;
+            file.new_dataset_builder()
+                .with_data(&density)
+                .create("density")?;
+            file.new_dataset_builder()
+                .with_data(&density_si)
+                .create("density_si")?;
+            file.new_dataset_builder()
+                .with_data(&self.external_potential)
+                .create("external_potential")?;
+            for (i, grid) in self.grid.grids().into_iter().enumerate() {
+                file.new_dataset_builder()
+                    .with_data(grid)
+                    .create(format!("grid_{}", i).as_str())?;
+            }
+            file.new_attr::<f64>()
+                .create("temperature")?
+                .write_scalar(&self.temperature.to_reduced(KELVIN)?)?;
+            file.new_attr_builder()
+                .with_data(&self.chemical_potential.to_reduced(JOULE / MOL)?)
+                .create("chemical_potential")?;
+            file.new_attr_builder()
+                .with_data(&self.bulk.molefracs)
+                .create("molefracs")?;
+            let geometry: VarLenUnicode = self.grid.name().parse()?;
+            file.new_attr::<VarLenUnicode>()
+                .create("geometry")?
+                .write_scalar(&geometry)?;
+            Ok(())
+        };
+        write().map_err(|e| EosError::Error(format!("Writing HDF5 file failed: {}", e)))
+    }
+
+    /// Restore the density profile and the chemical potential from an HDF5
+    /// file written by [DFTProfile::write_hdf5].
+    pub fn read_hdf5<P: AsRef<Path>>(&mut self, path: P) -> EosResult<()> {
+        let read = || -> Result<_, Box<dyn std::error::Error>> {
+            let file = hdf5::File::open(path.as_ref())?;
+            let density = file
+                .dataset("density")?
+                .read_dyn::<f64>()?
+                .into_dimensionality::<D::Larger>()?;
+            let chemical_potential = file.attr("chemical_potential")?.read_1d::<f64>()?;
+            Ok((density, chemical_potential))
+        };
+        let (density, chemical_potential) =
+            read().map_err(|e| EosError::Error(format!("Reading HDF5 file failed: {}", e)))?;
+        if density.shape() != self.density.shape() {
+            return Err(EosError::Error(format!(
+                "The density in the file has shape {:?}, but the profile requires {:?}.",
+                density.shape(),
+                self.density.shape()
+            )));
+        }
+        self.density = density * SIUnit::reference_density();
+        self.chemical_potential = chemical_potential * (JOULE / MOL);
+        Ok(())
+    }
 }
@@ -168,6 +168,108 @@ impl PyExternalPotential {
         })
     }
 
+    /// 12-6 Lennard-Jones potential of a spherical shell
+    ///
+    /// The fluid occupies the cavity inside the shell (the pore size is the
+    /// inner radius of the shell). Only available in spherical geometry.
+    ///
+    /// .. math:: V_i^\mathrm{ext}(r)=\frac{2\pi m_i\rho_s}{r}\int_R^{R+\Delta}a\int_{a-r}^{a+r}s\,u_{si}(s)\mathrm{d}s\,\mathrm{d}a,~~~~u_{si}(s)=4\varepsilon_{si}\left(\left(\frac{\sigma_{si}}{s}\right)^{12}-\left(\frac{\sigma_{si}}{s}\right)^6\right),~~~~\varepsilon_{si}=\sqrt{\varepsilon_{ss}\varepsilon_{ii}},~~~~\sigma_{si}=\frac{1}{2}\left(\sigma_{ss}+\sigma_{ii}\right)
+    ///
+    /// Parameters
+    /// ----------
+    /// sigma_ss : float
+    ///     Segment diameter of the solid.
+    /// epsilon_k_ss : float
+    ///     Energy parameter of the solid.
+    /// rho_s : float
+    ///     Density of the solid.
+    /// thickness : float
+    ///     Thickness of the shell.
+    ///
+    /// Returns
+    /// -------
+    /// ExternalPotential
+    ///
+    #[staticmethod]
+    #[pyo3(text_signature = "(sigma_ss, epsilon_k_ss, rho_s, thickness)")]
+    pub fn SphericalShell(sigma_ss: f64, epsilon_k_ss: f64, rho_s: f64, thickness: f64) -> Self {
+        Self(ExternalPotential::SphericalShell {
+            sigma_ss,
+            epsilon_k_ss,
+            rho_s,
+            thickness,
+        })
+    }
+
+    /// Individual external potentials for every segment.
+    ///
+    /// Can be used to specify different solid parameters for
+    /// the components of a mixture.
+    ///
+    /// Parameters
+    /// ----------
+    /// potentials : [ExternalPotential]
+    ///     The external potential for every segment.
+    ///
+    /// Returns
+    /// -------
+    /// ExternalPotential
+    ///
+    #[staticmethod]
+    #[pyo3(text_signature = "(potentials)")]
+    pub fn PerComponent(potentials: Vec<PyExternalPotential>) -> Self {
+        Self(ExternalPotential::PerComponent(
+            potentials.into_iter().map(|p| p.0).collect(),
+        ))
+    }
+
+    /// Sum of several external potentials.
+    ///
+    /// The potential cutoff of the pore is applied to the
+    /// total potential.
+    ///
+    /// Parameters
+    /// ----------
+    /// potentials : [ExternalPotential]
+    ///     The external potentials that are added up.
+    ///
+    /// Returns
+    /// -------
+    /// ExternalPotential
+    ///
+    #[staticmethod]
+    #[pyo3(text_signature = "(potentials)")]
+    pub fn Sum(potentials: Vec<PyExternalPotential>) -> Self {
+        Self(ExternalPotential::Sum(
+            potentials.into_iter().map(|p| p.0).collect(),
+        ))
+    }
+
+    /// Linear electrostatic potential of a charged surface.
+    ///
+    /// Parameters
+    /// ----------
+    /// surface_potential : float
+    ///     The electrostatic potential at the surface in V.
+    /// field : float
+    ///     The (fixed) electric field strength in V/Å.
+    /// charges : numpy.ndarray[float]
+    ///     The valences of all segments.
+    ///
+    /// Returns
+    /// -------
+    /// ExternalPotential
+    ///
+    #[staticmethod]
+    #[pyo3(text_signature = "(surface_potential, field, charges)")]
+    pub fn Electrostatic(surface_potential: f64, field: f64, charges: &PyArray1<f64>) -> Self {
+        Self(ExternalPotential::Electrostatic {
+            surface_potential,
+            field,
+            charges: charges.to_owned_array(),
+        })
+    }
+
     /// Free-energy averaged potential
     ///
     /// for details see: `J. Eller, J. Gross (2021) <https://pubs.acs.org/doi/abs/10.1021/acs.langmuir.0c03287>`_
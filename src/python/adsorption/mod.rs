@@ -10,13 +10,64 @@ macro_rules! impl_adsorption {
         #[pyclass(name = "Adsorption1D", unsendable)]
         pub struct PyAdsorption1D(Adsorption1D<SIUnit, $func>);
 
+        /// Container structure for adsorption isotherms in 2D pores.
+        #[pyclass(name = "Adsorption2D", unsendable)]
+        pub struct PyAdsorption2D(Adsorption2D<SIUnit, $func>);
+
         /// Container structure for adsorption isotherms in 3D pores.
         #[pyclass(name = "Adsorption3D", unsendable)]
         pub struct PyAdsorption3D(Adsorption3D<SIUnit, $func>);
 
         impl_adsorption_isotherm!($func, $py_func, PyAdsorption1D, PyPore1D, PyPoreProfile1D);
+        impl_adsorption_isotherm!($func, $py_func, PyAdsorption2D, PyPore2D, PyPoreProfile2D);
         impl_adsorption_isotherm!($func, $py_func, PyAdsorption3D, PyPore3D, PyPoreProfile3D);
 
+        #[pymethods]
+        impl PyAdsorption1D {
+            /// Calculate the solvation force per wall area between the walls
+            /// of a slit pore for the given wall separations.
+            ///
+            /// Parameters
+            /// ----------
+            /// functional : HelmholtzEnergyFunctional
+            ///     The Helmholtz energy functional.
+            /// potential : ExternalPotential
+            ///     The potential used to model wall-fluid interactions.
+            /// bulk : State
+            ///     The bulk state in equilibrium with the pore.
+            /// separations : SIArray1
+            ///     The wall separations.
+            /// n_grid : int
+            ///     The number of grid points used for all separations.
+            /// solver: DFTSolver, optional
+            ///     Custom solver options.
+            ///
+            /// Returns
+            /// -------
+            /// SIArray1
+            ///
+            #[staticmethod]
+            #[pyo3(text_signature = "(functional, potential, bulk, separations, n_grid, solver=None)")]
+            fn solvation_force(
+                functional: &$py_func,
+                potential: PyExternalPotential,
+                bulk: &PyState,
+                separations: PySIArray1,
+                n_grid: usize,
+                solver: Option<PyDFTSolver>,
+            ) -> PyResult<PySIArray1> {
+                Ok(Adsorption1D::solvation_force(
+                    &functional.0,
+                    &potential.0,
+                    &bulk.0,
+                    &separations.into(),
+                    n_grid,
+                    solver.map(|s| s.0).as_ref(),
+                )?
+                .into())
+            }
+        }
+
         fn parse_pressure_specification(pressure: &PyAny) -> PyResult<PressureSpecification<SIUnit>> {
             if let Ok((p_min, p_max, points)) = pressure.extract::<(PySINumber, PySINumber, usize)>() {
                 Ok(PressureSpecification::Plim {
@@ -85,6 +136,57 @@ macro_rules! impl_adsorption_isotherm {
                 )?))
             }
 
+            /// Calculate an adsorption isotherm with adaptive pressure steps.
+            /// Additional points are inserted between consecutive pressures
+            /// until the change in total adsorption per step is below
+            /// `max_adsorption_step` (relative to the maximum adsorption).
+            ///
+            /// Parameters
+            /// ----------
+            /// functional : HelmholtzEnergyFunctional
+            ///     The Helmholtz energy functional.
+            /// temperature : SINumber
+            ///     The temperature.
+            /// pressure : {(SINumber, SINumber, int), SIArray1}
+            ///     The initial pressure grid. Either a tuple containing the
+            ///     minimum pressure, the maximum pressure, and the number of
+            ///     points, or an array containing specific values.
+            /// pore : Pore
+            ///     The pore parameters.
+            /// max_adsorption_step : float
+            ///     The maximum change in total adsorption between two points
+            ///     relative to the maximum total adsorption.
+            /// molefracs: numpy.ndarray[float], optional
+            ///     For a mixture, the molefracs of the bulk system.
+            /// solver: DFTSolver, optional
+            ///     Custom solver options.
+            ///
+            /// Returns
+            /// -------
+            /// Adsorption
+            ///
+            #[staticmethod]
+            #[pyo3(text_signature = "(functional, temperature, pressure, pore, max_adsorption_step, molefracs=None, solver=None)")]
+            pub fn adaptive_adsorption_isotherm(
+                functional: &$py_func,
+                temperature: PySINumber,
+                pressure: &PyAny,
+                pore: &$py_pore,
+                max_adsorption_step: f64,
+                molefracs: Option<&PyArray1<f64>>,
+                solver: Option<PyDFTSolver>,
+            ) -> PyResult<Self> {
+                Ok(Self(Adsorption::adaptive_adsorption_isotherm(
+                    &functional.0,
+                    temperature.into(),
+                    &parse_pressure_specification(pressure)?,
+                    &pore.0,
+                    max_adsorption_step,
+                    molefracs.map(|x| x.to_owned_array()).as_ref(),
+                    solver.map(|s| s.0).as_ref(),
+                )?))
+            }
+
             /// Calculate a desorption isotherm for the given pressure range.
             /// The profiles are evaluated starting from the highest pressure.
             /// The resulting density profiles can be metastable.
@@ -130,6 +232,111 @@ macro_rules! impl_adsorption_isotherm {
                 )?))
             }
 
+            /// Calculate both branches of a hysteresis loop. The adsorption
+            /// branch is evaluated starting from the lowest pressure, the
+            /// desorption branch starting from the converged profile at the
+            /// highest pressure of the adsorption branch.
+            ///
+            /// Parameters
+            /// ----------
+            /// functional : HelmholtzEnergyFunctional
+            ///     The Helmholtz energy functional.
+            /// temperature : SINumber
+            ///     The temperature.
+            /// pressure : {(SINumber, SINumber, int), SIArray1}
+            ///     The pressures for which the profiles are calculated. Either
+            ///     a tuple containing the minimum pressure, the maximum pressure,
+            ///     and the number of points, or an array containing specific values.
+            /// pore : Pore
+            ///     The pore parameters.
+            /// molefracs: numpy.ndarray[float], optional
+            ///     For a mixture, the molefracs of the bulk system.
+            /// solver: DFTSolver, optional
+            ///     Custom solver options.
+            ///
+            /// Returns
+            /// -------
+            /// (Adsorption, Adsorption)
+            ///     The adsorption and the desorption branch.
+            ///
+            #[staticmethod]
+            #[pyo3(text_signature = "(functional, temperature, pressure, pore, molefracs=None, solver=None)")]
+            pub fn hysteresis(
+                functional: &$py_func,
+                temperature: PySINumber,
+                pressure: &PyAny,
+                pore: &$py_pore,
+                molefracs: Option<&PyArray1<f64>>,
+                solver: Option<PyDFTSolver>,
+            ) -> PyResult<(Self, Self)> {
+                let hysteresis = Adsorption::hysteresis(
+                    &functional.0,
+                    temperature.into(),
+                    &parse_pressure_specification(pressure)?,
+                    &pore.0,
+                    molefracs.map(|x| x.to_owned_array()).as_ref(),
+                    solver.map(|s| s.0).as_ref(),
+                )?;
+                Ok((Self(hysteresis.adsorption), Self(hysteresis.desorption)))
+            }
+
+            /// Trace the complete isotherm of a first-order transition in the
+            /// pore by pseudo-arclength continuation, including the unstable
+            /// states between the spinodals.
+            ///
+            /// Parameters
+            /// ----------
+            /// functional : HelmholtzEnergyFunctional
+            ///     The Helmholtz energy functional.
+            /// temperature : SINumber
+            ///     The temperature.
+            /// p_min : SINumber
+            ///     The lowest pressure of the isotherm.
+            /// p_max : SINumber
+            ///     The highest pressure of the isotherm.
+            /// pore : Pore
+            ///     The pore parameters.
+            /// arc_length_step : float
+            ///     The maximum step along the path in scaled pressure and loading.
+            /// molefracs: numpy.ndarray[float], optional
+            ///     For a mixture, the molefracs of the bulk system.
+            /// solver: DFTSolver, optional
+            ///     Custom solver options.
+            ///
+            /// Returns
+            /// -------
+            /// (Adsorption, Adsorption, Adsorption)
+            ///     The adsorption, the unstable, and the desorption branch.
+            ///
+            #[staticmethod]
+            #[pyo3(text_signature = "(functional, temperature, p_min, p_max, pore, arc_length_step, molefracs=None, solver=None)")]
+            pub fn arc_length_continuation(
+                functional: &$py_func,
+                temperature: PySINumber,
+                p_min: PySINumber,
+                p_max: PySINumber,
+                pore: &$py_pore,
+                arc_length_step: f64,
+                molefracs: Option<&PyArray1<f64>>,
+                solver: Option<PyDFTSolver>,
+            ) -> PyResult<(Self, Self, Self)> {
+                let isotherm = Adsorption::arc_length_continuation(
+                    &functional.0,
+                    temperature.into(),
+                    p_min.into(),
+                    p_max.into(),
+                    &pore.0,
+                    arc_length_step,
+                    molefracs.map(|x| x.to_owned_array()).as_ref(),
+                    solver.map(|s| s.0).as_ref(),
+                )?;
+                Ok((
+                    Self(isotherm.adsorption),
+                    Self(isotherm.unstable),
+                    Self(isotherm.desorption),
+                ))
+            }
+
             /// Calculate an equilibrium isotherm for the given pressure range.
             /// A phase equilibrium in the pore is calculated to determine the
             /// stable phases for every pressure. If no phase equilibrium can be
@@ -233,6 +440,48 @@ macro_rules! impl_adsorption_isotherm {
                 )?))
             }
 
+            /// Simulate a temperature-programmed desorption from a closed pore.
+            ///
+            /// Parameters
+            /// ----------
+            /// functional : HelmholtzEnergyFunctional
+            ///     The Helmholtz energy functional.
+            /// temperatures : SIArray1
+            ///     The temperatures of the ramp.
+            /// pressure : SINumber
+            ///     The pressure at which the pore is loaded at the first temperature.
+            /// pore : Pore
+            ///     The pore parameters.
+            /// molefracs: numpy.ndarray[float], optional
+            ///     For a mixture, the molefracs of the bulk system.
+            /// solver: DFTSolver, optional
+            ///     Custom solver options.
+            ///
+            /// Returns
+            /// -------
+            /// SIArray1
+            ///     The equilibrium pressure at every temperature.
+            #[staticmethod]
+            #[pyo3(text_signature = "(functional, temperatures, pressure, pore, molefracs=None, solver=None)")]
+            pub fn temperature_programmed_desorption(
+                functional: &$py_func,
+                temperatures: PySIArray1,
+                pressure: PySINumber,
+                pore: &$py_pore,
+                molefracs: Option<&PyArray1<f64>>,
+                solver: Option<PyDFTSolver>,
+            ) -> PyResult<PySIArray1> {
+                Ok(Adsorption::temperature_programmed_desorption(
+                    &functional.0,
+                    &temperatures.into(),
+                    pressure.into(),
+                    &pore.0,
+                    molefracs.map(|x| x.to_owned_array()).as_ref(),
+                    solver.map(|s| s.0).as_ref(),
+                )?
+                .into())
+            }
+
             #[getter]
             fn get_profiles(&self) -> Vec<$py_pore_profile> {
                 self.0
@@ -251,6 +500,11 @@ macro_rules! impl_adsorption_isotherm {
                 self.0.pressure().into()
             }
 
+            #[getter]
+            fn get_fugacity(&self) -> PySIArray2 {
+                self.0.fugacity().into()
+            }
+
             #[getter]
             fn get_molar_gibbs_energy(&self) -> PySIArray1 {
                 self.0.molar_gibbs_energy().into()
@@ -261,6 +515,36 @@ macro_rules! impl_adsorption_isotherm {
                 self.0.adsorption().into()
             }
 
+            /// Return the adsorption of the given component at every pressure.
+            ///
+            /// Parameters
+            /// ----------
+            /// component : int
+            ///     The index of the component.
+            ///
+            /// Returns
+            /// -------
+            /// SIArray1
+            #[pyo3(text_signature = "($self, component)")]
+            fn loading(&self, component: usize) -> PySIArray1 {
+                self.0.loading(component).into()
+            }
+
+            /// Return the adsorption of every component per mass of the adsorbent.
+            ///
+            /// Parameters
+            /// ----------
+            /// solid_mass : SINumber
+            ///     The mass of the solid in the domain of the profiles.
+            ///
+            /// Returns
+            /// -------
+            /// SIArray2
+            #[pyo3(text_signature = "($self, solid_mass)")]
+            fn loading_per_mass(&self, solid_mass: PySINumber) -> PySIArray2 {
+                self.0.loading_per_mass(solid_mass.into()).into()
+            }
+
             #[getter]
             fn get_total_adsorption(&self) -> PySIArray1 {
                 self.0.total_adsorption().into()
@@ -270,6 +554,58 @@ macro_rules! impl_adsorption_isotherm {
             fn get_grand_potential(&mut self) -> PySIArray1 {
                 self.0.grand_potential().into()
             }
+
+            /// Return the pressures and the total adsorption in the given
+            /// units, e.g., for plotting.
+            ///
+            /// Parameters
+            /// ----------
+            /// pressure_unit : SINumber
+            ///     The unit of the pressure, e.g., BAR.
+            /// loading_unit : SINumber
+            ///     The unit of the total adsorption.
+            ///
+            /// Returns
+            /// -------
+            /// (numpy.ndarray[float], numpy.ndarray[float])
+            #[pyo3(text_signature = "($self, pressure_unit, loading_unit)")]
+            fn pressure_loading<'py>(
+                &self,
+                pressure_unit: PySINumber,
+                loading_unit: PySINumber,
+                py: Python<'py>,
+            ) -> PyResult<(&'py PyArray1<f64>, &'py PyArray1<f64>)> {
+                let (p, n) = self
+                    .0
+                    .pressure_loading(pressure_unit.into(), loading_unit.into())?;
+                Ok((p.view().to_pyarray(py), n.view().to_pyarray(py)))
+            }
+
+            /// Return the pressures and the grand potentials in the given
+            /// units, e.g., for plotting.
+            ///
+            /// Parameters
+            /// ----------
+            /// pressure_unit : SINumber
+            ///     The unit of the pressure, e.g., BAR.
+            /// grand_potential_unit : SINumber
+            ///     The unit of the grand potential.
+            ///
+            /// Returns
+            /// -------
+            /// (numpy.ndarray[float], numpy.ndarray[float])
+            #[pyo3(text_signature = "($self, pressure_unit, grand_potential_unit)")]
+            fn pressure_grand_potential<'py>(
+                &self,
+                pressure_unit: PySINumber,
+                grand_potential_unit: PySINumber,
+                py: Python<'py>,
+            ) -> PyResult<(&'py PyArray1<f64>, &'py PyArray1<f64>)> {
+                let (p, omega) = self
+                    .0
+                    .pressure_grand_potential(pressure_unit.into(), grand_potential_unit.into())?;
+                Ok((p.view().to_pyarray(py), omega.view().to_pyarray(py)))
+            }
         }
     };
 }
@@ -14,16 +14,22 @@ macro_rules! impl_pore {
         /// potential : ExternalPotential
         ///     The potential used to model wall-fluid interactions.
         /// n_grid : int, optional
-        ///     The number of grid points.
+        ///     The number of grid points. Chosen from the pore size
+        ///     and the segment diameters if not provided.
         /// potential_cutoff : float, optional
         ///     Maximum value for the external potential.
+        /// potential_offset : float, optional
+        ///     Extent of the domain beyond the wall of slit pores in units
+        ///     of the largest segment diameter (default: 2).
+        /// quantum_correction : SINumber, optional
+        ///     The Feynman-Hibbs parameter hbar²/(24 m k_B) for light gases.
         ///
         /// Returns
         /// -------
         /// Pore1D
         ///
         #[pyclass(name = "Pore1D", unsendable)]
-        #[pyo3(text_signature = "(functional, geometry, pore_size, potential, n_grid=None, potential_cutoff=None)")]
+        #[pyo3(text_signature = "(functional, geometry, pore_size, potential, n_grid=None, potential_cutoff=None, potential_offset=None, quantum_correction=None)")]
         pub struct PyPore1D(Pore1D<SIUnit, $func>);
 
         #[pyclass(name = "PoreProfile1D", unsendable)]
@@ -41,15 +47,24 @@ macro_rules! impl_pore {
                 potential: PyExternalPotential,
                 n_grid: Option<usize>,
                 potential_cutoff: Option<f64>,
+                potential_offset: Option<f64>,
+                quantum_correction: Option<PySINumber>,
             ) -> Self {
-                Self(Pore1D::new(
+                let mut pore = Pore1D::new(
                     &functional.0,
                     geometry.0,
                     pore_size.into(),
                     potential.0,
                     n_grid,
                     potential_cutoff,
-                ))
+                );
+                if let Some(potential_offset) = potential_offset {
+                    pore = pore.potential_offset(potential_offset);
+                }
+                if let Some(quantum_correction) = quantum_correction {
+                    pore = pore.quantum_correction(quantum_correction.into());
+                }
+                Self(pore)
             }
 
             /// Initialize the pore for the given bulk state.
@@ -62,34 +77,551 @@ macro_rules! impl_pore {
             ///     The external potential in the pore. Used to
             ///     save computation time in the case of costly
             ///     evaluations of external potentials.
+            /// initial_density : SIArray, optional
+            ///     An initial guess for the density profile.
             ///
             /// Returns
             /// -------
             /// PoreProfile1D
-            #[pyo3(text_signature = "($self, bulk, external_potential=None)")]
+            #[pyo3(text_signature = "($self, bulk, external_potential=None, initial_density=None)")]
             fn initialize(
                 &self,
                 bulk: &PyState,
                 external_potential: Option<&PyArray2<f64>>,
+                initial_density: Option<PySIArray2>,
             ) -> PyResult<PyPoreProfile1D> {
-                Ok(PyPoreProfile1D(self.0.initialize(
-                    &bulk.0,
-                    external_potential.map(|e| e.to_owned_array()).as_ref(),
-                )?))
+                let external_potential = external_potential.map(|e| e.to_owned_array());
+                Ok(PyPoreProfile1D(match initial_density {
+                    Some(density) => self.0.initialize_with_density(
+                        &bulk.0,
+                        external_potential.as_ref(),
+                        &density.into(),
+                    )?,
+                    None => self.0.initialize(&bulk.0, external_potential.as_ref())?,
+                }))
+            }
+
+            /// Calculate the condensation pressure in the pore predicted
+            /// by the Kelvin equation. Only available for pure components.
+            ///
+            /// Parameters
+            /// ----------
+            /// temperature : SINumber
+            ///     The temperature.
+            /// contact_angle : float, optional
+            ///     The contact angle in radians (default: 0).
+            ///
+            /// Returns
+            /// -------
+            /// SINumber
+            #[args(contact_angle = "0.0")]
+            #[pyo3(text_signature = "($self, temperature, contact_angle=0.0)")]
+            fn kelvin_pressure(
+                &self,
+                temperature: PySINumber,
+                contact_angle: f64,
+            ) -> PyResult<PySINumber> {
+                Ok(self.0.kelvin_pressure(temperature.into(), contact_angle)?.into())
+            }
+
+            /// Calculate the external potential for several pore sizes at once.
+            ///
+            /// Parameters
+            /// ----------
+            /// pore_sizes : SIArray1
+            ///     The pore sizes.
+            /// temperature : SINumber
+            ///     The temperature.
+            ///
+            /// Returns
+            /// -------
+            /// [numpy.ndarray[float]]
+            #[pyo3(text_signature = "($self, pore_sizes, temperature)")]
+            fn external_potential_batch<'py>(
+                &self,
+                pore_sizes: PySIArray1,
+                temperature: PySINumber,
+                py: Python<'py>,
+            ) -> PyResult<Vec<&'py PyArray2<f64>>> {
+                let pore_sizes: SIArray1 = pore_sizes.into();
+                let pore_sizes: Vec<_> = (0..pore_sizes.len()).map(|i| pore_sizes.get(i)).collect();
+                Ok(self
+                    .0
+                    .external_potential_batch(&pore_sizes, temperature.into())?
+                    .into_iter()
+                    .map(|v| v.view().to_pyarray(py))
+                    .collect())
             }
         }
 
         #[pymethods]
         impl PyPoreProfile1D {
+            #[getter]
+            fn get_bulk_fugacity(&self) -> PySIArray1 {
+                self.0.bulk_fugacity().into()
+            }
+
             #[getter]
             fn get_grand_potential(&self) -> Option<PySINumber> {
                 self.0.grand_potential.map(PySINumber::from)
             }
 
+            #[getter]
+            fn get_association_fraction<'py>(
+                &self,
+                py: Python<'py>,
+            ) -> PyResult<Option<&'py PyArray2<f64>>> {
+                Ok(self
+                    .0
+                    .association_fraction()?
+                    .map(|x| x.view().to_pyarray(py)))
+            }
+
+            /// Solve the profile on a sequence of successively finer grids.
+            ///
+            /// Parameters
+            /// ----------
+            /// levels : int
+            ///     The number of grids including the original grid.
+            /// factor : int, optional
+            ///     The ratio of the numbers of grid points of successive grids (default: 2).
+            /// solver : DFTSolver, optional
+            ///     The solver used on all grids.
+            ///
+            /// Returns
+            /// -------
+            /// PoreProfile1D
+            #[args(factor = "2")]
+            #[pyo3(text_signature = "($self, levels, factor=2, solver=None)")]
+            fn solve_multigrid(
+                slf: &PyCell<Self>,
+                levels: usize,
+                factor: usize,
+                solver: Option<PyDFTSolver>,
+            ) -> PyResult<&PyCell<Self>> {
+                slf.borrow_mut()
+                    .0
+                    .solve_multigrid(levels, factor, solver.map(|s| s.0).as_ref())?;
+                Ok(slf)
+            }
+
+            /// Couple the density profile to the self-consistent electrostatic
+            /// potential of the ions and the charged walls of a slit pore.
+            ///
+            /// Parameters
+            /// ----------
+            /// valences : numpy.ndarray[float]
+            ///     The valences of all components.
+            /// relative_permittivity : float
+            ///     The relative permittivity of the solvent.
+            /// surface_charge_density : SINumber
+            ///     The number of elementary charges per area on each wall.
+            /// wall_position : SINumber
+            ///     The distance of the walls from the center of the pore.
+            /// charge_neutrality : bool, optional
+            ///     Enforce the electroneutrality of fluid and walls (default: False).
+            #[args(charge_neutrality = "false")]
+            #[pyo3(text_signature = "($self, valences, relative_permittivity, surface_charge_density, wall_position, charge_neutrality=False)")]
+            fn set_electrostatics(
+                &mut self,
+                valences: &PyArray1<f64>,
+                relative_permittivity: f64,
+                surface_charge_density: PySINumber,
+                wall_position: PySINumber,
+                charge_neutrality: bool,
+            ) -> PyResult<()> {
+                self.0.profile.electrostatics = Some(
+                    Electrostatics::new(
+                        valences.to_owned_array(),
+                        relative_permittivity,
+                        surface_charge_density.into(),
+                        wall_position.into(),
+                    )?
+                    .charge_neutrality(charge_neutrality),
+                );
+                Ok(())
+            }
+
             #[getter]
             fn get_interfacial_tension(&self) -> Option<PySINumber> {
                 self.0.interfacial_tension.map(PySINumber::from)
             }
+
+            #[getter]
+            fn get_excess_grand_potential(&self) -> Option<PySINumber> {
+                self.0.excess_grand_potential().map(PySINumber::from)
+            }
+
+            #[getter]
+            fn get_pore_volume(&self) -> PySINumber {
+                self.0.pore_volume().into()
+            }
+
+            #[getter]
+            fn get_surface_area(&self) -> PyResult<PySINumber> {
+                Ok(self.0.surface_area()?.into())
+            }
+
+            #[getter]
+            fn get_accessible_mask<'py>(&self, py: Python<'py>) -> &'py PyArray1<bool> {
+                self.0.accessible_mask().view().to_pyarray(py)
+            }
+
+            /// Calculate the number of fluid molecules in the first layer at the wall.
+            ///
+            /// Parameters
+            /// ----------
+            /// shell_cutoff : SINumber, optional
+            ///     The width of the layer. Defaults to the first minimum
+            ///     of the density after the first peak.
+            ///
+            /// Returns
+            /// -------
+            /// SINumber
+            #[pyo3(text_signature = "($self, shell_cutoff=None)")]
+            fn coordination_number(&self, shell_cutoff: Option<PySINumber>) -> PyResult<PySINumber> {
+                Ok(self
+                    .0
+                    .coordination_number(shell_cutoff.map(|c| c.into()))?
+                    .into())
+            }
+
+            /// Calculate the local compressibility, i.e., the derivative of the
+            /// reduced segment densities with respect to the reduced chemical
+            /// potential, by central finite differences.
+            ///
+            /// Parameters
+            /// ----------
+            /// solver : DFTSolver, optional
+            ///     The solver used for the perturbed profiles.
+            ///
+            /// Returns
+            /// -------
+            /// numpy.ndarray[float]
+            #[pyo3(text_signature = "($self, solver=None)")]
+            fn local_compressibility<'py>(
+                &self,
+                solver: Option<PyDFTSolver>,
+                py: Python<'py>,
+            ) -> PyResult<&'py PyArray2<f64>> {
+                Ok(self
+                    .0
+                    .local_compressibility(solver.map(|s| s.0).as_ref())?
+                    .view()
+                    .to_pyarray(py))
+            }
+
+            /// Solve the profile from the current density, an empty pore, and
+            /// a pore filled with the bulk liquid and return all distinct
+            /// solutions ordered by their grand potential.
+            ///
+            /// Parameters
+            /// ----------
+            /// solver : DFTSolver, optional
+            ///     The solver used for all initial guesses.
+            ///
+            /// Returns
+            /// -------
+            /// [(PoreProfile1D, bool)]
+            ///     The solutions and whether they are stable.
+            #[pyo3(text_signature = "($self, solver=None)")]
+            fn solve_all_branches(&self, solver: Option<PyDFTSolver>) -> PyResult<Vec<(Self, bool)>> {
+                Ok(self
+                    .0
+                    .solve_all_branches(solver.map(|s| s.0).as_ref())?
+                    .into_iter()
+                    .map(|(p, stable)| (Self(p), stable))
+                    .collect())
+            }
+
+            /// Calculate the differential entropy of adsorption relative to
+            /// the molar entropy of the bulk fluid. Only available for pure
+            /// components.
+            ///
+            /// Parameters
+            /// ----------
+            /// solver : DFTSolver, optional
+            ///     The solver used for the perturbed profiles.
+            ///
+            /// Returns
+            /// -------
+            /// SINumber
+            #[pyo3(text_signature = "($self, solver=None)")]
+            fn differential_entropy(&self, solver: Option<PyDFTSolver>) -> PyResult<PySINumber> {
+                Ok(self
+                    .0
+                    .differential_entropy(solver.map(|s| s.0).as_ref())?
+                    .into())
+            }
+
+            /// Return the constant normal component and the tangential
+            /// component of the pressure tensor in a slit pore.
+            ///
+            /// Returns
+            /// -------
+            /// (SINumber, SIArray1)
+            fn pressure_tensor(&self) -> PyResult<(PySINumber, PySIArray1)> {
+                let (p_n, p_t) = self.0.pressure_tensor()?;
+                Ok((p_n.into(), p_t.into()))
+            }
+
+            /// Return the interfacial tension per unit wall area. For slit
+            /// pores, the area of a single wall is used.
+            ///
+            /// Returns
+            /// -------
+            /// SINumber
+            fn interfacial_tension_per_area(&self) -> PyResult<PySINumber> {
+                Ok(self.0.interfacial_tension_per_area()?.into())
+            }
+
+            /// Return the solid-fluid interfacial tension, i.e., the excess
+            /// grand potential per unit wall area. Only available for slit pores.
+            ///
+            /// Returns
+            /// -------
+            /// SINumber
+            fn solid_fluid_interfacial_tension(&self) -> PyResult<PySINumber> {
+                Ok(self.0.solid_fluid_interfacial_tension()?.into())
+            }
+        }
+
+        /// Parameters required to specify a fluid around a convex solid,
+        /// i.e., a solid sphere or an infinitely long solid cylinder.
+        ///
+        /// Parameters
+        /// ----------
+        /// functional : HelmholtzEnergyFunctional
+        ///     The Helmholtz energy functional.
+        /// geometry : Geometry
+        ///     The geometry of the solid (Spherical or Polar).
+        /// solid_radius : SINumber
+        ///     The radius of the solid.
+        /// domain_size : SINumber
+        ///     The radius of the domain measured from the center of the solid.
+        /// sigma_ss : float
+        ///     The size parameter of the solid sites in units of Angstrom.
+        /// epsilon_k_ss : float
+        ///     The energy parameter of the solid sites in units of Kelvin.
+        /// rho_s : float
+        ///     The density of the solid sites in units of 1/Angstrom³.
+        /// n_grid : int, optional
+        ///     The number of grid points. Chosen from the domain size
+        ///     and the segment diameters if not provided.
+        /// potential_cutoff : float, optional
+        ///     Maximum value for the external potential.
+        ///
+        /// Returns
+        /// -------
+        /// CurvedSurface1D
+        ///
+        #[pyclass(name = "CurvedSurface1D", unsendable)]
+        #[pyo3(text_signature = "(functional, geometry, solid_radius, domain_size, sigma_ss, epsilon_k_ss, rho_s, n_grid=None, potential_cutoff=None)")]
+        pub struct PyCurvedSurface1D(CurvedSurface1D<SIUnit, $func>);
+
+        #[pymethods]
+        impl PyCurvedSurface1D {
+            #[new]
+            fn new(
+                functional: &$py_func,
+                geometry: PyGeometry,
+                solid_radius: PySINumber,
+                domain_size: PySINumber,
+                sigma_ss: f64,
+                epsilon_k_ss: f64,
+                rho_s: f64,
+                n_grid: Option<usize>,
+                potential_cutoff: Option<f64>,
+            ) -> Self {
+                Self(CurvedSurface1D::new(
+                    &functional.0,
+                    geometry.0,
+                    solid_radius.into(),
+                    domain_size.into(),
+                    sigma_ss,
+                    epsilon_k_ss,
+                    rho_s,
+                    n_grid,
+                    potential_cutoff,
+                ))
+            }
+
+            /// Initialize the density profile around the solid for the given bulk state.
+            ///
+            /// Parameters
+            /// ----------
+            /// bulk : State
+            ///     The bulk state in equilibrium with the fluid at the surface.
+            /// external_potential : numpy.ndarray[float], optional
+            ///     The external potential of the solid.
+            ///
+            /// Returns
+            /// -------
+            /// PoreProfile1D
+            #[pyo3(text_signature = "($self, bulk, external_potential=None)")]
+            fn initialize(
+                &self,
+                bulk: &PyState,
+                external_potential: Option<&PyArray2<f64>>,
+            ) -> PyResult<PyPoreProfile1D> {
+                let external_potential = external_potential.map(|e| e.to_owned_array());
+                Ok(PyPoreProfile1D(
+                    self.0.initialize(&bulk.0, external_potential.as_ref())?,
+                ))
+            }
+
+            /// Calculate the interfacial tension of the fluid at the
+            /// surface of the solid.
+            ///
+            /// Parameters
+            /// ----------
+            /// profile : PoreProfile1D
+            ///     The solved density profile.
+            ///
+            /// Returns
+            /// -------
+            /// SINumber
+            #[pyo3(text_signature = "($self, profile)")]
+            fn interfacial_tension(&self, profile: &PyPoreProfile1D) -> PyResult<PySINumber> {
+                Ok(self.0.interfacial_tension(&profile.0)?.into())
+            }
+
+            /// Calculate the adsorption (surface excess) of every component
+            /// at the surface of the solid.
+            ///
+            /// Parameters
+            /// ----------
+            /// profile : PoreProfile1D
+            ///     The solved density profile.
+            ///
+            /// Returns
+            /// -------
+            /// SIArray1
+            #[pyo3(text_signature = "($self, profile)")]
+            fn adsorption(&self, profile: &PyPoreProfile1D) -> PySIArray1 {
+                self.0.adsorption(&profile.0).into()
+            }
+
+            /// The surface area of the solid (per unit length for cylinders).
+            #[getter]
+            fn get_surface_area(&self) -> PySINumber {
+                self.0.surface_area().into()
+            }
+        }
+
+        /// Parameters required to specify a 2D cylindrical pore of finite length.
+        ///
+        /// Parameters
+        /// ----------
+        /// functional : HelmholtzEnergyFunctional
+        ///     The Helmholtz energy functional.
+        /// pore_size : SINumber
+        ///     The radius of the tube.
+        /// length : SINumber
+        ///     The length of the tube.
+        /// sigma_ss : float
+        ///     The size parameter of the solid sites.
+        /// epsilon_k_ss : float
+        ///     The energy parameter of the solid sites.
+        /// rho_s : float
+        ///     The surface density of solid sites.
+        /// n_grid : [int; 2], optional
+        ///     The number of grid points in radial and axial direction.
+        ///     Chosen from the pore size and the segment diameters if not provided.
+        /// potential_cutoff : float, optional
+        ///     Maximum value for the external potential.
+        /// reservoir_length : SINumber, optional
+        ///     Extent of the domain beyond the shell and the pore mouth.
+        ///
+        /// Returns
+        /// -------
+        /// Pore2D
+        ///
+        #[pyclass(name = "Pore2D", unsendable)]
+        #[pyo3(text_signature = "(functional, pore_size, length, sigma_ss, epsilon_k_ss, rho_s, n_grid=None, potential_cutoff=None, reservoir_length=None)")]
+        pub struct PyPore2D(Pore2D<SIUnit, $func>);
+
+        #[pyclass(name = "PoreProfile2D", unsendable)]
+        pub struct PyPoreProfile2D(PoreProfile2D<SIUnit, $func>);
+
+        impl_2d_profile!(PyPoreProfile2D, get_r, get_z);
+
+        #[pymethods]
+        impl PyPore2D {
+            #[new]
+            fn new(
+                functional: &$py_func,
+                pore_size: PySINumber,
+                length: PySINumber,
+                sigma_ss: f64,
+                epsilon_k_ss: f64,
+                rho_s: f64,
+                n_grid: Option<[usize; 2]>,
+                potential_cutoff: Option<f64>,
+                reservoir_length: Option<PySINumber>,
+            ) -> Self {
+                let mut pore = Pore2D::new(
+                    &functional.0,
+                    pore_size.into(),
+                    length.into(),
+                    sigma_ss,
+                    epsilon_k_ss,
+                    rho_s,
+                    n_grid,
+                    potential_cutoff,
+                );
+                if let Some(reservoir_length) = reservoir_length {
+                    pore = pore.reservoir_length(reservoir_length.into());
+                }
+                Self(pore)
+            }
+
+            /// Initialize the pore for the given bulk state.
+            ///
+            /// Parameters
+            /// ----------
+            /// bulk : State
+            ///     The bulk state in equilibrium with the pore.
+            /// external_potential : numpy.ndarray[float], optional
+            ///     The external potential in the pore. Used to
+            ///     save computation time in the case of costly
+            ///     evaluations of external potentials.
+            /// initial_density : SIArray, optional
+            ///     An initial guess for the density profile.
+            ///
+            /// Returns
+            /// -------
+            /// PoreProfile2D
+            #[pyo3(text_signature = "($self, bulk, external_potential=None, initial_density=None)")]
+            fn initialize(
+                &self,
+                bulk: &PyState,
+                external_potential: Option<&PyArray3<f64>>,
+                initial_density: Option<PySIArray3>,
+            ) -> PyResult<PyPoreProfile2D> {
+                let external_potential = external_potential.map(|e| e.to_owned_array());
+                Ok(PyPoreProfile2D(match initial_density {
+                    Some(density) => self.0.initialize_with_density(
+                        &bulk.0,
+                        external_potential.as_ref(),
+                        &density.into(),
+                    )?,
+                    None => self.0.initialize(&bulk.0, external_potential.as_ref())?,
+                }))
+            }
+        }
+
+        #[pymethods]
+        impl PyPoreProfile2D {
+            #[getter]
+            fn get_bulk_fugacity(&self) -> PySIArray1 {
+                self.0.bulk_fugacity().into()
+            }
+
+            #[getter]
+            fn get_grand_potential(&self) -> Option<PySINumber> {
+                self.0.grand_potential.map(PySINumber::from)
+            }
         }
 
         /// Parameters required to specify a 3D pore.
@@ -110,15 +642,43 @@ macro_rules! impl_pore {
         ///     The energy parameter of all interaction sites.
         /// potential_cutoff: float, optional
         ///     Maximum value for the external potential.
-        /// cutoff_radius: SINumber, optional
+        /// cutoff_radius: SINumber | float, optional
         ///     The cutoff radius for the calculation of solid-fluid interactions.
+        ///     A float is interpreted as a multiple of the largest solid-fluid
+        ///     diameter of every component.
+        /// strict_grid_spacing: bool, optional
+        ///     Raise an error instead of a warning for strongly anisotropic grids.
+        /// round_grid_to_fast_fft: bool, optional
+        ///     Increase the number of grid points to the next efficient FFT length.
+        /// periodic: [bool; 3], optional
+        ///     Whether the solid is periodic in each direction (default: all).
+        /// cell_vectors: SIArray2, optional
+        ///     The cell vectors (in the rows) of a triclinic unit cell.
+        ///     Replaces the system size.
+        /// quantum_correction : SINumber, optional
+        ///     The Feynman-Hibbs parameter hbar²/(24 m k_B) for light gases.
+        /// exp6_alpha : float, optional
+        ///     Use the exp-6 potential with the given steepness instead
+        ///     of the LJ 12-6 potential.
+        /// freeze_inaccessible : bool, optional
+        ///     Keep the density fixed at all grid points where the external
+        ///     potential reaches the potential cutoff.
+        /// elements : [str], optional
+        ///     The element of every interaction site, used to determine
+        ///     the mass of the solid.
+        /// vacuum_padding : [SINumber; 3], optional
+        ///     Width of the vacuum buffer added on both sides of every
+        ///     non-periodic direction.
+        /// wrap_coordinates : bool, optional
+        ///     Wrap all interaction sites into the unit cell in the
+        ///     periodic directions.
         ///
         /// Returns
         /// -------
         /// Pore3D
         ///
         #[pyclass(name = "Pore3D", unsendable)]
-        #[pyo3(text_signature = "(functional, system_size, n_grid, coordinates, sigma_ss, epsilon_k_ss, potential_cutoff=None, cutoff_radius=None)")]
+        #[pyo3(text_signature = "(functional, system_size, n_grid, coordinates, sigma_ss, epsilon_k_ss, potential_cutoff=None, cutoff_radius=None, strict_grid_spacing=None, round_grid_to_fast_fft=None, periodic=None, cell_vectors=None, quantum_correction=None, exp6_alpha=None, freeze_inaccessible=None, elements=None, vacuum_padding=None, wrap_coordinates=None)")]
         pub struct PyPore3D(Pore3D<SIUnit, $func>);
 
         #[pyclass(name = "PoreProfile3D", unsendable)]
@@ -137,9 +697,31 @@ macro_rules! impl_pore {
                 sigma_ss: &PyArray1<f64>,
                 epsilon_k_ss: &PyArray1<f64>,
                 potential_cutoff: Option<f64>,
-                cutoff_radius: Option<PySINumber>,
-            ) -> Self {
-                Self(Pore3D::new(
+                cutoff_radius: Option<&PyAny>,
+                strict_grid_spacing: Option<bool>,
+                round_grid_to_fast_fft: Option<bool>,
+                periodic: Option<[bool; 3]>,
+                cell_vectors: Option<PySIArray2>,
+                quantum_correction: Option<PySINumber>,
+                exp6_alpha: Option<f64>,
+                freeze_inaccessible: Option<bool>,
+                elements: Option<Vec<String>>,
+                vacuum_padding: Option<[PySINumber; 3]>,
+                wrap_coordinates: Option<bool>,
+            ) -> PyResult<Self> {
+                let cutoff_radius = match cutoff_radius {
+                    Some(c) => Some(if let Ok(c) = c.extract::<PySINumber>() {
+                        CutoffRadius::Fixed(c.into())
+                    } else if let Ok(c) = c.extract::<f64>() {
+                        CutoffRadius::Multiple(c)
+                    } else {
+                        return Err(PyErr::new::<PyValueError, _>(
+                            "`cutoff_radius` must be an SINumber or a float.".to_string(),
+                        ));
+                    }),
+                    None => None,
+                };
+                let mut pore = Pore3D::new(
                     &functional.0,
                     [system_size[0].into(), system_size[1].into(), system_size[2].into()],
                     n_grid,
@@ -147,8 +729,78 @@ macro_rules! impl_pore {
                     sigma_ss.to_owned_array(),
                     epsilon_k_ss.to_owned_array(),
                     potential_cutoff,
-                    cutoff_radius.map(|c| c.into()),
-                ))
+                    cutoff_radius,
+                )
+                .strict_grid_spacing(strict_grid_spacing.unwrap_or(false))
+                .round_grid_to_fast_fft(round_grid_to_fast_fft.unwrap_or(false))
+                .periodic(periodic.unwrap_or([true; 3]))
+                .freeze_inaccessible(freeze_inaccessible.unwrap_or(false))
+                .wrap_coordinates(wrap_coordinates.unwrap_or(false));
+                if let Some(cell_vectors) = cell_vectors {
+                    pore = pore.cell_vectors(cell_vectors.into());
+                }
+                if let Some(quantum_correction) = quantum_correction {
+                    pore = pore.quantum_correction(quantum_correction.into());
+                }
+                if let Some(alpha) = exp6_alpha {
+                    pore = pore.potential_form(PotentialForm::Exp6 { alpha });
+                }
+                if let Some(elements) = elements {
+                    let elements: Vec<_> = elements.iter().map(|e| e.as_str()).collect();
+                    pore = pore.elements(&elements)?;
+                }
+                if let Some(p) = vacuum_padding {
+                    pore = pore.vacuum_padding([p[0].into(), p[1].into(), p[2].into()]);
+                }
+                Ok(Self(pore))
+            }
+
+            /// Read the solid structure from a LAMMPS data file in `real` units.
+            ///
+            /// Parameters
+            /// ----------
+            /// functional : HelmholtzEnergyFunctional
+            ///     The Helmholtz energy functional.
+            /// path : str
+            ///     The path of the data file.
+            /// n_grid : [int; 3]
+            ///     The number of grid points in each direction.
+            /// potential_cutoff: float, optional
+            ///     Maximum value for the external potential.
+            /// cutoff_radius: SINumber | float, optional
+            ///     The cutoff radius for the calculation of solid-fluid interactions.
+            ///
+            /// Returns
+            /// -------
+            /// Pore3D
+            #[staticmethod]
+            #[pyo3(text_signature = "(functional, path, n_grid, potential_cutoff=None, cutoff_radius=None)")]
+            fn from_lammps_data(
+                functional: &$py_func,
+                path: String,
+                n_grid: [usize; 3],
+                potential_cutoff: Option<f64>,
+                cutoff_radius: Option<&PyAny>,
+            ) -> PyResult<Self> {
+                let cutoff_radius = match cutoff_radius {
+                    Some(c) => Some(if let Ok(c) = c.extract::<PySINumber>() {
+                        CutoffRadius::Fixed(c.into())
+                    } else if let Ok(c) = c.extract::<f64>() {
+                        CutoffRadius::Multiple(c)
+                    } else {
+                        return Err(PyErr::new::<PyValueError, _>(
+                            "`cutoff_radius` must be an SINumber or a float.".to_string(),
+                        ));
+                    }),
+                    None => None,
+                };
+                Ok(Self(Pore3D::from_lammps_data(
+                    &functional.0,
+                    path,
+                    n_grid,
+                    potential_cutoff,
+                    cutoff_radius,
+                )?))
             }
 
             /// Initialize the pore for the given bulk state.
@@ -161,34 +813,229 @@ macro_rules! impl_pore {
             ///     The external potential in the pore. Used to
             ///     save computation time in the case of costly
             ///     evaluations of external potentials.
+            /// initial_density : SIArray, optional
+            ///     An initial guess for the density profile.
             ///
             /// Returns
             /// -------
             /// PoreProfile3D
-            #[pyo3(text_signature = "($self, bulk, external_potential=None)")]
+            #[pyo3(text_signature = "($self, bulk, external_potential=None, initial_density=None)")]
             fn initialize(
                 &self,
                 bulk: &PyState,
                 external_potential: Option<&PyArray4<f64>>,
+                initial_density: Option<PySIArray4>,
             ) -> PyResult<PyPoreProfile3D> {
-                Ok(PyPoreProfile3D(self.0.initialize(
-                    &bulk.0,
-                    external_potential.map(|e| e.to_owned_array()).as_ref(),
-                )?))
+                let external_potential = external_potential.map(|e| e.to_owned_array());
+                Ok(PyPoreProfile3D(match initial_density {
+                    Some(density) => self.0.initialize_with_density(
+                        &bulk.0,
+                        external_potential.as_ref(),
+                        &density.into(),
+                    )?,
+                    None => self.0.initialize(&bulk.0, external_potential.as_ref())?,
+                }))
+            }
+
+            #[getter]
+            fn get_solid_mass(&self) -> PyResult<PySINumber> {
+                Ok(self.0.solid_mass()?.into())
+            }
+
+            /// Calculate the number of fluid molecules within the given
+            /// distance of any interaction site of the solid.
+            ///
+            /// Parameters
+            /// ----------
+            /// profile : PoreProfile3D
+            ///     The density profile in the pore.
+            /// shell_cutoff : SINumber
+            ///     The radius of the solvation shell.
+            ///
+            /// Returns
+            /// -------
+            /// SINumber
+            #[pyo3(text_signature = "($self, profile, shell_cutoff)")]
+            fn coordination_number(
+                &self,
+                profile: &PyPoreProfile3D,
+                shell_cutoff: PySINumber,
+            ) -> PyResult<PySINumber> {
+                Ok(self
+                    .0
+                    .coordination_number(&profile.0, shell_cutoff.into())?
+                    .into())
+            }
+
+            /// Calculate the contribution of every interaction site of the
+            /// solid to the solid-fluid interaction energy.
+            ///
+            /// Parameters
+            /// ----------
+            /// profile : PoreProfile3D
+            ///     The converged density profile in the pore.
+            ///
+            /// Returns
+            /// -------
+            /// SIArray1
+            #[pyo3(text_signature = "($self, profile)")]
+            fn energy_per_solid_atom(&self, profile: &PyPoreProfile3D) -> PyResult<PySIArray1> {
+                Ok(self.0.energy_per_solid_atom(&profile.0)?.into())
             }
         }
 
         #[pymethods]
         impl PyPoreProfile3D {
+            #[getter]
+            fn get_bulk_fugacity(&self) -> PySIArray1 {
+                self.0.bulk_fugacity().into()
+            }
+
             #[getter]
             fn get_grand_potential(&self) -> Option<PySINumber> {
                 self.0.grand_potential.map(PySINumber::from)
             }
 
+            #[getter]
+            fn get_association_fraction<'py>(
+                &self,
+                py: Python<'py>,
+            ) -> PyResult<Option<&'py PyArray4<f64>>> {
+                Ok(self
+                    .0
+                    .association_fraction()?
+                    .map(|x| x.view().to_pyarray(py)))
+            }
+
+            /// Solve the profile on a sequence of successively finer grids.
+            ///
+            /// Parameters
+            /// ----------
+            /// levels : int
+            ///     The number of grids including the original grid.
+            /// factor : int, optional
+            ///     The ratio of the numbers of grid points of successive grids (default: 2).
+            /// solver : DFTSolver, optional
+            ///     The solver used on all grids.
+            ///
+            /// Returns
+            /// -------
+            /// PoreProfile3D
+            #[args(factor = "2")]
+            #[pyo3(text_signature = "($self, levels, factor=2, solver=None)")]
+            fn solve_multigrid(
+                slf: &PyCell<Self>,
+                levels: usize,
+                factor: usize,
+                solver: Option<PyDFTSolver>,
+            ) -> PyResult<&PyCell<Self>> {
+                slf.borrow_mut()
+                    .0
+                    .solve_multigrid(levels, factor, solver.map(|s| s.0).as_ref())?;
+                Ok(slf)
+            }
+
+            /// Return the interfacial tension per given solid surface area.
+            ///
+            /// Parameters
+            /// ----------
+            /// surface_area : SINumber
+            ///     The surface area of the solid.
+            ///
+            /// Returns
+            /// -------
+            /// SINumber
+            #[pyo3(text_signature = "($self, surface_area)")]
+            fn interfacial_tension_per_area(&self, surface_area: PySINumber) -> PyResult<PySINumber> {
+                Ok(self
+                    .0
+                    .normalized_interfacial_tension(InterfacialTensionConvention::SurfaceArea(
+                        surface_area.into(),
+                    ))?
+                    .into())
+            }
+
+            #[getter]
+            fn get_accessible_mask<'py>(&self, py: Python<'py>) -> &'py PyArray3<bool> {
+                self.0.accessible_mask().view().to_pyarray(py)
+            }
+
+            /// Calculate the in-plane structure factor of the confined fluid.
+            ///
+            /// Parameters
+            /// ----------
+            /// plane_axis : int
+            ///     The axis normal to the plane.
+            ///
+            /// Returns
+            /// -------
+            /// (numpy.ndarray[float], numpy.ndarray[float])
+            ///     The wave numbers (in 1/Å) and the structure factor.
+            #[pyo3(text_signature = "($self, plane_axis)")]
+            fn structure_factor<'py>(
+                &self,
+                plane_axis: usize,
+                py: Python<'py>,
+            ) -> PyResult<(&'py PyArray1<f64>, &'py PyArray1<f64>)> {
+                let (q, s) = self.0.structure_factor(plane_axis)?;
+                Ok((q.view().to_pyarray(py), s.view().to_pyarray(py)))
+            }
+
+            /// Label the disconnected pores of the solid.
+            ///
+            /// Returns
+            /// -------
+            /// (numpy.ndarray[int], int)
+            ///     The labels of all grid points (0 for inaccessible points)
+            ///     and the number of pores.
+            fn pore_labels<'py>(&self, py: Python<'py>) -> (&'py PyArray3<usize>, usize) {
+                let (labels, n_pores) = self.0.pore_labels();
+                (labels.view().to_pyarray(py), n_pores)
+            }
+
+            /// Return the number of moles of every component in each pore.
+            ///
+            /// Returns
+            /// -------
+            /// SIArray2
+            fn loading_by_pore(&self) -> PySIArray2 {
+                self.0.loading_by_pore().into()
+            }
+
             #[getter]
             fn get_interfacial_tension(&self) -> Option<PySINumber> {
                 self.0.interfacial_tension.map(PySINumber::from)
             }
+
+            #[getter]
+            fn get_excess_grand_potential(&self) -> Option<PySINumber> {
+                self.0.excess_grand_potential().map(PySINumber::from)
+            }
+
+            /// Calculate the local compressibility, i.e., the derivative of the
+            /// reduced segment densities with respect to the reduced chemical
+            /// potential, by central finite differences.
+            ///
+            /// Parameters
+            /// ----------
+            /// solver : DFTSolver, optional
+            ///     The solver used for the perturbed profiles.
+            ///
+            /// Returns
+            /// -------
+            /// numpy.ndarray[float]
+            #[pyo3(text_signature = "($self, solver=None)")]
+            fn local_compressibility<'py>(
+                &self,
+                solver: Option<PyDFTSolver>,
+                py: Python<'py>,
+            ) -> PyResult<&'py PyArray4<f64>> {
+                Ok(self
+                    .0
+                    .local_compressibility(solver.map(|s| s.0).as_ref())?
+                    .view()
+                    .to_pyarray(py))
+            }
         }
     };
 }
@@ -113,6 +113,42 @@ macro_rules! impl_planar_interface {
             fn get_vle(&self) -> PyPhaseEquilibrium {
                 PyPhaseEquilibrium(self.0.vle.clone())
             }
+
+            /// Calculate the surface excess entropy per area of a pure component.
+            ///
+            /// Parameters
+            /// ----------
+            /// solver : DFTSolver, optional
+            ///     The solver used for the interfaces at shifted temperatures.
+            ///
+            /// Returns
+            /// -------
+            /// SINumber
+            #[pyo3(text_signature = "($self, solver=None)")]
+            fn surface_excess_entropy(&self, solver: Option<PyDFTSolver>) -> PyResult<PySINumber> {
+                Ok(self
+                    .0
+                    .surface_excess_entropy(solver.map(|s| s.0).as_ref())?
+                    .into())
+            }
+
+            /// Calculate the surface excess internal energy per area of a pure component.
+            ///
+            /// Parameters
+            /// ----------
+            /// solver : DFTSolver, optional
+            ///     The solver used for the interfaces at shifted temperatures.
+            ///
+            /// Returns
+            /// -------
+            /// SINumber
+            #[pyo3(text_signature = "($self, solver=None)")]
+            fn surface_excess_energy(&self, solver: Option<PyDFTSolver>) -> PyResult<PySINumber> {
+                Ok(self
+                    .0
+                    .surface_excess_energy(solver.map(|s| s.0).as_ref())?
+                    .into())
+            }
         }
     };
 }
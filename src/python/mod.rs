@@ -25,10 +25,13 @@ pub fn feos_dft(py: Python<'_>, m: &PyModule) -> PyResult<()> {
 
     m.add_class::<PyState>()?;
     m.add_class::<PyPore1D>()?;
+    m.add_class::<PyCurvedSurface1D>()?;
+    m.add_class::<PyPore2D>()?;
     m.add_class::<PyPore3D>()?;
     m.add_class::<PyPairCorrelation>()?;
     m.add_class::<PyExternalPotential>()?;
     m.add_class::<PyAdsorption1D>()?;
+    m.add_class::<PyAdsorption2D>()?;
     m.add_class::<PyAdsorption3D>()?;
 
     m.add_wrapped(wrap_pymodule!(quantity))?;
@@ -98,6 +98,20 @@ macro_rules! impl_profile {
                 Ok(n.into_iter().map(|n| n.view().to_pyarray(py)).collect())
             }
 
+            /// Return the weighted densities of every functional contribution
+            /// together with the name of the contribution.
+            ///
+            /// Returns
+            /// -------
+            /// [(str, numpy.ndarray)]
+            fn named_weighted_densities<'py>(
+                &self,
+                py: Python<'py>,
+            ) -> PyResult<Vec<(String, &'py $arr2<f64>)>> {
+                let n = self.0.profile.named_weighted_densities()?;
+                Ok(n.into_iter().map(|(name, n)| (name, n.view().to_pyarray(py))).collect())
+            }
+
             #[getter]
             fn get_functional_derivative<'py>(
                 &self,
@@ -106,6 +120,56 @@ macro_rules! impl_profile {
                 Ok(self.0.profile.functional_derivative()?.view().to_pyarray(py))
             }
 
+            /// Calculate the grand potential density of the inhomogeneous system.
+            ///
+            /// Returns
+            /// -------
+            /// SIArray
+            #[pyo3(text_signature = "($self)")]
+            fn grand_potential_density(&self) -> PyResult<$si_arr> {
+                Ok($si_arr::from(self.0.profile.grand_potential_density()?))
+            }
+
+            /// Calculate the local pressure (the negative grand potential density)
+            /// of the inhomogeneous system.
+            ///
+            /// Returns
+            /// -------
+            /// SIArray
+            #[pyo3(text_signature = "($self)")]
+            fn local_pressure(&self) -> PyResult<$si_arr> {
+                Ok($si_arr::from(self.0.profile.local_pressure()?))
+            }
+
+            /// Calculate the integration-weighted L2 norm of the density
+            /// difference to another profile, relative to the bulk density.
+            ///
+            /// Parameters
+            /// ----------
+            /// other: $struct
+            ///     The other profile, which is interpolated onto the grid
+            ///     of this profile.
+            ///
+            /// Returns
+            /// -------
+            /// float
+            #[pyo3(text_signature = "($self, other)")]
+            fn l2_difference(&self, other: &$struct) -> PyResult<f64> {
+                Ok(self.0.profile.l2_difference(&other.0.profile)?)
+            }
+
+            /// Restore the density profile and the chemical potential from
+            /// a checkpoint file written by the solver.
+            ///
+            /// Parameters
+            /// ----------
+            /// path: str
+            ///     The path of the checkpoint file.
+            #[pyo3(text_signature = "($self, path)")]
+            fn load_checkpoint(&mut self, path: String) -> PyResult<()> {
+                Ok(self.0.profile.load_checkpoint(path)?)
+            }
+
             /// Calculate the entropy density of the inhomogeneous system.
             ///
             /// Parameters
@@ -128,6 +192,33 @@ macro_rules! impl_profile {
                 ))
             }
 
+            /// Calculate the Helmholtz energy density of every contribution
+            /// of the functional.
+            ///
+            /// Parameters
+            /// ----------
+            /// contributions: Contributions, optional
+            ///     the contributions of the ideal chain term.
+            ///     Defaults to Contributions.Total.
+            ///
+            /// Returns
+            /// -------
+            /// [(str, SIArray)]
+            #[args(contributions = "PyContributions::Total()")]
+            #[pyo3(text_signature = "($self, contributions)")]
+            fn helmholtz_energy_density_contributions(
+                &self,
+                contributions: PyContributions,
+            ) -> PyResult<Vec<(String, $si_arr)>> {
+                Ok(self
+                    .0
+                    .profile
+                    .helmholtz_energy_density_contributions(contributions.0)?
+                    .into_iter()
+                    .map(|(name, f)| (name, $si_arr::from(f)))
+                    .collect())
+            }
+
             /// Calculate the entropy of the inhomogeneous system.
             ///
             /// Parameters
@@ -1,4 +1,6 @@
 use crate::DFTSolver;
+use numpy::PyArray1;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 
 /// Settings for the DFT solver.
@@ -39,6 +41,37 @@ impl PyDFTSolver {
         Self(DFTSolver::default())
     }
 
+    /// Solver preset for (vapor-liquid) interfaces.
+    ///
+    /// Returns
+    /// -------
+    /// DFTSolver
+    #[classattr]
+    fn default_interface() -> Self {
+        Self(DFTSolver::default_interface())
+    }
+
+    /// Solver preset for adsorption in pores.
+    ///
+    /// Returns
+    /// -------
+    /// DFTSolver
+    #[classattr]
+    fn default_pore() -> Self {
+        Self(DFTSolver::default_pore())
+    }
+
+    /// Slow but robust solver preset for hard cases, e.g.,
+    /// close to capillary condensation.
+    ///
+    /// Returns
+    /// -------
+    /// DFTSolver
+    #[classattr]
+    fn robust() -> Self {
+        Self(DFTSolver::robust())
+    }
+
     /// Add a picard iteration to the solver object.
     ///
     /// Parameters
@@ -127,6 +160,62 @@ impl PyDFTSolver {
         Self(solver)
     }
 
+    /// Set relative tolerances for the residuals of the individual components.
+    ///
+    /// Parameters
+    /// ----------
+    /// component_tol: numpy.ndarray[float]
+    ///     The tolerance for every component. The number of tolerances
+    ///     has to match the number of components of the system.
+    ///
+    /// Returns
+    /// -------
+    /// DFTSolver
+    #[pyo3(text_signature = "($self, component_tol)")]
+    fn component_tol(&self, component_tol: &PyArray1<f64>) -> PyResult<Self> {
+        let component_tol = component_tol.to_owned_array();
+        if component_tol.is_empty() || component_tol.iter().any(|&t| t.is_nan() || t <= 0.0) {
+            return Err(PyValueError::new_err(
+                "The component tolerances have to be positive, one for every component.",
+            ));
+        }
+        Ok(Self(self.0.clone().component_tol(component_tol)))
+    }
+
+    /// Write the current density profile to a file in regular intervals.
+    ///
+    /// Parameters
+    /// ----------
+    /// path: str
+    ///     The path of the checkpoint file.
+    /// every: int
+    ///     The number of residual evaluations between two checkpoints,
+    ///     counted over all algorithms of the solver.
+    ///
+    /// Returns
+    /// -------
+    /// DFTSolver
+    #[pyo3(text_signature = "($self, path, every)")]
+    fn checkpoint(&self, path: String, every: usize) -> Self {
+        Self(self.0.clone().checkpoint(path, every))
+    }
+
+    /// Precondition the residual by the inverse diagonal of the second
+    /// derivative of the functional estimated from the bulk.
+    ///
+    /// Parameters
+    /// ----------
+    /// preconditioner: bool
+    ///     Whether the residual is preconditioned.
+    ///
+    /// Returns
+    /// -------
+    /// DFTSolver
+    #[pyo3(text_signature = "($self, preconditioner)")]
+    fn preconditioner(&self, preconditioner: bool) -> Self {
+        Self(self.0.clone().preconditioner(preconditioner))
+    }
+
     fn _repr_markdown_(&self) -> String {
         self.0._repr_markdown_()
     }
@@ -3,7 +3,7 @@ use crate::adsorption::FluidParameters;
 use crate::convolver::ConvolverFFT;
 use crate::functional::{HelmholtzEnergyFunctional, DFT};
 use crate::geometry::{Axis, Grid};
-use crate::profile::{DFTProfile, CUTOFF_RADIUS, MAX_POTENTIAL};
+use crate::profile::{DFTProfile, CUTOFF_RADIUS, MAX_PAIR_POTENTIAL, MAX_POTENTIAL};
 use crate::solver::DFTSolver;
 use feos_core::{Contributions, EosResult, EosUnit, State};
 use ndarray::prelude::*;
@@ -194,12 +194,12 @@ fn evaluate(distance2: f64, sigma: f64, epsilon: f64, cutoff_radius2: f64) -> f6
     let potential: f64 = if distance2 > cutoff_radius2 {
         0.0
     } else if distance2 == 0.0 {
-        f64::INFINITY
+        MAX_PAIR_POTENTIAL
     } else {
         4.0 * epsilon * (sigma_r.powi(6) - sigma_r.powi(3))
     };
 
-    potential
+    potential.min(MAX_PAIR_POTENTIAL)
 }
 
 /// Evaluate the squared euclidian distance between a point and the coordinates of all solid atoms.
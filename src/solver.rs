@@ -1,8 +1,14 @@
 use feos_core::{EosError, EosResult};
+use log::debug;
 use ndarray::prelude::*;
 use ndarray_linalg::{Norm, SolveH};
 use std::collections::VecDeque;
 use std::fmt;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Duration;
 
 const DEFAULT_PARAMS_PICARD: SolverParameter = SolverParameter {
     solver: DFTAlgorithm::PicardIteration(1.0),
@@ -41,18 +47,86 @@ enum DFTAlgorithm {
     AndersonMixing(usize),
 }
 
+/// Observer that is notified about the progress of a [DFTSolver].
+///
+/// The iterations are counted over all algorithms of the solver.
+pub trait ProgressObserver {
+    /// Called after every iteration with the norm of the residual.
+    fn on_iteration(&self, iteration: usize, residual: f64);
+
+    /// Called after every iteration with the residuals of the individual
+    /// components (see [DFTSolver::component_tol]).
+    fn on_component_residuals(&self, _iteration: usize, _residuals: &Array1<f64>) {}
+
+    /// Called once the solver converged.
+    fn on_converged(&self, iteration: usize);
+
+    /// Called if the solver did not converge or the iteration failed.
+    fn on_failed(&self, iteration: usize, residual: f64);
+}
+
+/// Observer that prints the progress of the solver to the console.
+pub struct StdoutObserver;
+
+impl ProgressObserver for StdoutObserver {
+    fn on_iteration(&self, iteration: usize, residual: f64) {
+        println!("{:>4} | {:.6e}", iteration, residual);
+    }
+
+    fn on_converged(&self, iteration: usize) {
+        println!("converged in {} iterations", iteration);
+    }
+
+    fn on_failed(&self, iteration: usize, residual: f64) {
+        println!(
+            "not converged after {} iterations (residual: {:.6e})",
+            iteration, residual
+        );
+    }
+}
+
+/// Iteration counts and timings of a call to
+/// [DFTProfile::solve_with_stats](crate::DFTProfile::solve_with_stats).
+///
+/// A convolution is the calculation of all weighted densities or of the
+/// functional derivative from the partial derivatives of the functional.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SolverStats {
+    /// Number of iterations over all algorithms of the solver.
+    pub iterations: usize,
+    /// Number of evaluations of the residual.
+    pub residual_evaluations: usize,
+    /// Number of convolutions.
+    pub convolutions: usize,
+    /// Time spent in convolutions.
+    pub convolution_time: Duration,
+    /// Time spent in the residual apart from convolutions, mainly in the
+    /// evaluation of the functional.
+    pub functional_time: Duration,
+    /// Time spent outside of the residual, mainly in the mixing step.
+    pub mixing_time: Duration,
+    /// Total time of the calculation.
+    pub total_time: Duration,
+}
+
 /// Settings for the DFT solver.
 #[derive(Clone)]
 pub struct DFTSolver {
     parameters: Vec<SolverParameter>,
-    output: bool,
+    component_tol: Option<Array1<f64>>,
+    checkpoint: Option<(PathBuf, usize)>,
+    observer: Option<Rc<dyn ProgressObserver>>,
+    preconditioner: bool,
 }
 
 impl Default for DFTSolver {
     fn default() -> Self {
         Self {
             parameters: vec![DEFAULT_PARAMS_ANDERSON_LOG, DEFAULT_PARAMS_ANDERSON],
-            output: false,
+            component_tol: None,
+            checkpoint: None,
+            observer: None,
+            preconditioner: false,
         }
     }
 }
@@ -62,10 +136,65 @@ impl DFTSolver {
     pub fn new() -> Self {
         Self {
             parameters: Vec::new(),
-            output: false,
+            component_tol: None,
+            checkpoint: None,
+            observer: None,
+            preconditioner: false,
         }
     }
 
+    /// Solver preset for (vapor-liquid) interfaces.
+    ///
+    /// Interfaces are smooth, so Anderson mixing can be used from the start:
+    /// the logarithmic density is iterated until a tolerance of 1e-5 before
+    /// the profile is converged to 1e-11 without the logarithm.
+    pub fn default_interface() -> Self {
+        Self::new()
+            .anderson_mixing(None)
+            .log()
+            .max_iter(100)
+            .tol(1e-5)
+            .anderson_mixing(None)
+            .max_iter(150)
+    }
+
+    /// Solver preset for adsorption in pores.
+    ///
+    /// Pores show a strongly structured density close to the walls, for which
+    /// Anderson mixing can diverge in the first iterations. A damped Picard
+    /// iteration of the logarithmic density is therefore used until a tolerance
+    /// of 1e-5, before Anderson mixing converges the profile to 1e-11.
+    pub fn default_pore() -> Self {
+        Self::new()
+            .picard_iteration(None)
+            .log()
+            .max_iter(100)
+            .tol(1e-5)
+            .anderson_mixing(None)
+            .max_iter(300)
+    }
+
+    /// Solver preset for hard cases, e.g., close to capillary condensation.
+    ///
+    /// Uses small damping factors and high iteration limits. The preset is
+    /// considerably slower than the others but converges in more cases.
+    pub fn robust() -> Self {
+        Self::new()
+            .picard_iteration(None)
+            .log()
+            .max_iter(500)
+            .tol(1e-5)
+            .beta(0.05)
+            .anderson_mixing(Some(50))
+            .log()
+            .max_iter(500)
+            .tol(1e-8)
+            .beta(0.05)
+            .anderson_mixing(None)
+            .max_iter(500)
+            .beta(0.1)
+    }
+
     /// Add a Picard iteration to the solver.
     pub fn picard_iteration(mut self, max_rel: Option<f64>) -> Self {
         let mut algorithm = DEFAULT_PARAMS_PICARD;
@@ -111,44 +240,202 @@ impl DFTSolver {
     }
 
     /// Print the iteration to the console.
-    pub fn output(mut self) -> Self {
-        self.output = true;
+    ///
+    /// Shorthand for an [observer](Self::observer) of type [StdoutObserver].
+    pub fn output(self) -> Self {
+        self.observer(Rc::new(StdoutObserver))
+    }
+
+    /// Set relative tolerances for the residuals of the individual components.
+    ///
+    /// In addition to the tolerance of each solver, convergence then requires the
+    /// residual of every component, normalized by its bulk density, to fall below
+    /// the respective tolerance. Use this for mixtures with dilute components.
+    ///
+    /// The number of tolerances has to match the number of components of the
+    /// system, which is checked when the solver is used.
+    pub fn component_tol(mut self, component_tol: Array1<f64>) -> Self {
+        self.component_tol = Some(component_tol);
+        self
+    }
+
+    /// Write the current iterate to `path` every `every` residual evaluations.
+    ///
+    /// The counter includes every evaluation of the residual over all algorithms
+    /// of the solver, not only the iterations of a single algorithm.
+    /// The checkpoint is written to a temporary file first and then renamed,
+    /// so that an interrupted write does not corrupt an existing checkpoint.
+    /// Use [DFTProfile::load_checkpoint](crate::DFTProfile::load_checkpoint)
+    /// to resume the calculation.
+    pub fn checkpoint<P: AsRef<Path>>(mut self, path: P, every: usize) -> Self {
+        self.checkpoint = Some((path.as_ref().to_path_buf(), every.max(1)));
+        self
+    }
+
+    /// Notify the given observer about the progress of the solver.
+    ///
+    /// Use [StdoutObserver] for a compact console output.
+    pub fn observer(mut self, observer: Rc<dyn ProgressObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Precondition the residual by the inverse diagonal of the second
+    /// derivative of the functional, $\frac{1}{1+\rho c_{ii}^{(2)}}$,
+    /// estimated from the bulk phase.
+    ///
+    /// Rescales components with very different curvature, e.g., in
+    /// associating or chain fluids. Convergence is still assessed on the
+    /// unscaled residual.
+    pub fn preconditioner(mut self, preconditioner: bool) -> Self {
+        self.preconditioner = preconditioner;
         self
     }
 
-    pub(crate) fn solve<F>(&self, x: &mut Array1<f64>, residual: &mut F) -> EosResult<(bool, usize)>
+    pub(crate) fn solve<F, N, P>(
+        &self,
+        x: &mut Array1<f64>,
+        residual: &mut F,
+        n_components: usize,
+        component_residuals: N,
+        preconditioner: P,
+    ) -> EosResult<(bool, usize, f64)>
     where
         F: FnMut(&Array1<f64>, ArrayViewMut1<f64>, bool) -> EosResult<()>,
+        N: Fn(&Array1<f64>, bool) -> Array1<f64>,
+        P: FnOnce() -> EosResult<Array1<f64>>,
     {
-        if self.output {
-            println!("solver               | iter | residual ");
+        if let Some(component_tol) = &self.component_tol {
+            if component_tol.len() != n_components {
+                return Err(EosError::Error(format!(
+                    "{} component tolerances were provided for {} components.",
+                    component_tol.len(),
+                    n_components
+                )));
+            }
         }
+        let preconditioner = if self.preconditioner {
+            Some(preconditioner()?)
+        } else {
+            None
+        };
+        let components = |res: &Array1<f64>, log: bool| {
+            let residuals = component_residuals(res, log);
+            let converged = self.component_tol.as_ref().map_or(true, |tol| {
+                residuals.iter().zip(tol.iter()).all(|(r, t)| r < t)
+            });
+            (residuals, converged)
+        };
+        let mut evaluations = 0;
+        let mut residual = |x: &Array1<f64>, res: ArrayViewMut1<f64>, log: bool| -> EosResult<()> {
+            if let Some((path, every)) = &self.checkpoint {
+                evaluations += 1;
+                if evaluations % every == 0 {
+                    write_checkpoint(path, x)?;
+                }
+            }
+            residual(x, res, log)
+        };
+        let observer = self.observer.as_deref();
         let mut converged = false;
         let mut iterations = 0;
+        let mut res = f64::NAN;
         for algorithm in &self.parameters {
-            let (c, i) = algorithm.solve(x, residual, self.output)?;
+            let observe = |k: usize, r: f64, components: &Array1<f64>| {
+                debug!(
+                    "{:?} iteration {:>4} | {:.6e} | {}",
+                    algorithm.solver,
+                    iterations + k,
+                    r,
+                    format_components(components)
+                );
+                if let Some(observer) = observer {
+                    observer.on_iteration(iterations + k, r);
+                    observer.on_component_residuals(iterations + k, components);
+                }
+            };
+            let (c, i, r) = algorithm
+                .solve(
+                    x,
+                    &mut residual,
+                    &components,
+                    preconditioner.as_ref(),
+                    &observe,
+                )
+                .map_err(|e| {
+                    if let Some(observer) = observer {
+                        observer.on_failed(iterations, f64::NAN);
+                    }
+                    e
+                })?;
             converged = c;
             iterations += i;
+            res = r;
+        }
+        if let Some(observer) = observer {
+            if converged {
+                observer.on_converged(iterations);
+            } else {
+                observer.on_failed(iterations, res);
+            }
         }
-        Ok((converged, iterations))
+        Ok((converged, iterations, res))
     }
 }
 
+/// Format the residuals of the individual components for the console output.
+fn format_components(residuals: &Array1<f64>) -> String {
+    residuals
+        .iter()
+        .map(|r| format!("{:.2e}", r))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Write the solution vector to a checkpoint file.
+fn write_checkpoint(path: &Path, x: &Array1<f64>) -> EosResult<()> {
+    let tmp = path.with_extension("tmp");
+    let write = || -> std::io::Result<()> {
+        let mut file = BufWriter::new(fs::File::create(&tmp)?);
+        for xi in x.iter() {
+            writeln!(file, "{:?}", xi)?;
+        }
+        file.flush()?;
+        fs::rename(&tmp, path)
+    };
+    write().map_err(|e| EosError::Error(format!("Writing checkpoint failed: {}", e)))
+}
+
+/// Read the solution vector from a checkpoint file.
+pub(crate) fn read_checkpoint(path: &Path) -> EosResult<Array1<f64>> {
+    let error = |e: String| EosError::Error(format!("Reading checkpoint failed: {}", e));
+    fs::read_to_string(path)
+        .map_err(|e| error(e.to_string()))?
+        .lines()
+        .map(|l| l.trim().parse::<f64>().map_err(|e| error(e.to_string())))
+        .collect::<EosResult<Vec<_>>>()
+        .map(Array1::from_vec)
+}
+
 impl SolverParameter {
     fn solve<F>(
         &self,
         x: &mut Array1<f64>,
         residual: &mut F,
-        output: bool,
-    ) -> EosResult<(bool, usize)>
+        components: &dyn Fn(&Array1<f64>, bool) -> (Array1<f64>, bool),
+        preconditioner: Option<&Array1<f64>>,
+        observe: &dyn Fn(usize, f64, &Array1<f64>),
+    ) -> EosResult<(bool, usize, f64)>
     where
         F: FnMut(&Array1<f64>, ArrayViewMut1<f64>, bool) -> EosResult<()>,
     {
         match self.solver {
             DFTAlgorithm::PicardIteration(max_rel) => {
-                self.solve_picard(max_rel, x, residual, output)
+                self.solve_picard(max_rel, x, residual, components, preconditioner, observe)
+            }
+            DFTAlgorithm::AndersonMixing(mmax) => {
+                self.solve_anderson(mmax, x, residual, components, preconditioner, observe)
             }
-            DFTAlgorithm::AndersonMixing(mmax) => self.solve_anderson(mmax, x, residual, output),
         }
     }
 
@@ -157,19 +444,25 @@ impl SolverParameter {
         max_rel: f64,
         x: &mut Array1<f64>,
         residual: &mut F,
-        output: bool,
-    ) -> EosResult<(bool, usize)>
+        components: &dyn Fn(&Array1<f64>, bool) -> (Array1<f64>, bool),
+        preconditioner: Option<&Array1<f64>>,
+        observe: &dyn Fn(usize, f64, &Array1<f64>),
+    ) -> EosResult<(bool, usize, f64)>
     where
         F: FnMut(&Array1<f64>, ArrayViewMut1<f64>, bool) -> EosResult<()>,
     {
-        if output {
-            println!("{:-<43}", "");
-        }
         let mut resm = Array::zeros(x.raw_dim());
+        let mut last_res = f64::NAN;
 
         for k in 1..=self.max_iter {
             // calculate residual
             residual(x, resm.view_mut(), self.log)?;
+            let res = resm.norm() / (resm.len() as f64).sqrt();
+            let (res_components, components_converged) = components(&resm, self.log);
+            let converged = res < self.tol && components_converged;
+            if let Some(preconditioner) = preconditioner {
+                resm *= preconditioner;
+            }
 
             // calculate beta
             let mut beta_min: Option<f64> = None;
@@ -190,25 +483,17 @@ impl SolverParameter {
             }
 
             // check for convergence
-            let res = resm.norm() / (resm.len() as f64).sqrt();
-            if output {
-                println!(
-                    "Picard iteration {:3} | {:>4} | {:.6e} | {}",
-                    if self.log { "log" } else { "" },
-                    k,
-                    res,
-                    beta_min.unwrap_or(self.beta)
-                );
-            }
+            observe(k, res, &res_components);
 
             if res.is_nan() {
                 return Err(EosError::IterationFailed(String::from("Picard Iteration")));
             }
-            if res < self.tol && beta_min.is_none() {
-                return Ok((true, k));
+            if converged && beta_min.is_none() {
+                return Ok((true, k, res));
             }
+            last_res = res;
         }
-        Ok((false, self.max_iter))
+        Ok((false, self.max_iter, last_res))
     }
 
     fn solve_anderson<F>(
@@ -216,18 +501,18 @@ impl SolverParameter {
         mmax: usize,
         x: &mut Array1<f64>,
         residual: &mut F,
-        output: bool,
-    ) -> EosResult<(bool, usize)>
+        components: &dyn Fn(&Array1<f64>, bool) -> (Array1<f64>, bool),
+        preconditioner: Option<&Array1<f64>>,
+        observe: &dyn Fn(usize, f64, &Array1<f64>),
+    ) -> EosResult<(bool, usize, f64)>
     where
         F: FnMut(&Array1<f64>, ArrayViewMut1<f64>, bool) -> EosResult<()>,
     {
-        if output {
-            println!("{:-<43}", "");
-        }
         let mut resm = VecDeque::with_capacity(mmax);
         let mut xm = VecDeque::with_capacity(mmax);
         let mut r;
         let mut alpha;
+        let mut last_res = f64::NAN;
 
         for k in 1..=self.max_iter {
             // drop old values
@@ -240,6 +525,12 @@ impl SolverParameter {
             // calculate residual
             let mut res = Array::zeros(x.raw_dim());
             residual(x, res.view_mut(), self.log)?;
+            let res_norm = res.norm() / (res.len() as f64).sqrt();
+            let (res_components, components_converged) = components(&res, self.log);
+            let converged = res_norm < self.tol && components_converged;
+            if let Some(preconditioner) = preconditioner {
+                res *= preconditioner;
+            }
             resm.push_back(res);
 
             // save x value
@@ -271,25 +562,18 @@ impl SolverParameter {
             }
 
             // check for convergence
-            let resv = &resm[m - 1];
-            let res = resv.norm() / (resv.len() as f64).sqrt();
-            if output {
-                println!(
-                    "Anderson mixing {:3}  | {:>4} | {:.6e} ",
-                    if self.log { "log" } else { "" },
-                    k,
-                    res
-                );
-            }
+            let res = res_norm;
+            observe(k, res, &res_components);
 
             if res.is_nan() {
                 return Err(EosError::IterationFailed(String::from("Anderson Mixing")));
             }
-            if res < self.tol {
-                return Ok((true, k));
+            if converged {
+                return Ok((true, k, res));
             }
+            last_res = res;
         }
-        Ok((false, self.max_iter))
+        Ok((false, self.max_iter, last_res))
     }
 }
 
@@ -277,6 +277,15 @@ impl<T> WeightFunctionInfo<T> {
 }
 
 impl<T: DualNum<f64>> WeightFunctionInfo<T> {
+    /// Return the largest kernel radius of all weight functions.
+    pub fn max_kernel_radius(&self) -> f64 {
+        self.as_slice()
+            .iter()
+            .flat_map(|w| w.iter())
+            .flat_map(|w| w.kernel_radius.iter())
+            .fold(0.0, |acc, r| acc.max(r.re()))
+    }
+
     /// calculates the matrix of weight constants for this set of weighted densities
     pub fn weight_constants(&self, k: T, dimensions: usize) -> Array2<T> {
         let segments = self.component_index.len();